@@ -0,0 +1,505 @@
+//! cli.rs
+//!
+//! Command-line surface. Global flags override the config file (see `config.rs`).
+//!
+//! Every interactive prompt has a flag equivalent so the tool can run from
+//! scripts/CI/cron; the `inquire` menu in `terminal.rs` is only the fallback
+//! when no subcommand is given, and it dispatches through `run` as well.
+
+use crate::client::llm_client::ask_llm;
+use crate::config::{Config, ConfigOverrides};
+use crate::index::qdrant_schema::QdrantSchema;
+use crate::indexing;
+use crate::inference::{
+    EXTERNAL, callers, dependencies, lookup, preview_embedding, rag, routes, table_usages,
+    topic_usages,
+};
+use crate::watch;
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use inquire::Confirm;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[command(version, about = "Index microservice repos into Qdrant and query them")]
+pub struct Cli {
+    #[command(flatten)]
+    pub overrides: ConfigOverrides,
+
+    /// Runs the interactive menu when omitted.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum Command {
+    /// Index every repo (subdirectory) of a project into one collection.
+    Index {
+        /// Project root; its basename becomes the collection name.
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        /// Keep points of removed files/symbols instead of deleting them.
+        #[arg(long)]
+        no_prune: bool,
+    },
+    /// Keep the index up to date: file notifications plus a periodic full rescan.
+    Watch {
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        /// Override `indexing.rescan_interval_secs`.
+        #[arg(long)]
+        interval_secs: Option<u64>,
+        /// Keep points of removed files/symbols instead of deleting them.
+        #[arg(long)]
+        no_prune: bool,
+    },
+    /// Replace points written under an older point-id scheme, then re-index.
+    Migrate {
+        #[arg(default_value = ".")]
+        path: PathBuf,
+    },
+    /// Semantic search over a collection, answered by the LLM.
+    Query {
+        #[arg(long)]
+        collection: String,
+        /// Repo to restrict the search to (`*` for all repos).
+        #[arg(long, default_value = "*")]
+        repo: String,
+        /// Only symbols carrying this annotation/decorator, e.g. `GetMapping`
+        /// or `router.get`.
+        #[arg(long)]
+        annotation: Option<String>,
+        /// The question / search text.
+        text: String,
+    },
+    /// Exact lookup of a symbol by its fully qualified name.
+    Lookup {
+        #[arg(long)]
+        collection: String,
+        /// e.g. `auth_service::client::QdrantClient::new`
+        qualified_name: String,
+    },
+    /// Route inventory: every HTTP endpoint found in code and API specs.
+    Routes {
+        #[arg(long)]
+        collection: String,
+        /// Repo to list (`*` for all repos).
+        #[arg(long, default_value = "*")]
+        repo: String,
+    },
+    /// Services calling a route, from the HTTP call graph.
+    Callers {
+        #[arg(long)]
+        collection: String,
+        /// Repo exposing the route (`*` for any repo).
+        #[arg(long, default_value = "*")]
+        repo: String,
+        /// `METHOD /path`, e.g. `POST /orders`; a bare path matches every method.
+        route: String,
+    },
+    /// Services a repo calls over HTTP, and its calls to external APIs.
+    #[command(alias = "deps")]
+    Dependencies {
+        #[arg(long)]
+        collection: String,
+        repo: String,
+    },
+    /// Services consuming a Kafka topic / NATS subject (wildcards honoured).
+    Consumers {
+        #[arg(long)]
+        collection: String,
+        /// e.g. `order.created`
+        topic: String,
+    },
+    /// Services publishing to a Kafka topic / NATS subject.
+    Producers {
+        #[arg(long)]
+        collection: String,
+        topic: String,
+    },
+    /// Repos defining a database table, and the services reading / writing it.
+    Table {
+        #[arg(long)]
+        collection: String,
+        /// e.g. `payments.ledger_entries`; a bare name matches any schema.
+        table: String,
+    },
+    /// Show the text embedded for a symbol, rendered with the current (or
+    /// given) embedding templates.
+    Preview {
+        #[arg(long)]
+        collection: String,
+        qualified_name: String,
+        /// Templates to try instead of `embedder.template_dir`.
+        #[arg(long)]
+        template_dir: Option<PathBuf>,
+    },
+    /// Send a prompt straight to the LLM (no retrieval).
+    Ask { prompt: String },
+    /// List Qdrant collections.
+    Collections,
+    /// Delete a collection.
+    Drop {
+        #[arg(long)]
+        collection: String,
+        /// Skip the confirmation prompt.
+        #[arg(long, short)]
+        yes: bool,
+    },
+}
+
+pub async fn run(config: &Config, command: Command) -> Result<()> {
+    match command {
+        Command::Index { path, no_prune } => {
+            let mut config = config.clone();
+            if no_prune {
+                config.indexing.prune = false;
+            }
+            indexing::index(&config, &path).await?;
+        }
+        Command::Watch {
+            path,
+            interval_secs,
+            no_prune,
+        } => {
+            let mut config = config.clone();
+            if let Some(secs) = interval_secs {
+                config.indexing.rescan_interval_secs = secs;
+            }
+            if no_prune {
+                config.indexing.prune = false;
+            }
+            watch::watch(&config, &path).await?;
+        }
+        Command::Migrate { path } => {
+            indexing::migrate(config, &path).await?;
+        }
+        Command::Query {
+            collection,
+            repo,
+            annotation,
+            text,
+        } => {
+            let docs = rag(config, &text, &collection, &repo, annotation.as_deref()).await?;
+            println!();
+            for d in docs {
+                println!("[{:.3}] {} {}", d.score, d.id, d.payload);
+            }
+        }
+        Command::Lookup {
+            collection,
+            qualified_name,
+        } => {
+            let points = lookup(config, &collection, &qualified_name).await?;
+            if points.is_empty() {
+                eprintln!("[lookup] no symbol named '{qualified_name}'");
+            }
+            for p in points {
+                println!("{} {}", p.id, p.payload);
+            }
+        }
+        Command::Routes { collection, repo } => {
+            for p in routes(config, &collection, &repo).await? {
+                let field = |k: &str| p.payload["metadata"][k].as_str().unwrap_or("-").to_string();
+                println!(
+                    "{:<16} {:<7} {:<40} {:<32} {}:{}",
+                    p.payload["repo"].as_str().unwrap_or_default(),
+                    field("method"),
+                    field("path"),
+                    field("handler"),
+                    p.payload["file_path"].as_str().unwrap_or_default(),
+                    p.payload["line_start"],
+                );
+            }
+        }
+        Command::Callers {
+            collection,
+            repo,
+            route,
+        } => {
+            let points = callers(config, &collection, &route, &repo).await?;
+            if points.is_empty() {
+                eprintln!("[callers] no calls to '{route}'");
+            }
+            for p in points {
+                print_call(&p.payload);
+            }
+        }
+        Command::Dependencies { collection, repo } => {
+            let groups = dependencies(config, &collection, &repo).await?;
+            if groups.is_empty() {
+                eprintln!("[dependencies] no http calls in '{repo}'");
+            }
+            for (target, points) in groups {
+                println!("{target}");
+                for p in points {
+                    let routes: Vec<&str> = p.payload["call_targets"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter(|t| t["repo"] == target.as_str() || target == EXTERNAL)
+                        .filter_map(|t| t["route"].as_str())
+                        .collect();
+                    print!("  ");
+                    print_call(&p.payload);
+                    if !routes.is_empty() {
+                        println!("      -> {}", routes.join(", "));
+                    }
+                }
+            }
+        }
+        Command::Consumers { collection, topic } => {
+            print_topic_usages(config, &collection, &topic, "subscribe").await?;
+        }
+        Command::Producers { collection, topic } => {
+            print_topic_usages(config, &collection, &topic, "publish").await?;
+        }
+        Command::Table { collection, table } => {
+            print_table_usages(config, &collection, &table).await?;
+        }
+        Command::Preview {
+            collection,
+            qualified_name,
+            template_dir,
+        } => {
+            let previews = preview_embedding(
+                config,
+                &collection,
+                &qualified_name,
+                template_dir.as_deref(),
+            )
+            .await?;
+            if previews.is_empty() {
+                eprintln!("[preview] no symbol named '{qualified_name}'");
+            }
+            for p in previews {
+                println!(
+                    "=== {} {}:{} (template: {})",
+                    p.point.id,
+                    p.point.payload["file_path"].as_str().unwrap_or_default(),
+                    p.point.payload["line_start"],
+                    p.template,
+                );
+                println!("{}\n", p.input);
+            }
+        }
+        Command::Ask { prompt } => {
+            ask_llm(
+                &config.llm.url,
+                &config.llm.api_key,
+                &config.llm.model,
+                &prompt,
+            )
+            .await;
+        }
+        Command::Collections => {
+            let schema = QdrantSchema::new(&config.qdrant.url)?
+                .with_timeout_secs(config.qdrant.timeout_secs)?;
+            for name in schema.list_collections().await? {
+                println!("{name}");
+            }
+        }
+        Command::Drop { collection, yes } => {
+            let confirmed = yes
+                || Confirm::new(&format!("Delete collection '{collection}'?"))
+                    .with_default(false)
+                    .prompt()?;
+            if !confirmed {
+                eprintln!("[drop] aborted");
+                return Ok(());
+            }
+            let schema = QdrantSchema::new(&config.qdrant.url)?
+                .with_timeout_secs(config.qdrant.timeout_secs)?;
+            if schema.delete_collection(&collection).await? {
+                eprintln!("[drop] deleted '{collection}'");
+            } else {
+                eprintln!("[drop] collection '{collection}' not found");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// One `http_call` point: repo, caller, method + URL template, location.
+fn print_call(p: &serde_json::Value) {
+    let field = |k: &str| p["metadata"][k].as_str().unwrap_or("-").to_string();
+    println!(
+        "{:<16} {:<24} {:<7} {:<48} {}:{}",
+        p["repo"].as_str().unwrap_or_default(),
+        field("caller"),
+        field("method"),
+        field("url"),
+        p["file_path"].as_str().unwrap_or_default(),
+        p["line_start"],
+    );
+}
+
+/// One line per usage: repo, topic, broker, group, caller, message type, location.
+async fn print_topic_usages(
+    config: &Config,
+    collection: &str,
+    topic: &str,
+    role: &str,
+) -> Result<()> {
+    let points = topic_usages(config, collection, topic, role).await?;
+    if points.is_empty() {
+        eprintln!("[topics] no {role} usages of '{topic}'");
+    }
+    for p in points {
+        let field = |k: &str| p.payload["metadata"][k].as_str().unwrap_or("-").to_string();
+        println!(
+            "{:<16} {:<28} {:<6} {:<20} {:<24} {:<20} {}:{}",
+            p.payload["repo"].as_str().unwrap_or_default(),
+            field("topic"),
+            field("broker"),
+            field("group"),
+            field("caller"),
+            field("message_type"),
+            p.payload["file_path"].as_str().unwrap_or_default(),
+            p.payload["line_start"],
+        );
+    }
+    Ok(())
+}
+
+/// Definitions (owner repo, source, location) then one line per query:
+/// repo, operation, tables, caller, location.
+async fn print_table_usages(config: &Config, collection: &str, table: &str) -> Result<()> {
+    let points = table_usages(config, collection, table).await?;
+    if points.is_empty() {
+        eprintln!("[table] no definitions or queries of '{table}'");
+    }
+    for p in points {
+        let field = |k: &str| p.payload["metadata"][k].as_str().unwrap_or("-").to_string();
+        let location = format!(
+            "{}:{}",
+            p.payload["file_path"].as_str().unwrap_or_default(),
+            p.payload["line_start"],
+        );
+        if p.payload["type"] == "table" {
+            let name = match p.payload["metadata"]["schema"].as_str() {
+                Some(schema) => format!("{schema}.{}", field("table")),
+                None => field("table"),
+            };
+            println!(
+                "{:<16} {:<7} {:<40} {:<24} {}",
+                p.payload["repo"].as_str().unwrap_or_default(),
+                field("operation"),
+                name,
+                field("source"),
+                location,
+            );
+            continue;
+        }
+        let tables: Vec<&str> = p.payload["metadata"]["tables"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|t| t.as_str())
+            .collect();
+        println!(
+            "{:<16} {:<7} {:<40} {:<24} {}",
+            p.payload["repo"].as_str().unwrap_or_default(),
+            field("operation"),
+            tables.join(", "),
+            field("caller"),
+            location,
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_subcommand_means_interactive() {
+        let cli = Cli::try_parse_from(["microservices-rag"]).unwrap();
+        assert!(cli.command.is_none());
+    }
+
+    #[test]
+    fn query_flags_and_defaults() {
+        let cli = Cli::try_parse_from([
+            "microservices-rag",
+            "--qdrant-url",
+            "http://q:6333",
+            "query",
+            "--collection",
+            "shop",
+            "who publishes order.created?",
+        ])
+        .unwrap();
+        assert_eq!(cli.overrides.qdrant_url.as_deref(), Some("http://q:6333"));
+        match cli.command {
+            Some(Command::Query {
+                collection,
+                repo,
+                annotation,
+                text,
+            }) => {
+                assert_eq!(collection, "shop");
+                assert_eq!(repo, "*");
+                assert_eq!(annotation, None);
+                assert_eq!(text, "who publishes order.created?");
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn lookup_takes_qualified_name() {
+        let cli = Cli::try_parse_from([
+            "microservices-rag",
+            "lookup",
+            "--collection",
+            "shop",
+            "auth_service::client::QdrantClient::new",
+        ])
+        .unwrap();
+        match cli.command {
+            Some(Command::Lookup { qualified_name, .. }) => {
+                assert_eq!(qualified_name, "auth_service::client::QdrantClient::new");
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn callers_and_deps_alias() {
+        let cli = Cli::try_parse_from([
+            "microservices-rag",
+            "callers",
+            "--collection",
+            "shop",
+            "POST /orders",
+        ])
+        .unwrap();
+        match cli.command {
+            Some(Command::Callers { repo, route, .. }) => {
+                assert_eq!(repo, "*");
+                assert_eq!(route, "POST /orders");
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "microservices-rag",
+            "deps",
+            "--collection",
+            "shop",
+            "billing",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Dependencies { repo, .. }) if repo == "billing"
+        ));
+    }
+
+    #[test]
+    fn missing_required_flag_is_an_error_not_a_panic() {
+        assert!(Cli::try_parse_from(["microservices-rag", "query", "text"]).is_err());
+    }
+}
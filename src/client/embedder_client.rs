@@ -4,8 +4,7 @@
 //! (e.g., LM Studio running locally). Provides single and batch embedding.
 //!
 //! Assumptions:
-//! - Server endpoint is hardcoded to localhost (LM Studio style).
-//! - Model name is configurable.
+//! - Server endpoint and model name come from `Config` (LM Studio by default).
 //! - Single-threaded; no parallelization.
 //! - One embedding per document (no chunking).
 //!
@@ -15,7 +14,7 @@
 //!
 //! #[tokio::main]
 //! async fn main() -> anyhow::Result<()> {
-//!   let client = EmbedderClient::new("text-embedding-3-small", None)?;
+//!   let client = EmbedderClient::new("http://localhost:1234/v1", "text-embedding-3-small", None)?;
 //!   let vec = client.embed_text("hello world").await?;
 //!   println!("dim = {}", vec.len());
 //!
@@ -29,8 +28,6 @@ use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-/// Default base URL for LM Studio (OpenAI-compatible) embeddings API.
-const DEFAULT_EMBED_BASE_URL: &str = "http://localhost:1234/v1";
 /// Default per-request timeout.
 const DEFAULT_TIMEOUT_SECS: u64 = 60;
 
@@ -69,17 +66,21 @@ pub enum EmbedError {
 impl EmbedderClient {
     /// Create a new client.
     ///
-    /// - `model`: embedding model name (configurable).
+    /// - `base_url`: API base URL without the `/embeddings` suffix.
+    /// - `model`: embedding model name.
     /// - `expected_dim`: if Some(d), will validate that returned vectors have length `d`.
-    /// - Endpoint is hardcoded to `DEFAULT_EMBED_BASE_URL` as requested.
-    pub fn new<S: Into<String>>(model: S, expected_dim: Option<usize>) -> Result<Self, EmbedError> {
+    pub fn new<U: Into<String>, S: Into<String>>(
+        base_url: U,
+        model: S,
+        expected_dim: Option<usize>,
+    ) -> Result<Self, EmbedError> {
         let http = reqwest::Client::builder()
             .timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
             .build()?;
 
         Ok(Self {
             http,
-            base_url: DEFAULT_EMBED_BASE_URL.to_string(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
             model: model.into(),
             expected_dim,
         })
@@ -103,13 +104,13 @@ impl EmbedderClient {
         let mut out = self.embed_texts_raw(&[text]).await?;
         let first = out.pop().ok_or(EmbedError::EmptyResponse)?.embedding;
 
-        if let Some(expected) = self.expected_dim {
-            if first.len() != expected {
-                return Err(EmbedError::DimMismatch {
-                    expected,
-                    got: first.len(),
-                });
-            }
+        if let Some(expected) = self.expected_dim
+            && first.len() != expected
+        {
+            return Err(EmbedError::DimMismatch {
+                expected,
+                got: first.len(),
+            });
         }
         Ok(first)
     }
@@ -147,13 +148,13 @@ impl EmbedderClient {
 
         let mut result = Vec::with_capacity(n);
         for (_, emb) in pairs {
-            if let Some(expected) = self.expected_dim {
-                if emb.len() != expected {
-                    return Err(EmbedError::DimMismatch {
-                        expected,
-                        got: emb.len(),
-                    });
-                }
+            if let Some(expected) = self.expected_dim
+                && emb.len() != expected
+            {
+                return Err(EmbedError::DimMismatch {
+                    expected,
+                    got: emb.len(),
+                });
             }
             result.push(emb);
         }
@@ -206,7 +207,6 @@ struct EmbeddingsRequest {
 #[derive(Debug, Serialize)]
 #[serde(untagged)]
 enum EmbeddingInput {
-    Single { input: String },
     Array(Vec<String>),
 }
 
@@ -24,7 +24,7 @@ pub struct Choice {
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-struct DeltaContent {
+pub struct DeltaContent {
     pub content: String,
 }
 
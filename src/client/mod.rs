@@ -1,3 +1,3 @@
 pub(crate) mod embedder_client;
+pub(crate) mod llm_client;
 pub(crate) mod qdrant_client;
-pub(crate) mod llm_client;
\ No newline at end of file
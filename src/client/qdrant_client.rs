@@ -1,12 +1,16 @@
 //! qdrant_client.rs
 //!
-//! Lightweight Qdrant client for upserting points in batches with retries.
+//! Lightweight Qdrant client for upserting points in batches with retries,
+//! plus payload-only scrolls/updates used by incremental indexing.
 //! - Assumes a *single* vector per point (simple collection schema).
 //! - Uses string UUID point IDs generated by id_generator.
 //! - Payload is any arbitrary data.
 //!
 //! Notes:
 //! - Upsert endpoint: POST /collections/{name}/points?wait=true
+//! - Scroll endpoint: POST /collections/{name}/points/scroll (paged by `next_page_offset`)
+//! - Payload updates: POST /collections/{name}/points/batch with `set_payload` operations
+//! - Deletes: POST /collections/{name}/points/delete
 //! - Batch & retry: simple exponential backoff.
 
 use std::{thread, time::Duration};
@@ -16,6 +20,12 @@ use serde::{Deserialize, Serialize};
 use serde_json::{Value as JsonValue, json};
 use thiserror::Error;
 
+/// Default per-request timeout.
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+
+/// Points per scroll request.
+const SCROLL_PAGE_SIZE: usize = 256;
+
 #[derive(Error, Debug)]
 pub enum QdrantError {
     #[error("http: {0}")]
@@ -35,20 +45,132 @@ pub struct QdrantClient {
 }
 
 impl QdrantClient {
-    pub fn new() -> Result<Self, QdrantError> {
+    pub fn new<S: Into<String>>(base_url: S) -> Result<Self, QdrantError> {
         let http = reqwest::Client::builder()
-            .timeout(Duration::from_secs(60))
+            .timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
             .build()?;
         Ok(Self {
             http,
-            base_url: "http://localhost:6333".into(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
         })
     }
 
+    /// Override the timeout
+    pub fn with_timeout_secs(mut self, secs: u64) -> Result<Self, QdrantError> {
+        self.http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(secs))
+            .build()?;
+        Ok(self)
+    }
+
     fn upsert_url(&self, collection: &str) -> String {
         format!("{}/collections/{}/points", self.base_url, collection)
     }
 
+    fn points_url(&self, collection: &str, op: &str) -> String {
+        format!("{}/collections/{}/points/{}", self.base_url, collection, op)
+    }
+
+    /// Scroll through all points matching `filter`, returning only the requested
+    /// payload fields (no vectors).
+    ///
+    /// - `filter`: a Qdrant filter object, e.g. `{"must": [{"key": "repo", "match": {"value": "x"}}]}`
+    /// - `fields`: payload keys to return
+    pub async fn scroll_points(
+        &self,
+        collection: &str,
+        filter: JsonValue,
+        fields: &[&str],
+    ) -> Result<Vec<ScrolledPoint>, QdrantError> {
+        let url = self.points_url(collection, "scroll");
+        let mut out = Vec::new();
+        let mut offset: Option<JsonValue> = None;
+
+        loop {
+            let req = ScrollRequest {
+                filter: &filter,
+                limit: SCROLL_PAGE_SIZE,
+                offset: offset.take(),
+                with_payload: fields,
+                with_vector: false,
+            };
+            let resp = self.http.post(&url).json(&req).send().await?;
+            if !resp.status().is_success() {
+                let status = resp.status();
+                let body = resp.text().await.unwrap_or_default();
+                return Err(QdrantError::Status { status, body });
+            }
+
+            let page: ScrollResponse = resp.json().await?;
+            out.extend(page.result.points.into_iter().map(|p| ScrolledPoint {
+                id: point_id_string(p.id),
+                payload: p.payload.unwrap_or(JsonValue::Null),
+            }));
+            match page.result.next_page_offset {
+                Some(next) if !next.is_null() => offset = Some(next),
+                _ => break,
+            }
+        }
+        Ok(out)
+    }
+
+    /// Delete points by id.
+    pub async fn delete_points(&self, collection: &str, ids: &[String]) -> Result<(), QdrantError> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let url = self.points_url(collection, "delete");
+        let resp = self
+            .http
+            .post(format!("{url}?wait=true"))
+            .json(&json!({ "points": ids }))
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(QdrantError::Status { status, body });
+        }
+        Ok(())
+    }
+
+    /// Overwrite selected payload keys of existing points without touching
+    /// their vectors. Sent as `set_payload` operations, `batch_size` per request.
+    pub async fn set_payloads(
+        &self,
+        collection: &str,
+        updates: Vec<(String, JsonValue)>,
+        batch_size: usize,
+    ) -> Result<(), QdrantError> {
+        let url = self.points_url(collection, "batch");
+        for chunk in updates.chunks(batch_size.max(1)) {
+            let operations: Vec<JsonValue> = chunk
+                .iter()
+                .map(|(id, payload)| {
+                    json!({
+                        "set_payload": {
+                            "payload": payload,
+                            "points": [id],
+                        }
+                    })
+                })
+                .collect();
+
+            let resp = self
+                .http
+                .post(format!("{url}?wait=true"))
+                .json(&json!({ "operations": operations }))
+                .send()
+                .await?;
+            if !resp.status().is_success() {
+                let status = resp.status();
+                let body = resp.text().await.unwrap_or_default();
+                return Err(QdrantError::Status { status, body });
+            }
+        }
+        Ok(())
+    }
+
     /// Upsert a batch of points, with simple retry/backoff per batch.
     ///
     /// - `batch_size`: how many points to send per request (e.g., 64/128).
@@ -87,10 +209,10 @@ impl QdrantClient {
                 Ok(_) => return Ok(()),
                 Err(e) => {
                     // For 4xx (client) errors, retries likely won't help.
-                    if let QdrantError::Status { status, .. } = &e {
-                        if status.is_client_error() {
-                            return Err(e);
-                        }
+                    if let QdrantError::Status { status, .. } = &e
+                        && status.is_client_error()
+                    {
+                        return Err(e);
                     }
                     attempt += 1;
                     if attempt > max_retries {
@@ -103,27 +225,6 @@ impl QdrantClient {
         }
     }
 
-    pub async fn create_collection(&self, collection_name: &str) {
-        let url = format!("http://localhost:6333/collections/{collection_name}");
-
-        let request_body = json!({
-          "vectors": {
-            "size": 768,
-            "distance": "Cosine"
-          }
-        });
-
-        let response = self.http.put(url).json(&request_body).send().await.unwrap();
-
-        if response.status().is_success() {
-            let body: serde_json::Value = response.json().await.unwrap();
-            println!("Success: {:?}", body);
-        } else {
-            let error_body = response.text().await.unwrap();
-            eprintln!("Error: {:?}", error_body);
-        }
-    }
-
     async fn upsert_once(&self, collection: &str, batch: &[PointWrite]) -> Result<(), QdrantError> {
         let url = self.upsert_url(collection);
         let req = UpsertPointsRequest {
@@ -175,10 +276,51 @@ struct UpsertResponse {
     // time, result, etc. omitted
 }
 
+/// A point returned by `scroll_points` (payload subset, no vector).
+#[derive(Debug, Clone)]
+pub struct ScrolledPoint {
+    pub id: String,
+    pub payload: JsonValue,
+}
+
+#[derive(Debug, Serialize)]
+struct ScrollRequest<'a> {
+    filter: &'a JsonValue,
+    limit: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<JsonValue>,
+    with_payload: &'a [&'a str],
+    with_vector: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ScrollResponse {
+    result: ScrollResult,
+}
+
+#[derive(Debug, Deserialize)]
+struct ScrollResult {
+    points: Vec<ScrollPointRead>,
+    next_page_offset: Option<JsonValue>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ScrollPointRead {
+    id: JsonValue,
+    payload: Option<JsonValue>,
+}
+
+/// Qdrant ids are either UUID strings or unsigned integers.
+fn point_id_string(id: JsonValue) -> String {
+    match id {
+        JsonValue::String(s) => s,
+        other => other.to_string(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_json::json;
 
     #[test]
     fn can_serialize_upsert_body() {
@@ -199,4 +341,25 @@ mod tests {
         assert!(s.contains("\"points\""));
         assert!(s.contains("\"vector\""));
     }
+
+    #[test]
+    fn scroll_request_omits_missing_offset() {
+        let filter = json!({"must": [{"key": "repo", "match": {"value": "svc_auth"}}]});
+        let req = ScrollRequest {
+            filter: &filter,
+            limit: 10,
+            offset: None,
+            with_payload: &["hash_source"],
+            with_vector: false,
+        };
+        let v = serde_json::to_value(&req).unwrap();
+        assert!(v.get("offset").is_none());
+        assert_eq!(v["with_payload"], json!(["hash_source"]));
+    }
+
+    #[test]
+    fn point_ids_are_stringified() {
+        assert_eq!(point_id_string(json!("abc")), "abc");
+        assert_eq!(point_id_string(json!(42)), "42");
+    }
 }
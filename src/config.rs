@@ -0,0 +1,391 @@
+//! config.rs
+//!
+//! Layered runtime configuration shared by indexing, inference and the clients.
+//!
+//! Precedence (lowest → highest):
+//! 1. built-in defaults (local Qdrant + LM Studio)
+//! 2. TOML file (`--config`, `MSRAG_CONFIG`, or `./microservices-rag.toml` if present)
+//! 3. environment variables (`MSRAG_*`)
+//! 4. CLI flags
+//!
+//! Env vars and CLI flags share one `ConfigOverrides` struct (clap reads both),
+//! so CLI wins over env automatically.
+//!
+//! Example file:
+//! ```toml
+//! [qdrant]
+//! url = "http://qdrant.internal:6333"
+//! timeout_secs = 30
+//!
+//! [embedder]
+//! base_url = "http://localhost:1234/v1"
+//! model = "text-embedding-embeddinggemma-300m"
+//! vector_size = 768
+//! template_dir = "embedding-templates"
+//!
+//! [llm]
+//! url = "http://localhost:1234/v1/chat/completions"
+//! model = "qwen/qwen3-coder-30b"
+//!
+//! [indexing]
+//! embed_batch = 32
+//! max_chunk_chars = 6000
+//! ```
+
+use clap::Args;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// File picked up from the working directory when no explicit path is given.
+pub const DEFAULT_CONFIG_FILE: &str = "microservices-rag.toml";
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("failed to read config {path}: {source}")]
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("invalid config {path}: {source}")]
+    Toml {
+        path: String,
+        source: toml::de::Error,
+    },
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub qdrant: QdrantConfig,
+    pub embedder: EmbedderConfig,
+    pub llm: LlmConfig,
+    pub indexing: IndexingConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct QdrantConfig {
+    /// Base URL of the Qdrant REST API.
+    pub url: String,
+    pub timeout_secs: u64,
+}
+
+impl Default for QdrantConfig {
+    fn default() -> Self {
+        Self {
+            url: "http://localhost:6333".into(),
+            timeout_secs: 60,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EmbedderConfig {
+    /// Base URL of the OpenAI-compatible API (without `/embeddings`).
+    pub base_url: String,
+    pub model: String,
+    /// Must match the model output and the Qdrant collection.
+    pub vector_size: usize,
+    pub timeout_secs: u64,
+    /// Directory of embedding input templates (see
+    /// `transform::embedding_template`); the built-in one when unset.
+    pub template_dir: Option<PathBuf>,
+}
+
+impl Default for EmbedderConfig {
+    fn default() -> Self {
+        Self {
+            base_url: "http://localhost:1234/v1".into(),
+            model: "text-embedding-embeddinggemma-300m".into(),
+            vector_size: 768,
+            timeout_secs: 60,
+            template_dir: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LlmConfig {
+    /// Full chat completions URL.
+    pub url: String,
+    pub api_key: String,
+    pub model: String,
+}
+
+impl Default for LlmConfig {
+    fn default() -> Self {
+        Self {
+            url: "http://localhost:1234/v1/chat/completions".into(),
+            // LM Studio accepts any key; this is its conventional placeholder.
+            api_key: "lm-studio".into(),
+            model: "qwen/qwen3-coder-30b".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IndexingConfig {
+    /// One summary document per file (imports, symbol signatures).
+    pub include_filename_doc: bool,
+    pub embed_batch: usize,
+    pub upsert_batch: usize,
+    pub upsert_retries: usize,
+    /// Delete points whose file/symbol no longer exists after each repo pass.
+    pub prune: bool,
+    /// Full rescan period in `watch` mode.
+    pub rescan_interval_secs: u64,
+    /// How long `watch` waits for a burst of file events to settle.
+    pub watch_debounce_ms: u64,
+    /// Documents with more code than this are split into chunks.
+    pub max_chunk_chars: usize,
+    /// Lines each chunk repeats from the end of the previous one.
+    pub chunk_overlap_lines: usize,
+}
+
+impl Default for IndexingConfig {
+    fn default() -> Self {
+        Self {
+            include_filename_doc: true,
+            embed_batch: 64,
+            upsert_batch: 64,
+            upsert_retries: 3,
+            prune: true,
+            rescan_interval_secs: 600, // 10 minutes (safe default)
+            watch_debounce_ms: 1_000,
+            max_chunk_chars: 4_000,
+            chunk_overlap_lines: 3,
+        }
+    }
+}
+
+/// Overrides coming from the environment and the command line.
+#[derive(Debug, Clone, Default, Args)]
+pub struct ConfigOverrides {
+    /// Path to a TOML config file.
+    #[arg(long = "config", env = "MSRAG_CONFIG", global = true)]
+    pub config_path: Option<PathBuf>,
+
+    /// Qdrant base URL.
+    #[arg(long, env = "MSRAG_QDRANT_URL", global = true)]
+    pub qdrant_url: Option<String>,
+
+    /// Qdrant request timeout in seconds.
+    #[arg(long, env = "MSRAG_QDRANT_TIMEOUT_SECS", global = true)]
+    pub qdrant_timeout_secs: Option<u64>,
+
+    /// Embeddings API base URL (OpenAI-compatible).
+    #[arg(long, env = "MSRAG_EMBED_URL", global = true)]
+    pub embed_url: Option<String>,
+
+    /// Embedding model name.
+    #[arg(long, env = "MSRAG_EMBED_MODEL", global = true)]
+    pub embed_model: Option<String>,
+
+    /// Embedding vector size.
+    #[arg(long, env = "MSRAG_VECTOR_SIZE", global = true)]
+    pub vector_size: Option<usize>,
+
+    /// Embeddings request timeout in seconds.
+    #[arg(long, env = "MSRAG_EMBED_TIMEOUT_SECS", global = true)]
+    pub embed_timeout_secs: Option<u64>,
+
+    /// Directory of embedding input templates.
+    #[arg(long, env = "MSRAG_EMBED_TEMPLATE_DIR", global = true)]
+    pub embed_template_dir: Option<PathBuf>,
+
+    /// Chat completions URL.
+    #[arg(long, env = "MSRAG_LLM_URL", global = true)]
+    pub llm_url: Option<String>,
+
+    /// Chat completions API key.
+    #[arg(long, env = "MSRAG_LLM_API_KEY", global = true, hide_env_values = true)]
+    pub llm_api_key: Option<String>,
+
+    /// Chat model name.
+    #[arg(long, env = "MSRAG_LLM_MODEL", global = true)]
+    pub llm_model: Option<String>,
+
+    /// Emit one summary document per file (`true`/`false`).
+    #[arg(long, env = "MSRAG_INCLUDE_FILENAME_DOC", global = true)]
+    pub include_filename_doc: Option<bool>,
+
+    /// Texts per embeddings request.
+    #[arg(long, env = "MSRAG_EMBED_BATCH", global = true)]
+    pub embed_batch: Option<usize>,
+
+    /// Points per Qdrant upsert request.
+    #[arg(long, env = "MSRAG_UPSERT_BATCH", global = true)]
+    pub upsert_batch: Option<usize>,
+
+    /// Retries of a failed upsert batch.
+    #[arg(long, env = "MSRAG_UPSERT_RETRIES", global = true)]
+    pub upsert_retries: Option<usize>,
+
+    /// Delete stale points after each repo pass (`true`/`false`).
+    #[arg(long, env = "MSRAG_PRUNE", global = true)]
+    pub prune: Option<bool>,
+
+    /// Full rescan period in `watch` mode.
+    #[arg(long, env = "MSRAG_RESCAN_INTERVAL_SECS", global = true)]
+    pub rescan_interval_secs: Option<u64>,
+
+    /// How long `watch` waits for file events to settle.
+    #[arg(long, env = "MSRAG_WATCH_DEBOUNCE_MS", global = true)]
+    pub watch_debounce_ms: Option<u64>,
+
+    /// Documents with more code than this are split into chunks.
+    #[arg(long, env = "MSRAG_MAX_CHUNK_CHARS", global = true)]
+    pub max_chunk_chars: Option<usize>,
+
+    /// Lines each chunk repeats from the previous one.
+    #[arg(long, env = "MSRAG_CHUNK_OVERLAP_LINES", global = true)]
+    pub chunk_overlap_lines: Option<usize>,
+}
+
+impl Config {
+    /// Resolve the full configuration: defaults → file → env/CLI overrides.
+    pub fn load(overrides: &ConfigOverrides) -> Result<Self, ConfigError> {
+        let mut config = match &overrides.config_path {
+            Some(path) => Self::from_file(path)?,
+            None => {
+                let default_path = Path::new(DEFAULT_CONFIG_FILE);
+                if default_path.is_file() {
+                    Self::from_file(default_path)?
+                } else {
+                    Self::default()
+                }
+            }
+        };
+        config.apply(overrides);
+        Ok(config)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let text = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.display().to_string(),
+            source,
+        })?;
+        Self::from_toml_str(&text).map_err(|source| ConfigError::Toml {
+            path: path.display().to_string(),
+            source,
+        })
+    }
+
+    pub fn from_toml_str(text: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(text)
+    }
+
+    /// Apply env/CLI overrides on top of the current values.
+    pub fn apply(&mut self, o: &ConfigOverrides) {
+        if let Some(v) = &o.qdrant_url {
+            self.qdrant.url = v.clone();
+        }
+        if let Some(v) = o.qdrant_timeout_secs {
+            self.qdrant.timeout_secs = v;
+        }
+        if let Some(v) = &o.embed_url {
+            self.embedder.base_url = v.clone();
+        }
+        if let Some(v) = &o.embed_model {
+            self.embedder.model = v.clone();
+        }
+        if let Some(v) = o.vector_size {
+            self.embedder.vector_size = v;
+        }
+        if let Some(v) = o.embed_timeout_secs {
+            self.embedder.timeout_secs = v;
+        }
+        if let Some(v) = &o.embed_template_dir {
+            self.embedder.template_dir = Some(v.clone());
+        }
+        if let Some(v) = &o.llm_url {
+            self.llm.url = v.clone();
+        }
+        if let Some(v) = &o.llm_api_key {
+            self.llm.api_key = v.clone();
+        }
+        if let Some(v) = &o.llm_model {
+            self.llm.model = v.clone();
+        }
+        if let Some(v) = o.include_filename_doc {
+            self.indexing.include_filename_doc = v;
+        }
+        if let Some(v) = o.embed_batch {
+            self.indexing.embed_batch = v;
+        }
+        if let Some(v) = o.upsert_batch {
+            self.indexing.upsert_batch = v;
+        }
+        if let Some(v) = o.upsert_retries {
+            self.indexing.upsert_retries = v;
+        }
+        if let Some(v) = o.prune {
+            self.indexing.prune = v;
+        }
+        if let Some(v) = o.rescan_interval_secs {
+            self.indexing.rescan_interval_secs = v;
+        }
+        if let Some(v) = o.watch_debounce_ms {
+            self.indexing.watch_debounce_ms = v;
+        }
+        if let Some(v) = o.max_chunk_chars {
+            self.indexing.max_chunk_chars = v;
+        }
+        if let Some(v) = o.chunk_overlap_lines {
+            self.indexing.chunk_overlap_lines = v;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_file_keeps_defaults() {
+        let cfg = Config::from_toml_str(
+            r#"
+[qdrant]
+url = "http://qdrant:6333"
+
+[indexing]
+embed_batch = 8
+"#,
+        )
+        .unwrap();
+        assert_eq!(cfg.qdrant.url, "http://qdrant:6333");
+        assert_eq!(cfg.indexing.embed_batch, 8);
+        assert_eq!(cfg.indexing.upsert_batch, 64);
+        assert_eq!(cfg.embedder.vector_size, 768);
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        assert!(Config::from_toml_str("[qdrant]\nurll = \"x\"\n").is_err());
+    }
+
+    #[test]
+    fn overrides_win_over_file() {
+        let mut cfg = Config::from_toml_str("[embedder]\nmodel = \"from-file\"\n").unwrap();
+        cfg.apply(&ConfigOverrides {
+            embed_model: Some("from-cli".into()),
+            vector_size: Some(1024),
+            qdrant_timeout_secs: Some(5),
+            upsert_batch: Some(16),
+            prune: Some(false),
+            ..Default::default()
+        });
+        assert_eq!(cfg.embedder.model, "from-cli");
+        assert_eq!(cfg.embedder.vector_size, 1024);
+        assert_eq!(cfg.qdrant.timeout_secs, 5);
+        assert_eq!(cfg.indexing.upsert_batch, 16);
+        assert!(!cfg.indexing.prune);
+        assert_eq!(cfg.qdrant.url, "http://localhost:6333");
+    }
+}
@@ -1,32 +1,100 @@
 //! id_generator.rs
 //!
 //! Deterministic ID and content-hash helpers for Qdrant points.
-//! - Point IDs: UUIDv5 derived from a canonical document key
-//! - Content hash: SHA-256 hex for future incremental update logic
+//! - Point IDs: UUIDv5 derived from a canonical document key built on the
+//!   *qualified* symbol (module path + parent type + name) plus an occurrence
+//!   ordinal, so same-named items in one file never collide
+//! - Content hash: SHA-256 hex used by incremental indexing to skip unchanged docs
 
 use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
+/// Version of the id scheme below, stored in each payload as `id_scheme`.
+/// Points with an older (or missing) version are unreachable by new ids and
+/// are cleaned up by pruning or the `migrate` command.
+///
+/// - 1: `repo|path|symbol|type` (bare symbol name; collided across impl blocks)
+/// - 2: qualified symbol + occurrence ordinal (+ chunk index for chunks)
+pub const ID_SCHEME: u32 = 2;
+
+/// Module path, parent type and name of a symbol, trimmed, empty ones
+/// dropped. Parent types containing whitespace (impl headers) and the name
+/// of an `impl` document become one `<impl ...>` segment. Shared by the
+/// point-id key and `qualified_name` (see `ingest::language`).
+pub fn symbol_segments(
+    module_path: Option<&str>,
+    parent_type: Option<&str>,
+    name: &str,
+    kind: &str,
+) -> Vec<String> {
+    let impl_segment = |header: &str| format!("<impl {header}>");
+    let parent = parent_type.map(str::trim).map(|p| {
+        if p.contains(char::is_whitespace) {
+            impl_segment(p)
+        } else {
+            p.to_string()
+        }
+    });
+    let name = match name.trim() {
+        header if kind == "impl" && !header.is_empty() => impl_segment(header),
+        name => name.to_string(),
+    };
+    [
+        module_path.map(|m| m.trim().to_string()),
+        parent,
+        Some(name),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|s| !s.is_empty())
+    .collect()
+}
+
+/// Joins `symbol_segments` into one `::`-separated symbol, e.g.
+/// `handlers::<impl Display for Foo>::fmt`.
+pub fn qualified_symbol(
+    module_path: Option<&str>,
+    parent_type: Option<&str>,
+    name: &str,
+    kind: &str,
+) -> String {
+    symbol_segments(module_path, parent_type, name, kind).join("::")
+}
+
 /// Canonicalizes the "document identity" that should remain stable across runs.
 ///
-/// Recommended inputs:
+/// Inputs:
 /// - repo (collection name)
 /// - file_path (relative path from repo root)
-/// - symbol_name (e.g., fn name, struct name, or filename for type=filename)
+/// - qualified_symbol (see `qualified_symbol`; filename for type=filename)
 /// - kind (function|method|struct|enum|trait|filename)
+/// - ordinal: 0 for the first item with this (path, symbol, kind); n for the
+///   n-th repeat (overloads, `#[cfg]` twins). Omitted from the key when 0 so
+///   the common case does not depend on item order.
+/// - chunk: `chunk_index` of a chunk of an oversized item (see
+///   `ingest::chunker`); chunks of one item share its ordinal.
 pub fn canonical_document_key(
     repo: &str,
     file_path: &str,
-    symbol_name: &str,
+    qualified_symbol: &str,
     kind: &str,
+    ordinal: u32,
+    chunk: Option<u32>,
 ) -> String {
-    format!(
+    let mut key = format!(
         "repo={}|path={}|symbol={}|type={}",
         repo.trim(),
         file_path.trim(),
-        symbol_name.trim(),
+        qualified_symbol.trim(),
         kind.trim().to_lowercase()
-    )
+    );
+    if ordinal > 0 {
+        key.push_str(&format!("|n={ordinal}"));
+    }
+    if let Some(chunk) = chunk {
+        key.push_str(&format!("|chunk={chunk}"));
+    }
+    key
 }
 
 /// Stable, deterministic UUIDv5 point ID derived from the canonical key.
@@ -36,16 +104,18 @@ pub fn canonical_document_key(
 pub fn deterministic_point_id(
     repo: &str,
     file_path: &str,
-    symbol_name: &str,
+    qualified_symbol: &str,
     kind: &str,
+    ordinal: u32,
+    chunk: Option<u32>,
 ) -> String {
-    let key = canonical_document_key(repo, file_path, symbol_name, kind);
+    let key = canonical_document_key(repo, file_path, qualified_symbol, kind, ordinal, chunk);
     let id = Uuid::new_v5(&Uuid::NAMESPACE_URL, key.as_bytes());
     id.to_string()
 }
 
-/// Content hash capturing the current *contents* of a document. This can be used
-/// later to detect changes and skip re-embedding.
+/// Content hash capturing the current *contents* of a document. Indexing compares
+/// it with the stored `hash_source` to skip re-embedding.
 ///
 /// A practical canonicalization: signature + doc_comment + code
 pub fn content_hash(signature: Option<&str>, doc_comment: Option<&str>, code: &str) -> String {
@@ -70,18 +140,55 @@ mod tests {
 
     #[test]
     fn deterministic_ids_are_stable() {
-        let a = deterministic_point_id("repoA", "src/lib.rs", "foo", "function");
-        let b = deterministic_point_id("repoA", "src/lib.rs", "foo", "function");
+        let a = deterministic_point_id("repoA", "src/lib.rs", "foo", "function", 0, None);
+        let b = deterministic_point_id("repoA", "src/lib.rs", "foo", "function", 0, None);
         assert_eq!(a, b);
     }
 
     #[test]
     fn ids_change_when_key_changes() {
-        let a = deterministic_point_id("repoA", "src/lib.rs", "foo", "function");
-        let b = deterministic_point_id("repoA", "src/lib.rs", "bar", "function");
+        let a = deterministic_point_id("repoA", "src/lib.rs", "foo", "function", 0, None);
+        let b = deterministic_point_id("repoA", "src/lib.rs", "bar", "function", 0, None);
         assert_ne!(a, b);
     }
 
+    #[test]
+    fn same_name_in_different_impls_does_not_collide() {
+        let a = qualified_symbol(None, Some("A"), "new", "method");
+        let b = qualified_symbol(None, Some("B"), "new", "method");
+        assert_ne!(
+            deterministic_point_id("r", "src/lib.rs", &a, "method", 0, None),
+            deterministic_point_id("r", "src/lib.rs", &b, "method", 0, None)
+        );
+    }
+
+    #[test]
+    fn ordinal_disambiguates_overloads() {
+        let a = deterministic_point_id("r", "A.kt", "Svc::get", "method", 0, None);
+        let b = deterministic_point_id("r", "A.kt", "Svc::get", "method", 1, None);
+        assert_ne!(a, b);
+        assert!(
+            !canonical_document_key("r", "A.kt", "Svc::get", "method", 0, None).contains("|n=")
+        );
+    }
+
+    #[test]
+    fn qualified_symbol_formats_segments() {
+        assert_eq!(qualified_symbol(None, None, "foo", "function"), "foo");
+        assert_eq!(
+            qualified_symbol(Some("a::b"), Some("Foo"), "bar", "method"),
+            "a::b::Foo::bar"
+        );
+        assert_eq!(
+            qualified_symbol(None, Some("Display for Foo"), "fmt", "method"),
+            "<impl Display for Foo>::fmt"
+        );
+        assert_eq!(
+            qualified_symbol(Some(" "), None, "Display for Foo", "impl"),
+            "<impl Display for Foo>"
+        );
+    }
+
     #[test]
     fn content_hash_changes_with_code() {
         let h1 = content_hash(Some("fn foo()"), Some("/// docs"), "let x = 1;");
@@ -1,2 +1,2 @@
 pub(crate) mod id_generator;
-pub(crate) mod qdrant_schema;
\ No newline at end of file
+pub(crate) mod qdrant_schema;
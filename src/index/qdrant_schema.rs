@@ -2,6 +2,7 @@
 //!
 //! Minimal schema manager for Qdrant collections:
 //! - ensure_collection(): create if missing, else validate vector params
+//! - list_collections() / delete_collection()
 //!
 //! Assumes Qdrant is reachable at a base URL.
 
@@ -12,6 +13,9 @@ use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+/// Default per-request timeout.
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub enum Distance {
     Cosine,
@@ -43,14 +47,22 @@ pub struct QdrantSchema {
 impl QdrantSchema {
     pub fn new<S: Into<String>>(base_url: S) -> Result<Self, SchemaError> {
         let http = reqwest::Client::builder()
-            .timeout(Duration::from_secs(30))
+            .timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
             .build()?;
         Ok(Self {
             http,
-            base_url: base_url.into(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
         })
     }
 
+    /// Override the timeout
+    pub fn with_timeout_secs(mut self, secs: u64) -> Result<Self, SchemaError> {
+        self.http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(secs))
+            .build()?;
+        Ok(self)
+    }
+
     fn collections_url(&self) -> String {
         format!("{}/collections", self.base_url)
     }
@@ -84,6 +96,37 @@ impl QdrantSchema {
         }
     }
 
+    /// Names of all collections on the server.
+    pub async fn list_collections(&self) -> Result<Vec<String>, SchemaError> {
+        let resp = self.http.get(self.collections_url()).send().await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(SchemaError::Status { status, body });
+        }
+        let body: ListCollectionsResponse = resp.json().await?;
+        Ok(body
+            .result
+            .map(|r| r.collections.into_iter().map(|c| c.name).collect())
+            .unwrap_or_default())
+    }
+
+    /// Delete a collection. Returns `false` if it did not exist.
+    pub async fn delete_collection(&self, name: &str) -> Result<bool, SchemaError> {
+        let resp = self.http.delete(self.collection_url(name)).send().await?;
+        match resp.status() {
+            s if s.is_success() => {
+                let body: DeleteCollectionResponse = resp.json().await?;
+                Ok(body.result.unwrap_or(false))
+            }
+            StatusCode::NOT_FOUND => Ok(false),
+            status => {
+                let body = resp.text().await.unwrap_or_default();
+                Err(SchemaError::Status { status, body })
+            }
+        }
+    }
+
     async fn get_collection(&self, name: &str) -> Result<Option<CollectionInfo>, SchemaError> {
         let url = self.collection_url(name);
         let resp = self.http.get(url).send().await?;
@@ -120,10 +163,7 @@ impl QdrantSchema {
         if !resp.status().is_success() {
             let status = resp.status();
             let text = resp.text().await.unwrap_or_default();
-            return Err(SchemaError::Status {
-                status,
-                body: text,
-            });
+            return Err(SchemaError::Status { status, body: text });
         }
         Ok(())
     }
@@ -151,6 +191,26 @@ struct VectorParams {
     distance: Distance,
 }
 
+#[derive(Debug, Deserialize)]
+struct ListCollectionsResponse {
+    result: Option<ListCollectionsResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListCollectionsResult {
+    collections: Vec<CollectionDescription>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CollectionDescription {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeleteCollectionResponse {
+    result: Option<bool>,
+}
+
 #[derive(Debug, Deserialize)]
 struct GetCollectionResponse {
     // status/ time omitted
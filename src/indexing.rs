@@ -1,76 +1,167 @@
 // src/indexing
-//! Wires everything together into a minimal daemon:
+//! Wires everything together (the long-running daemon loop lives in `watch.rs`):
 //! - ensure qdrant collection (per repo)
 //! - scan repo (respects .gitignore)
-//! - parse rust → Documents
+//! - parse sources → Documents (per-language rules, see `ingest::language`)
+//! - chunk → split documents too large to embed along AST sub-blocks
+//!   (`ingest::chunker`)
 //! - normalize → NormalizedDoc
-//! - build canonical text
+//! - diff against existing points (`hash_source` + embedding model + template)
+//!   so unchanged documents are not re-embedded; moved-but-unchanged ones (or
+//!   ones whose `metadata` changed) only get a payload update
+//! - render the embedding input (`transform::embedding_template`)
 //! - embed → vectors (LM Studio / OpenAI-compatible endpoint)
 //! - upsert → Qdrant
+//! - prune → delete the repo's points not produced by this pass (renamed/removed
+//!   symbols and files, repos that disappeared); disable with `--no-prune`
+//! - link → match `http_call` points against other repos' endpoints and store
+//!   the edges on the calls (`link_call_graph`); store each topic's publisher /
+//!   subscriber repos on its usages (`link_topics`); link route endpoints to
+//!   their handler's point (`link_route_handlers`)
 //!
-//! Run: `cargo run -- /path/to/repo`
+//! Run: `cargo run -- index /path/to/project`
 //! (If no path provided, defaults to current directory.)
 //!
-//! This loop is idempotent. We re-index on each cycle (static update behavior).
+//! Each pass is idempotent; only new or changed documents are re-embedded.
 
 use crate::client::embedder_client::EmbedderClient;
 use crate::client::qdrant_client::{PointWrite, QdrantClient};
-use crate::index::id_generator::deterministic_point_id;
+use crate::config::Config;
+use crate::index::id_generator::ID_SCHEME;
 use crate::index::qdrant_schema::{Distance, QdrantSchema};
-use crate::ingest::repo_scanner::ProjectScanner;
-use crate::ingest::rust_parser::{CodeParser, ParseLanguage};
+use crate::ingest::chunker::{ChunkLimits, chunk_documents};
+use crate::ingest::language::{LanguageRegistry, SourceFile};
+use crate::ingest::repo_scanner::{ProjectScanner, repo_dir_name};
+use crate::transform::call_graph::resolve_calls;
 use crate::transform::doc_normalizer::{DocNormalizer, NormalizedDoc};
-use crate::{
-    EMBED_BASE_MODEL, EMBED_BATCH, INCLUDE_FILENAME_DOC, QDRANT_URL, UPSERT_BATCH, UPSERT_RETRIES,
-    VECTOR_SIZE,
-};
+use crate::transform::embedding_template::EmbeddingTemplates;
+use crate::transform::route_linker::route_handlers;
+use crate::transform::topic_graph::topic_participants;
 use anyhow::{Context, Result};
 use indicatif::{ProgressBar, ProgressIterator, ProgressStyle};
 use serde_json::json;
-use std::env;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 const DISTANCE: Distance = Distance::Cosine;
 
-pub async fn index(p: &str) -> Result<()> {
-    let root = PathBuf::from(p);
-    let collection = repo_name(&root)?;
+pub async fn index(config: &Config, p: &Path) -> Result<()> {
+    let root = project_root(p)?;
+    let ctx = IndexContext::new(config, &root)?;
 
     eprintln!(
         "Indexing repo:\n  root: {}\n  collection: {}\n",
         root.display(),
-        collection
+        ctx.collection
     );
 
-    // clients
-    let schema = QdrantSchema::new(QDRANT_URL)?;
-    let qdrant = QdrantClient::new()?;
-    let embedder = EmbedderClient::new(EMBED_BASE_MODEL, Some(VECTOR_SIZE))?;
+    tick_once(&ctx, &root).await;
+
+    Ok(())
+}
+
+/// Moves a collection to the current `ID_SCHEME`: deletes every point written
+/// under an older scheme, then re-indexes the project so they are recreated
+/// under their new ids.
+pub async fn migrate(config: &Config, p: &Path) -> Result<()> {
+    let root = project_root(p)?;
+    let ctx = IndexContext::new(config, &root)?;
 
-    tick_once(&schema, &qdrant, &embedder, &root, &collection).await;
+    // `must_not` also matches points that predate the `id_scheme` field
+    let legacy_filter = json!({
+        "must_not": [{ "key": "id_scheme", "match": { "value": ID_SCHEME } }]
+    });
+    let legacy = ctx
+        .qdrant
+        .scroll_points(&ctx.collection, legacy_filter, &["repo"])
+        .await
+        .with_context(|| format!("failed to scan '{}' for legacy points", ctx.collection))?;
 
+    if legacy.is_empty() {
+        eprintln!(
+            "[migrate] '{}' already uses id scheme {ID_SCHEME}",
+            ctx.collection
+        );
+    } else {
+        let ids: Vec<String> = legacy.into_iter().map(|p| p.id).collect();
+        ctx.qdrant.delete_points(&ctx.collection, &ids).await?;
+        eprintln!(
+            "[migrate] removed {} legacy points from '{}'; re-indexing",
+            ids.len(),
+            ctx.collection
+        );
+    }
+
+    tick_once(&ctx, &root).await;
     Ok(())
 }
 
-async fn tick_once(
-    schema: &QdrantSchema,
-    qdrant: &QdrantClient,
-    embedder: &EmbedderClient,
-    root: &Path,
-    collection: &str,
-) {
+/// Clients and settings shared by every pass over one project.
+pub(crate) struct IndexContext<'a> {
+    pub config: &'a Config,
+    pub collection: String,
+    /// Languages to scan and parse.
+    pub registry: LanguageRegistry,
+    /// Embedding input templates.
+    pub templates: EmbeddingTemplates,
+    schema: QdrantSchema,
+    qdrant: QdrantClient,
+    embedder: EmbedderClient,
+}
+
+impl<'a> IndexContext<'a> {
+    pub fn new(config: &'a Config, root: &Path) -> Result<Self> {
+        let schema =
+            QdrantSchema::new(&config.qdrant.url)?.with_timeout_secs(config.qdrant.timeout_secs)?;
+        let qdrant =
+            QdrantClient::new(&config.qdrant.url)?.with_timeout_secs(config.qdrant.timeout_secs)?;
+        let embedder = EmbedderClient::new(
+            &config.embedder.base_url,
+            &config.embedder.model,
+            Some(config.embedder.vector_size),
+        )?
+        .with_timeout_secs(config.embedder.timeout_secs)?;
+        let templates = EmbeddingTemplates::load(config.embedder.template_dir.as_deref())?;
+
+        Ok(Self {
+            config,
+            collection: repo_name(root)?,
+            registry: LanguageRegistry::default(),
+            templates,
+            schema,
+            qdrant,
+            embedder,
+        })
+    }
+
+    /// Language of a document's file and the name of its embedding template.
+    fn template_of(&self, d: &NormalizedDoc) -> (Option<&'static str>, &str) {
+        let language = self
+            .registry
+            .for_path(Path::new(&d.file_path))
+            .map(|l| l.name());
+        (language, self.templates.select(&d.kind, language))
+    }
+}
+
+/// Resolves the project root; canonicalized so `.` still yields a meaningful
+/// collection name.
+pub(crate) fn project_root(p: &Path) -> Result<PathBuf> {
+    p.canonicalize()
+        .with_context(|| format!("cannot resolve path {}", p.display()))
+}
+
+/// One full pass: every repo under `root`.
+pub(crate) async fn tick_once(ctx: &IndexContext<'_>, root: &Path) {
     // 1) ensure collection
-    if let Err(e) = schema
-        .ensure_collection(collection, VECTOR_SIZE, DISTANCE)
-        .await
-    {
+    if let Err(e) = ensure_collection(ctx).await {
         eprintln!("[ensure_collection] error: {e:#}");
         return;
     }
 
-    // 2) scan repo (.gitignore-aware)
-    let scanner = ProjectScanner::new();
+    // 2) scan project (one subdirectory per repo)
+    let scanner = ProjectScanner::new(&ctx.registry);
     let repo_roots = match scanner.scan_project(root) {
         Ok(v) => v,
         Err(e) => {
@@ -79,142 +170,621 @@ async fn tick_once(
         }
     };
 
-    for repo_root in repo_roots {
-        let files = match scanner.scan_repo(&repo_root) {
-            Ok(v) => v,
-            Err(e) => {
-                eprintln!("[scan_repo] error: {e:#}");
-                return;
-            }
-        };
-        if files.is_empty() {
-            eprintln!("[scan_repo] no files found");
-            continue;
+    for repo_root in &repo_roots {
+        index_repo(ctx, repo_root, None).await;
+    }
+
+    if ctx.config.indexing.prune {
+        let present: HashSet<String> = repo_roots.iter().map(|r| repo_dir_name(r)).collect();
+        prune_missing_repos(ctx, &present).await;
+    }
+
+    link_across_repos(ctx).await;
+}
+
+pub(crate) async fn ensure_collection(ctx: &IndexContext<'_>) -> Result<()> {
+    ctx.schema
+        .ensure_collection(&ctx.collection, ctx.config.embedder.vector_size, DISTANCE)
+        .await?;
+    Ok(())
+}
+
+/// Indexes one repo. With `only`, just the given repo-relative paths are
+/// re-parsed (used by watch mode for changed files); pruning is then scoped
+/// to those paths too.
+pub(crate) async fn index_repo(
+    ctx: &IndexContext<'_>,
+    repo_root: &Path,
+    only: Option<&HashSet<String>>,
+) {
+    let repo = repo_dir_name(repo_root);
+
+    // snapshot of what Qdrant already holds (drives both diffing and pruning)
+    let existing = match fetch_existing(ctx, &repo, only).await {
+        Ok(m) => Some(m),
+        Err(e) => {
+            eprintln!("[existing] error: {e:#}; re-embedding everything, skipping prune");
+            None
         }
-        eprintln!("[1/4] Scan repo {repo_root:?}; {} files", files.len());
+    };
+    let empty = HashMap::new();
 
-        // 3) parse → documents
-        let mut rust_parser = match CodeParser::new(ParseLanguage::Rust) {
-            Ok(p) => p,
-            Err(e) => {
-                eprintln!("[parser] init error: {e:#}");
-                return;
-            }
-        };
-        let mut kotlin_parser = match CodeParser::new(ParseLanguage::Kotlin) {
-            Ok(p) => p,
-            Err(e) => {
-                eprintln!("[parser] init error: {e:#}");
-                return;
-            }
+    let Some(outcome) = sync_repo(
+        ctx,
+        repo_root,
+        &repo,
+        only,
+        existing.as_ref().unwrap_or(&empty),
+    )
+    .await
+    else {
+        return;
+    };
+
+    let Some(existing) = &existing else {
+        return;
+    };
+    if ctx.config.indexing.prune {
+        prune_stale(ctx, &repo, existing, &outcome).await;
+    } else {
+        let legacy = existing
+            .values()
+            .filter(|p| p.id_scheme != u64::from(ID_SCHEME))
+            .count();
+        if legacy > 0 {
+            eprintln!(
+                "[index] {legacy} points in '{repo}' use an older id scheme; \
+                 run `migrate` (or index without --no-prune) to replace them"
+            );
+        }
+    }
+}
+
+/// What a repo pass produced; anything else stored for the repo is stale.
+#[derive(Debug, Default)]
+struct PassOutcome {
+    produced: HashSet<String>,
+    /// Files that could not be parsed this time; their points are kept.
+    failed_files: HashSet<String>,
+}
+
+/// Scan → parse → normalize → diff → embed/upsert. Returns `None` when the
+/// pass was aborted (nothing reliable to prune against).
+async fn sync_repo(
+    ctx: &IndexContext<'_>,
+    repo_root: &Path,
+    repo: &str,
+    only: Option<&HashSet<String>>,
+    existing: &HashMap<String, ExistingPoint>,
+) -> Option<PassOutcome> {
+    let config = ctx.config;
+    let collection = ctx.collection.as_str();
+    let mut outcome = PassOutcome::default();
+
+    // scan repo (.gitignore-aware)
+    let scanner = ProjectScanner::new(&ctx.registry);
+    let mut files = match scanner.scan_repo(repo_root) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("[scan_repo] error: {e:#}");
+            return None;
+        }
+    };
+    if let Some(only) = only {
+        files.retain(|f| only.contains(&f.file_path));
+    }
+    if files.is_empty() {
+        eprintln!("[scan_repo] no files found");
+        return Some(outcome);
+    }
+    eprintln!("[1/4] Scan repo {repo_root:?}; {} files", files.len());
+
+    // parse → documents
+    let include_filename_doc = config.indexing.include_filename_doc;
+    let limits = ChunkLimits {
+        max_chars: config.indexing.max_chunk_chars,
+        overlap_lines: config.indexing.chunk_overlap_lines,
+    };
+    let mut all_docs = Vec::new();
+    for f in files.into_iter().progress() {
+        let Some(language) = ctx.registry.for_path(Path::new(&f.file_path)) else {
+            continue;
         };
-        let mut ts_parser = match CodeParser::new(ParseLanguage::TypeScript) {
-            Ok(p) => p,
-            Err(e) => {
-                eprintln!("[parser] init error: {e:#}");
-                return;
-            }
+        let file = SourceFile {
+            repo: &f.repo,
+            file_path: &f.file_path,
+            source: &f.source,
         };
-        let mut js_parser = match CodeParser::new(ParseLanguage::JavaScript) {
-            Ok(p) => p,
+        match language.parse_file(&file, include_filename_doc) {
+            Ok(docs) => all_docs.extend(chunk_documents(language, &file, docs, limits)),
             Err(e) => {
-                eprintln!("[parser] init error: {e:#}");
-                return;
+                eprintln!("[parse_file] {} ({}): {e:#}", f.file_path, language.name());
+                outcome.failed_files.insert(f.file_path);
             }
-        };
+        }
+    }
+    if all_docs.is_empty() {
+        eprintln!("[parse] yielded 0 documents");
+        return Some(outcome);
+    }
+    eprintln!("[2/4] Normalising {} documents", all_docs.len());
 
-        let mut all_docs = Vec::new();
-        for f in files.into_iter().progress() {
-            if f.file_path.ends_with("rs") {
-                match rust_parser.parse_file(&f.repo, &f.file_path, &f.source, INCLUDE_FILENAME_DOC)
-                {
-                    Ok(mut docs) => all_docs.append(&mut docs),
-                    Err(e) => eprintln!("[parse_file] {}: {e:#}", f.file_path),
-                }
-            } else if f.file_path.ends_with("kt") {
-                match kotlin_parser.parse_file(
-                    &f.repo,
-                    &f.file_path,
-                    &f.source,
-                    INCLUDE_FILENAME_DOC,
-                ) {
-                    Ok(mut docs) => all_docs.append(&mut docs),
-                    Err(e) => eprintln!("[parse_file] {}: {e:#}", f.file_path),
-                }
-            } else if f.file_path.ends_with("ts") {
-                match kotlin_parser.parse_file(
-                    &f.repo,
-                    &f.file_path,
-                    &f.source,
-                    INCLUDE_FILENAME_DOC,
-                ) {
-                    Ok(mut docs) => all_docs.append(&mut docs),
-                    Err(e) => eprintln!("[parse_file] {}: {e:#}", f.file_path),
-                }
-            } else if f.file_path.ends_with("js") {
-                match kotlin_parser.parse_file(
-                    &f.repo,
-                    &f.file_path,
-                    &f.source,
-                    INCLUDE_FILENAME_DOC,
-                ) {
-                    Ok(mut docs) => all_docs.append(&mut docs),
-                    Err(e) => eprintln!("[parse_file] {}: {e:#}", f.file_path),
-                }
-            }
+    // normalize
+    let normalizer = DocNormalizer::default();
+    let norm_docs: Vec<NormalizedDoc> = normalizer.normalize_all(all_docs);
+    outcome.produced = norm_docs.iter().map(|d| d.point_id.clone()).collect();
+
+    // diff against what is already in Qdrant
+    let plan = plan_updates(norm_docs, existing, &config.embedder.model, |d| {
+        ctx.templates.fingerprint(ctx.template_of(d).1).to_string()
+    });
+    eprintln!(
+        "[3/4] Embedding {} docs ({} moved, {} unchanged)",
+        plan.embed.len(),
+        plan.moved.len(),
+        plan.unchanged
+    );
+
+    if !plan.moved.is_empty()
+        && let Err(e) = ctx
+            .qdrant
+            .set_payloads(collection, plan.moved, config.indexing.upsert_batch)
+            .await
+    {
+        eprintln!("[set_payloads] error: {e:#}");
+    }
+    if plan.embed.is_empty() {
+        eprintln!("[index] done: '{repo}' is up to date");
+        return Some(outcome);
+    }
+
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(ProgressStyle::with_template("{prefix} {spinner} {wide_msg}").unwrap());
+    pb.set_prefix("[3/4]");
+    pb.set_message("Embedding and upserting...");
+    pb.enable_steady_tick(Duration::from_millis(100));
+
+    // embed + upsert (batched)
+    if let Err(e) = embed_and_upsert(ctx, &plan.embed).await {
+        eprintln!("[index] error: {e:#}");
+        return None;
+    }
+
+    pb.finish_with_message("Done!");
+
+    eprintln!(
+        "[index] done: upserted {} documents into '{}'",
+        plan.embed.len(),
+        collection
+    );
+    Some(outcome)
+}
+
+/// Max stale points listed individually in the prune report.
+const PRUNE_REPORT_LIMIT: usize = 20;
+
+/// Deletes the repo's points that this pass did not produce.
+async fn prune_stale(
+    ctx: &IndexContext<'_>,
+    repo: &str,
+    existing: &HashMap<String, ExistingPoint>,
+    outcome: &PassOutcome,
+) {
+    let stale = stale_points(existing, outcome);
+    if stale.is_empty() {
+        return;
+    }
+
+    let ids: Vec<String> = stale.iter().map(|(id, _)| id.to_string()).collect();
+    if let Err(e) = ctx.qdrant.delete_points(&ctx.collection, &ids).await {
+        eprintln!("[prune] error: {e:#}");
+        return;
+    }
+
+    eprintln!(
+        "[prune] removed {} stale points from '{repo}':",
+        stale.len()
+    );
+    for (_, p) in stale.iter().take(PRUNE_REPORT_LIMIT) {
+        eprintln!("  - {}::{} ({})", p.file_path, p.symbol_name, p.kind);
+    }
+    if stale.len() > PRUNE_REPORT_LIMIT {
+        eprintln!("  ... and {} more", stale.len() - PRUNE_REPORT_LIMIT);
+    }
+}
+
+/// Stored points not produced by this pass, excluding files that failed to
+/// parse. Sorted by location for a readable report.
+fn stale_points<'a>(
+    existing: &'a HashMap<String, ExistingPoint>,
+    outcome: &PassOutcome,
+) -> Vec<(&'a str, &'a ExistingPoint)> {
+    let mut stale: Vec<(&str, &ExistingPoint)> = existing
+        .iter()
+        .filter(|(id, p)| {
+            !outcome.produced.contains(*id) && !outcome.failed_files.contains(&p.file_path)
+        })
+        .map(|(id, p)| (id.as_str(), p))
+        .collect();
+    stale.sort_by(|a, b| (&a.1.file_path, a.1.line_start).cmp(&(&b.1.file_path, b.1.line_start)));
+    stale
+}
+
+/// Deletes points of repos that no longer exist under the project root.
+async fn prune_missing_repos(ctx: &IndexContext<'_>, present: &HashSet<String>) {
+    let points = match ctx
+        .qdrant
+        .scroll_points(&ctx.collection, json!({}), &["repo"])
+        .await
+    {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("[prune] error: {e:#}");
+            return;
+        }
+    };
+
+    let mut gone: HashMap<String, Vec<String>> = HashMap::new();
+    for p in points {
+        let repo = p.payload["repo"].as_str().unwrap_or_default();
+        if !present.contains(repo) {
+            gone.entry(repo.to_string()).or_default().push(p.id);
         }
-        if all_docs.is_empty() {
-            eprintln!("[parse] yielded 0 documents");
+    }
+
+    for (repo, ids) in gone {
+        match ctx.qdrant.delete_points(&ctx.collection, &ids).await {
+            Ok(()) => eprintln!(
+                "[prune] removed {} points of missing repo '{repo}'",
+                ids.len()
+            ),
+            Err(e) => eprintln!("[prune] error: {e:#}"),
+        }
+    }
+}
+
+/// Links between points, refreshed after every pass (full or watch):
+/// re-upserted points lose them, another repo's routes/topics may have
+/// changed, and a watch pass only re-parses the changed files.
+pub(crate) async fn link_across_repos(ctx: &IndexContext<'_>) {
+    link_call_graph(ctx).await;
+    link_topics(ctx).await;
+    link_route_handlers(ctx).await;
+}
+
+/// Payload keys read to resolve the cross-repo call graph.
+const CALL_GRAPH_FIELDS: &[&str] = &["repo", "type", "symbol_name", "metadata", "call_targets"];
+
+/// Resolves every `http_call` of the collection against the other repos'
+/// endpoints (see `transform::call_graph`) and stores the result on the call
+/// points: `call_targets` (`[{repo, route, point_id}]`) plus the flat
+/// `target_routes` / `target_repos` for `match` filters. Only calls whose
+/// targets changed are written.
+async fn link_call_graph(ctx: &IndexContext<'_>) {
+    let filter = json!({
+        "must": [{ "key": "type", "match": { "any": ["endpoint", "http_call"] } }]
+    });
+    let points = match ctx
+        .qdrant
+        .scroll_points(&ctx.collection, filter, CALL_GRAPH_FIELDS)
+        .await
+    {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("[call_graph] error: {e:#}");
             return;
         }
-        eprintln!("[2/4] Normalising {} documents", all_docs.len());
+    };
+    let stored: HashMap<&str, &serde_json::Value> = points
+        .iter()
+        .map(|p| (p.id.as_str(), &p.payload["call_targets"]))
+        .collect();
 
-        // 4) normalize
-        let normalizer = DocNormalizer::default();
-        let norm_docs: Vec<NormalizedDoc> = all_docs
-            .into_iter()
-            .map(|d| normalizer.normalize(d))
-            .collect();
-        eprintln!("[3/4] Embedding {} docs", norm_docs.len());
+    let updates: Vec<(String, serde_json::Value)> = resolve_calls(&points)
+        .into_iter()
+        .filter(|(id, targets)| stored.get(id.as_str()).copied() != Some(&json!(targets)))
+        .map(|(id, targets)| {
+            let routes: BTreeSet<&str> = targets.iter().map(|t| t.route.as_str()).collect();
+            let repos: BTreeSet<&str> = targets.iter().map(|t| t.repo.as_str()).collect();
+            let payload = json!({
+                "call_targets": targets,
+                "target_routes": routes,
+                "target_repos": repos,
+            });
+            (id, payload)
+        })
+        .collect();
+    if updates.is_empty() {
+        return;
+    }
+
+    let count = updates.len();
+    match ctx
+        .qdrant
+        .set_payloads(&ctx.collection, updates, ctx.config.indexing.upsert_batch)
+        .await
+    {
+        Ok(()) => eprintln!("[call_graph] relinked {count} http call(s)"),
+        Err(e) => eprintln!("[call_graph] error: {e:#}"),
+    }
+}
 
-        let pb = ProgressBar::new_spinner();
-        pb.set_style(ProgressStyle::with_template("{prefix} {spinner} {wide_msg}").unwrap());
-        pb.set_prefix("[3/4]");
-        pb.set_message("Embedding and upserting...");
-        pb.enable_steady_tick(Duration::from_millis(100));
+/// Payload keys read to build the topic graph.
+const TOPIC_FIELDS: &[&str] = &["repo", "type", "metadata", "publishers", "subscribers"];
 
-        // 5) embed + 6) upsert (batched)
-        if let Err(e) = embed_and_upsert(collection, &norm_docs, embedder, qdrant).await {
-            eprintln!("[index] error: {e:#}");
+/// Stores on every `topic` point the repos publishing to / subscribing to its
+/// topic (see `transform::topic_graph`) as `publishers` / `subscribers`. Only
+/// points whose lists changed are written.
+async fn link_topics(ctx: &IndexContext<'_>) {
+    let filter = json!({ "must": [{ "key": "type", "match": { "value": "topic" } }] });
+    let points = match ctx
+        .qdrant
+        .scroll_points(&ctx.collection, filter, TOPIC_FIELDS)
+        .await
+    {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("[topics] error: {e:#}");
             return;
         }
+    };
+    let stored: HashMap<&str, (&serde_json::Value, &serde_json::Value)> = points
+        .iter()
+        .map(|p| {
+            (
+                p.id.as_str(),
+                (&p.payload["publishers"], &p.payload["subscribers"]),
+            )
+        })
+        .collect();
 
-        pb.finish_with_message("Done!");
+    let updates: Vec<(String, serde_json::Value)> = topic_participants(&points)
+        .into_iter()
+        .map(|(id, participants)| {
+            let lists = (
+                json!(participants.publishers),
+                json!(participants.subscribers),
+            );
+            (id, lists)
+        })
+        .filter(|(id, (publishers, subscribers))| {
+            stored.get(id.as_str()).copied() != Some((publishers, subscribers))
+        })
+        .map(|(id, (publishers, subscribers))| {
+            let payload = json!({ "publishers": publishers, "subscribers": subscribers });
+            (id, payload)
+        })
+        .collect();
+    if updates.is_empty() {
+        return;
+    }
 
-        eprintln!(
-            "[index] done: upserted {} documents into '{}'",
-            norm_docs.len(),
-            collection
-        );
+    let count = updates.len();
+    match ctx
+        .qdrant
+        .set_payloads(&ctx.collection, updates, ctx.config.indexing.upsert_batch)
+        .await
+    {
+        Ok(()) => eprintln!("[topics] updated {count} topic usage(s)"),
+        Err(e) => eprintln!("[topics] error: {e:#}"),
     }
 }
 
-async fn embed_and_upsert(
-    collection: &str,
-    docs: &[NormalizedDoc],
-    embedder: &EmbedderClient,
-    qdrant: &QdrantClient,
-) -> Result<()> {
+/// Payload keys read to link endpoints to their handlers.
+const ROUTE_HANDLER_FIELDS: &[&str] = &[
+    "repo",
+    "file_path",
+    "type",
+    "symbol_name",
+    "qualified_name",
+    "metadata",
+    "handler_point_id",
+];
+
+/// Stores on every `endpoint` point found in code the point id of its
+/// handler function/method (see `transform::route_linker`) as
+/// `handler_point_id`. Only points whose link changed are written.
+async fn link_route_handlers(ctx: &IndexContext<'_>) {
+    let filter = json!({
+        "must": [{ "key": "type", "match": { "any": ["endpoint", "function", "method"] } }]
+    });
+    let points = match ctx
+        .qdrant
+        .scroll_points(&ctx.collection, filter, ROUTE_HANDLER_FIELDS)
+        .await
+    {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("[route_handlers] error: {e:#}");
+            return;
+        }
+    };
+    let stored: HashMap<&str, &serde_json::Value> = points
+        .iter()
+        .map(|p| (p.id.as_str(), &p.payload["handler_point_id"]))
+        .collect();
+
+    let updates: Vec<(String, serde_json::Value)> = route_handlers(&points)
+        .into_iter()
+        .filter(|(id, handler)| stored.get(id.as_str()).copied() != Some(&json!(handler)))
+        .map(|(id, handler)| (id, json!({ "handler_point_id": handler })))
+        .collect();
+    if updates.is_empty() {
+        return;
+    }
+
+    let count = updates.len();
+    match ctx
+        .qdrant
+        .set_payloads(&ctx.collection, updates, ctx.config.indexing.upsert_batch)
+        .await
+    {
+        Ok(()) => eprintln!("[route_handlers] relinked {count} endpoint(s)"),
+        Err(e) => eprintln!("[route_handlers] error: {e:#}"),
+    }
+}
+
+/// What is already stored for a point, as far as change detection cares.
+#[derive(Debug, Clone, PartialEq)]
+struct ExistingPoint {
+    file_path: String,
+    symbol_name: String,
+    kind: String,
+    /// Part of the embedding input, so a change forces re-embedding.
+    qualified_name: String,
+    hash_source: String,
+    embed_model: String,
+    /// Fingerprint of the template the input was rendered with.
+    embed_template: String,
+    line_start: u64,
+    line_end: u64,
+    /// Not embedded; a change only updates the payload.
+    metadata: serde_json::Map<String, serde_json::Value>,
+    /// 0 for points written before the field existed.
+    id_scheme: u64,
+}
+
+/// Payload keys fetched for change detection and the prune report.
+const EXISTING_FIELDS: &[&str] = &[
+    "file_path",
+    "symbol_name",
+    "type",
+    "qualified_name",
+    "hash_source",
+    "embed_model",
+    "embed_template",
+    "line_start",
+    "line_end",
+    "metadata",
+    "id_scheme",
+];
+
+/// Qdrant filter selecting a repo's points, optionally narrowed to some files.
+fn repo_filter(repo: &str, only: Option<&HashSet<String>>) -> serde_json::Value {
+    let mut must = vec![json!({ "key": "repo", "match": { "value": repo } })];
+    if let Some(files) = only {
+        let files: Vec<&String> = files.iter().collect();
+        must.push(json!({ "key": "file_path", "match": { "any": files } }));
+    }
+    json!({ "must": must })
+}
+
+async fn fetch_existing(
+    ctx: &IndexContext<'_>,
+    repo: &str,
+    only: Option<&HashSet<String>>,
+) -> Result<HashMap<String, ExistingPoint>> {
+    let points = ctx
+        .qdrant
+        .scroll_points(&ctx.collection, repo_filter(repo, only), EXISTING_FIELDS)
+        .await?;
+
+    Ok(points
+        .into_iter()
+        .map(|p| {
+            let str_field = |k: &str| p.payload[k].as_str().unwrap_or_default().to_string();
+            let num_field = |k: &str| p.payload[k].as_u64().unwrap_or_default();
+            let existing = ExistingPoint {
+                file_path: str_field("file_path"),
+                symbol_name: str_field("symbol_name"),
+                kind: str_field("type"),
+                qualified_name: str_field("qualified_name"),
+                hash_source: str_field("hash_source"),
+                embed_model: str_field("embed_model"),
+                embed_template: str_field("embed_template"),
+                line_start: num_field("line_start"),
+                line_end: num_field("line_end"),
+                metadata: p.payload["metadata"]
+                    .as_object()
+                    .cloned()
+                    .unwrap_or_default(),
+                id_scheme: num_field("id_scheme"),
+            };
+            (p.id, existing)
+        })
+        .collect())
+}
+
+/// Result of diffing freshly parsed documents against stored points.
+#[derive(Debug, Default)]
+struct UpdatePlan {
+    /// New or changed: needs embedding + upsert.
+    embed: Vec<NormalizedDoc>,
+    /// Same content at different lines or with new metadata: payload-only update.
+    moved: Vec<(String, serde_json::Value)>,
+    unchanged: usize,
+}
+
+/// `template_of` gives the fingerprint of a document's embedding template.
+fn plan_updates(
+    docs: Vec<NormalizedDoc>,
+    existing: &HashMap<String, ExistingPoint>,
+    embed_model: &str,
+    template_of: impl Fn(&NormalizedDoc) -> String,
+) -> UpdatePlan {
+    let mut plan = UpdatePlan::default();
+    for d in docs {
+        match existing.get(&d.point_id) {
+            Some(e)
+                if e.hash_source == d.hash_source
+                    && e.embed_model == embed_model
+                    && e.embed_template == template_of(&d)
+                    && e.qualified_name == d.qualified_name.as_deref().unwrap_or_default() =>
+            {
+                let mut patch = serde_json::Map::new();
+                if e.line_start != u64::from(d.line_start) || e.line_end != u64::from(d.line_end) {
+                    patch.insert("line_start".into(), json!(d.line_start));
+                    patch.insert("line_end".into(), json!(d.line_end));
+                }
+                if e.metadata != d.metadata {
+                    patch.insert("metadata".into(), json!(d.metadata));
+                }
+                if patch.is_empty() {
+                    plan.unchanged += 1;
+                } else {
+                    plan.moved
+                        .push((d.point_id, serde_json::Value::Object(patch)));
+                }
+            }
+            _ => plan.embed.push(d),
+        }
+    }
+    plan
+}
+
+async fn embed_and_upsert(ctx: &IndexContext<'_>, docs: &[NormalizedDoc]) -> Result<()> {
+    let config = ctx.config;
     let mut start = 0usize;
     while start < docs.len() {
-        let end = (start + EMBED_BATCH).min(docs.len());
+        let end = (start + config.indexing.embed_batch).min(docs.len());
         let batch = &docs[start..end];
 
-        // build canonical texts
-        let inputs: Vec<String> = batch.iter().map(build_embedding_input).collect();
+        // payloads, and the inputs rendered from them
+        let mut payloads = Vec::with_capacity(batch.len());
+        let mut inputs = Vec::with_capacity(batch.len());
+        for d in batch {
+            let (language, template) = ctx.template_of(d);
+            let payload = point_payload(
+                d,
+                &config.embedder.model,
+                ctx.templates.fingerprint(template),
+            );
+            let input = ctx
+                .templates
+                .render(template, &payload, language)
+                .with_context(|| {
+                    format!(
+                        "rendering template '{template}' for {}::{} failed",
+                        d.file_path, d.symbol_name
+                    )
+                })?;
+            payloads.push(payload);
+            inputs.push(input);
+        }
 
         // embed (single call per batch)
-        let vectors = embedder
+        let vectors = ctx
+            .embedder
             .embed_texts(&inputs)
             .await
             .with_context(|| format!("embed_texts failed on range [{start}..{end})"))?;
@@ -222,36 +792,25 @@ async fn embed_and_upsert(
         // map to Qdrant points
         let points: Vec<PointWrite> = batch
             .iter()
-            .zip(vectors.into_iter())
-            .map(|(d, vec)| {
-                let id = deterministic_point_id(&d.repo, &d.file_path, &d.symbol_name, &d.kind);
-                let payload = json!({
-                    "repo": d.repo,
-                    "file_path": d.file_path,
-                    "symbol_name": d.symbol_name,
-                    "type": d.kind, // "function" | "method" | ...
-                    "code": d.code,
-                    "line_start": d.line_start,
-                    "line_end": d.line_end,
-                    "parent_type": d.parent_type,
-                    "signature": d.signature,
-                    "doc_comment": d.doc_comment,
-                    "hash_source": d.hash_source,
-                    "timestamp_indexed": d.timestamp_indexed.timestamp(),
-                });
-                PointWrite {
-                    id,
-                    vector: vec,
-                    payload,
-                }
+            .zip(payloads)
+            .zip(vectors)
+            .map(|((d, payload), vec)| PointWrite {
+                id: d.point_id.clone(),
+                vector: vec,
+                payload,
             })
             .collect();
 
         eprintln!("[4/4] Upserting [{start}..{end}");
 
         // upsert (may split further if points > UPSERT_BATCH)
-        qdrant
-            .upsert_points_batched(collection, points, UPSERT_BATCH, UPSERT_RETRIES)
+        ctx.qdrant
+            .upsert_points_batched(
+                &ctx.collection,
+                points,
+                config.indexing.upsert_batch,
+                config.indexing.upsert_retries,
+            )
             .await
             .with_context(|| format!("upsert_points_batched failed on range [{start}..{end})"))?;
 
@@ -260,36 +819,34 @@ async fn embed_and_upsert(
     Ok(())
 }
 
-/// Canonical embedding template (consistent with earlier design).
-fn build_embedding_input(d: &NormalizedDoc) -> String {
-    let parent = d.parent_type.as_deref().unwrap_or("");
-    let signature = d.signature.as_deref().unwrap_or("");
-    let docs = d.doc_comment.as_deref().unwrap_or("");
-
-    format!(
-        "repo: {repo}\npath: {path}\ntype: {kind}\nsymbol: {sym}\nparent: {parent}\nlines: {ls}-{le}\n\n[DOC]\n{doc}\n\n[SIGNATURE]\n{sig}\n\n[CODE]\n{code}",
-        repo = d.repo,
-        path = d.file_path,
-        kind = d.kind,
-        sym = d.symbol_name,
-        parent = parent,
-        ls = d.line_start,
-        le = d.line_end,
-        doc = docs,
-        sig = signature,
-        code = d.code
-    )
+/// Qdrant payload of a document; also the context of its embedding template.
+fn point_payload(d: &NormalizedDoc, embed_model: &str, embed_template: &str) -> serde_json::Value {
+    json!({
+        "repo": d.repo,
+        "file_path": d.file_path,
+        "symbol_name": d.symbol_name,
+        "type": d.kind, // "function" | "method" | ...
+        "code": d.code,
+        "line_start": d.line_start,
+        "line_end": d.line_end,
+        "parent_type": d.parent_type,
+        "module_path": d.module_path,
+        "qualified_name": d.qualified_name,
+        "annotations": d.annotations,
+        // flat copy for `match` filters
+        "annotation_names": d.annotations.iter().map(|a| &a.name).collect::<Vec<_>>(),
+        "metadata": d.metadata,
+        "signature": d.signature,
+        "doc_comment": d.doc_comment,
+        "hash_source": d.hash_source,
+        "embed_model": embed_model,
+        "embed_template": embed_template,
+        "id_scheme": ID_SCHEME,
+        "timestamp_indexed": d.timestamp_indexed.timestamp(),
+    })
 }
 
-pub fn query_repo_from_args() -> Result<(String, String)> {
-    let mut args = env::args().skip(1);
-    let query = args.next().unwrap();
-    let repo = args.next().unwrap();
-
-    Ok((query, repo))
-}
-
-fn repo_name(root: &PathBuf) -> Result<String> {
+fn repo_name(root: &Path) -> Result<String> {
     let name = root
         .file_name()
         .and_then(|s| s.to_str())
@@ -309,3 +866,140 @@ fn repo_name(root: &PathBuf) -> Result<String> {
         .collect::<String>();
     Ok(norm.trim_matches('-').to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::rust_parser::{Document, DocumentKind};
+
+    fn doc(name: &str, code: &str, line_start: u32) -> NormalizedDoc {
+        let d = Document {
+            repo: "billing".into(),
+            file_path: "src/lib.rs".into(),
+            symbol_name: name.into(),
+            kind: DocumentKind::Function,
+            signature: None,
+            doc_comment: None,
+            code: code.into(),
+            parent_type: None,
+            module_path: None,
+            qualified_name: Some(format!("billing::{name}")),
+            annotations: Vec::new(),
+            metadata: Default::default(),
+            line_start,
+            line_end: line_start + 1,
+        };
+        DocNormalizer::default().normalize(d, 0)
+    }
+
+    fn template(_: &NormalizedDoc) -> String {
+        "t".into()
+    }
+
+    fn stored(d: &NormalizedDoc, model: &str) -> ExistingPoint {
+        ExistingPoint {
+            file_path: d.file_path.clone(),
+            symbol_name: d.symbol_name.clone(),
+            kind: d.kind.clone(),
+            qualified_name: d.qualified_name.clone().unwrap_or_default(),
+            hash_source: d.hash_source.clone(),
+            embed_model: model.into(),
+            embed_template: template(d),
+            line_start: d.line_start.into(),
+            line_end: d.line_end.into(),
+            metadata: d.metadata.clone(),
+            id_scheme: ID_SCHEME.into(),
+        }
+    }
+
+    #[test]
+    fn only_new_or_changed_docs_are_embedded() {
+        let same = doc("same", "fn same() {}", 1);
+        let moved = doc("moved", "fn moved() {}", 10);
+        let changed = doc("changed", "fn changed() { 1 }", 20);
+
+        let mut existing = HashMap::new();
+        existing.insert(same.point_id.clone(), stored(&same, "m"));
+        let mut moved_before = stored(&moved, "m");
+        moved_before.line_start = 3;
+        existing.insert(moved.point_id.clone(), moved_before);
+        let mut changed_before = stored(&changed, "m");
+        changed_before.hash_source = "stale".into();
+        existing.insert(changed.point_id.clone(), changed_before);
+
+        let fresh = doc("fresh", "fn fresh() {}", 30);
+        let plan = plan_updates(vec![same, moved, changed, fresh], &existing, "m", template);
+
+        let embedded: Vec<&str> = plan.embed.iter().map(|d| d.symbol_name.as_str()).collect();
+        assert_eq!(embedded, vec!["changed", "fresh"]);
+        assert_eq!(plan.moved.len(), 1);
+        assert_eq!(plan.moved[0].1["line_start"], 10);
+        assert_eq!(plan.unchanged, 1);
+    }
+
+    #[test]
+    fn qualified_name_change_forces_reembedding() {
+        let d = doc("f", "fn f() {}", 1);
+        let mut before = stored(&d, "m");
+        before.qualified_name = "billing::old::f".into();
+        let mut existing = HashMap::new();
+        existing.insert(d.point_id.clone(), before);
+        let plan = plan_updates(vec![d], &existing, "m", template);
+        assert_eq!(plan.embed.len(), 1);
+    }
+
+    #[test]
+    fn metadata_change_is_a_payload_update() {
+        let mut d = doc("f", "fn f() {}", 1);
+        let mut existing = HashMap::new();
+        existing.insert(d.point_id.clone(), stored(&d, "m"));
+        d.metadata.insert("status".into(), json!("accepted"));
+        let plan = plan_updates(vec![d], &existing, "m", template);
+        assert!(plan.embed.is_empty());
+        assert_eq!(plan.moved.len(), 1);
+        assert_eq!(
+            plan.moved[0].1,
+            json!({ "metadata": { "status": "accepted" } })
+        );
+    }
+
+    #[test]
+    fn model_change_forces_reembedding() {
+        let d = doc("f", "fn f() {}", 1);
+        let mut existing = HashMap::new();
+        existing.insert(d.point_id.clone(), stored(&d, "old-model"));
+        let plan = plan_updates(vec![d], &existing, "new-model", template);
+        assert_eq!(plan.embed.len(), 1);
+    }
+
+    #[test]
+    fn template_change_forces_reembedding() {
+        let d = doc("f", "fn f() {}", 1);
+        let mut existing = HashMap::new();
+        existing.insert(d.point_id.clone(), stored(&d, "m"));
+        let plan = plan_updates(vec![d], &existing, "m", |_| "edited".into());
+        assert_eq!(plan.embed.len(), 1);
+    }
+
+    #[test]
+    fn stale_points_exclude_produced_and_failed_files() {
+        let kept = doc("kept", "fn kept() {}", 1);
+        let renamed_away = doc("old_name", "fn old_name() {}", 5);
+        let mut unparsable = stored(&doc("x", "fn x() {}", 1), "m");
+        unparsable.file_path = "src/broken.rs".into();
+
+        let mut existing = HashMap::new();
+        existing.insert(kept.point_id.clone(), stored(&kept, "m"));
+        existing.insert(renamed_away.point_id.clone(), stored(&renamed_away, "m"));
+        existing.insert("broken-id".to_string(), unparsable);
+
+        let outcome = PassOutcome {
+            produced: HashSet::from([kept.point_id.clone()]),
+            failed_files: HashSet::from(["src/broken.rs".to_string()]),
+        };
+        let stale = stale_points(&existing, &outcome);
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].0, renamed_away.point_id);
+        assert_eq!(stale[0].1.symbol_name, "old_name");
+    }
+}
@@ -4,8 +4,20 @@
 //!
 //! fn inference(query: &str, k: u8) -> Vec<Document>
 //!   1. Embeds the query text via your embedding server
-//!   2. Queries Qdrant's /points/search endpoint
-//!   3. Returns the top-k payloads decoded as Documents
+//!   2. Queries Qdrant's /points/search endpoint, optionally restricted to a
+//!      repo and/or symbols carrying an annotation/decorator (`annotation_names`)
+//!   3. Returns the top-k payloads decoded as Documents, chunks of a symbol
+//!      too large to embed whole stitched back into the symbol
+//!
+//! `lookup` is the exact counterpart: points whose `qualified_name` equals
+//! the given path, straight from Qdrant (no embedding, no LLM). `routes`
+//! lists the `endpoint` points the same way; `callers` and `dependencies`
+//! walk the cross-repo HTTP call graph (`http_call` points and the
+//! `call_targets` stored on them at indexing time); `topic_usages` answers
+//! "who consumes / publishes `order.created`" from the `topic` points, and
+//! `table_usages` "who owns / reads / writes `payments.ledger_entries`" from
+//! the `table` and `sql_query` points. `preview_embedding` renders the
+//! embedding input of a symbol's points with the current templates.
 //!
 //! Assumes:
 //! - Same model + vector size as your indexer
@@ -13,53 +25,69 @@
 
 use crate::client::embedder_client::EmbedderClient;
 use crate::client::llm_client::ask_llm;
+use crate::client::qdrant_client::{QdrantClient, ScrolledPoint};
+use crate::config::Config;
+use crate::ingest::language::LanguageRegistry;
+use crate::ingest::sql::same_table;
+use crate::transform::call_graph::normalize_path;
+use crate::transform::embedding_template::EmbeddingTemplates;
+use crate::transform::topic_graph::topics_overlap;
 use anyhow::{Context, Result};
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
 
-const QDRANT_URL: &str = "http://localhost:6333";
-const EMBED_MODEL: &str = "text-embedding-embeddinggemma-300m"; // must match what you used to index
-const VECTOR_SIZE: usize = 768;
-
-pub async fn rag(query: &str, collection: &str, repo: &str) -> Result<Vec<QdrantPoint>> {
+pub async fn rag(
+    config: &Config,
+    query: &str,
+    collection: &str,
+    repo: &str,
+    annotation: Option<&str>,
+) -> Result<Vec<QdrantPoint>> {
     let repo = repo.trim();
     let collection = collection.trim();
 
     // 1. embed query
-    let embedder = EmbedderClient::new(EMBED_MODEL, Some(VECTOR_SIZE))?;
+    // must match the model + vector size used to index
+    let embedder = EmbedderClient::new(
+        &config.embedder.base_url,
+        &config.embedder.model,
+        Some(config.embedder.vector_size),
+    )?
+    .with_timeout_secs(config.embedder.timeout_secs)?;
     let vec = embedder.embed_text(query.trim()).await?;
 
     // 2. search Qdrant
-    let url = format!("{QDRANT_URL}/collections/{collection}/points/query");
-    let body = if repo == "*" {
-        json!({
-            "query": {
-                "recommend": {
-                    "positive": [vec]
-                }
-            },
-            "with_payload": ["code", "repo"],
-            "limit": 3,
-        })
-    } else {
-        json!({
-            "query": {
-                "recommend": {
-                    "positive": [vec]
-                }
-            },
-            "filter": {
-                "must": {
-                    "key": "repo",
-                    "match": {
-                        "value": repo
-                    },
-                }
-            },
-            "with_payload": ["code", "repo"],
-            "limit": 3,
-        })
-    };
+    let url = format!(
+        "{}/collections/{collection}/points/query",
+        config.qdrant.url.trim_end_matches('/')
+    );
+    let mut must = Vec::new();
+    if repo != "*" {
+        must.push(json!({ "key": "repo", "match": { "value": repo } }));
+    }
+    if let Some(annotation) = annotation.map(str::trim).filter(|a| !a.is_empty()) {
+        must.push(json!({ "key": "annotation_names", "match": { "value": annotation } }));
+    }
+    let mut body = json!({
+        "query": {
+            "recommend": {
+                "positive": [vec]
+            }
+        },
+        // file_path + signature (a heading path for doc sections) let the
+        // answer cite where code and design notes come from; metadata and
+        // lines are needed to reassemble chunks
+        "with_payload": [
+            "code", "repo", "file_path", "type", "signature",
+            "metadata", "line_start", "line_end",
+        ],
+        "limit": 3,
+    });
+    if !must.is_empty() {
+        body["filter"] = json!({ "must": must });
+    }
 
     let resp = reqwest::Client::new()
         .post(&url)
@@ -77,7 +105,9 @@ pub async fn rag(query: &str, collection: &str, repo: &str) -> Result<Vec<Qdrant
     let result: QdrantSearchResponse = resp.json().await?;
 
     // Get response
-    let docs = result.result.points.into_iter().collect();
+    let qdrant =
+        QdrantClient::new(&config.qdrant.url)?.with_timeout_secs(config.qdrant.timeout_secs)?;
+    let docs = reassemble_chunks(&qdrant, collection, result.result.points).await?;
 
     // "Augment" response with natural language
     let prompt = format!(
@@ -95,9 +125,9 @@ so... can you give me a response to my query?
     "#
     );
     ask_llm(
-        "http://localhost:1234/v1/chat/completions",
-        "lm-studio",            // LM Studio typically uses this as API key
-        "qwen/qwen3-coder-30b", // Your model name
+        &config.llm.url,
+        &config.llm.api_key,
+        &config.llm.model,
         &prompt,
     )
     .await;
@@ -105,10 +135,451 @@ so... can you give me a response to my query?
     Ok(docs)
 }
 
-#[derive(Debug, Serialize)]
-struct QdrantSearchRequest {
-    vector: Vec<f32>,
-    limit: usize,
+/// Payload keys returned by `lookup`.
+const LOOKUP_FIELDS: &[&str] = &[
+    "repo",
+    "file_path",
+    "qualified_name",
+    "type",
+    "signature",
+    "doc_comment",
+    "line_start",
+    "line_end",
+    "code",
+    "metadata",
+];
+
+/// Points whose `qualified_name` is exactly `qualified_name`.
+pub async fn lookup(
+    config: &Config,
+    collection: &str,
+    qualified_name: &str,
+) -> Result<Vec<ScrolledPoint>> {
+    let qdrant =
+        QdrantClient::new(&config.qdrant.url)?.with_timeout_secs(config.qdrant.timeout_secs)?;
+    let filter = json!({
+        "must": [{ "key": "qualified_name", "match": { "value": qualified_name.trim() } }]
+    });
+    let points = qdrant
+        .scroll_points(collection.trim(), filter, LOOKUP_FIELDS)
+        .await
+        .with_context(|| format!("lookup of '{qualified_name}' failed"))?;
+    Ok(merge_chunks(points))
+}
+
+/// Payload keys of the chunks stitched back together.
+const CHUNK_FIELDS: &[&str] = &[
+    "repo",
+    "file_path",
+    "line_start",
+    "line_end",
+    "code",
+    "metadata",
+];
+
+/// Search hits with each chunk (see `ingest::chunker`) replaced by its whole
+/// symbol, stitched from all its chunks. Later hits on the same symbol are
+/// dropped, so it keeps the rank of its best chunk.
+async fn reassemble_chunks(
+    qdrant: &QdrantClient,
+    collection: &str,
+    hits: Vec<QdrantPoint>,
+) -> Result<Vec<QdrantPoint>> {
+    let mut seen = HashSet::new();
+    let mut out = Vec::with_capacity(hits.len());
+    for mut hit in hits {
+        let Some(parent) = chunk_parent(&hit.payload) else {
+            out.push(hit);
+            continue;
+        };
+        if !seen.insert(parent.clone()) {
+            continue;
+        }
+        let filter = json!({
+            "must": [
+                { "key": "repo", "match": { "value": hit.payload["repo"] } },
+                { "key": "file_path", "match": { "value": hit.payload["file_path"] } },
+                { "key": "metadata.chunk_of", "match": { "value": hit.payload["metadata"]["chunk_of"] } },
+            ]
+        });
+        let mut chunks = qdrant
+            .scroll_points(collection, filter, CHUNK_FIELDS)
+            .await
+            .with_context(|| format!("fetching the chunks of '{parent}' failed"))?;
+        chunks.retain(|c| chunk_parent(&c.payload).as_ref() == Some(&parent));
+        stitch(&mut hit.payload, chunks.iter().map(|c| &c.payload));
+        out.push(hit);
+    }
+    Ok(out)
+}
+
+/// `points` with the chunks of each symbol merged into the first of them.
+fn merge_chunks(points: Vec<ScrolledPoint>) -> Vec<ScrolledPoint> {
+    let mut groups: HashMap<String, Vec<Value>> = HashMap::new();
+    for p in &points {
+        if let Some(parent) = chunk_parent(&p.payload) {
+            groups.entry(parent).or_default().push(p.payload.clone());
+        }
+    }
+    points
+        .into_iter()
+        .filter_map(|mut p| match chunk_parent(&p.payload) {
+            None => Some(p),
+            Some(parent) => {
+                let chunks = groups.remove(&parent)?;
+                stitch(&mut p.payload, &chunks);
+                Some(p)
+            }
+        })
+        .collect()
+}
+
+/// Identifies the symbol a chunk belongs to; `None` for whole documents.
+fn chunk_parent(payload: &Value) -> Option<String> {
+    let meta = &payload["metadata"];
+    let chunk_of = meta["chunk_of"].as_str()?;
+    Some(format!(
+        "{}:{}:{chunk_of}@{}",
+        payload["repo"].as_str().unwrap_or_default(),
+        payload["file_path"].as_str().unwrap_or_default(),
+        meta["parent_lines"],
+    ))
+}
+
+/// Sets `payload`'s code to its chunks joined in order (the lines each repeats
+/// from the previous one dropped; pieces of a line cut for length glued back)
+/// and its lines to the whole symbol's, and removes the chunk keys from its
+/// metadata.
+fn stitch<'a>(payload: &mut Value, chunks: impl IntoIterator<Item = &'a Value>) {
+    let mut chunks: Vec<&Value> = chunks.into_iter().collect();
+    chunks.sort_by_key(|c| c["metadata"]["chunk_index"].as_u64());
+
+    let mut lines: Vec<String> = Vec::new();
+    // first line not yet in `lines`
+    let mut next_line = 0u64;
+    for c in chunks {
+        let code = c["code"].as_str().unwrap_or_default();
+        let start = c["line_start"].as_u64().unwrap_or_default();
+        let end = c["line_end"].as_u64().unwrap_or_default();
+        if end < next_line {
+            // a further piece of the last line (see `ingest::chunker`)
+            if let Some(last) = lines.last_mut() {
+                last.push_str(code);
+            }
+            continue;
+        }
+        let skip = next_line.saturating_sub(start) as usize;
+        lines.extend(code.split('\n').skip(skip).map(str::to_string));
+        next_line = end + 1;
+    }
+    let code = lines.join("\n");
+
+    let parent_lines = payload["metadata"]["parent_lines"].clone();
+    payload["code"] = json!(code);
+    payload["line_start"] = parent_lines[0].clone();
+    payload["line_end"] = parent_lines[1].clone();
+    if let Some(meta) = payload["metadata"].as_object_mut() {
+        for key in ["chunk_index", "chunk_count", "chunk_of", "parent_lines"] {
+            meta.remove(key);
+        }
+    }
+}
+
+/// Payload keys returned by `routes`.
+const ROUTE_FIELDS: &[&str] = &["repo", "file_path", "line_start", "metadata"];
+
+/// Every endpoint (from code and API specs), optionally of one repo, sorted
+/// by repo, path and method.
+pub async fn routes(config: &Config, collection: &str, repo: &str) -> Result<Vec<ScrolledPoint>> {
+    let qdrant =
+        QdrantClient::new(&config.qdrant.url)?.with_timeout_secs(config.qdrant.timeout_secs)?;
+    let mut must = vec![json!({ "key": "type", "match": { "value": "endpoint" } })];
+    if repo.trim() != "*" {
+        must.push(json!({ "key": "repo", "match": { "value": repo.trim() } }));
+    }
+    let mut points = qdrant
+        .scroll_points(collection.trim(), json!({ "must": must }), ROUTE_FIELDS)
+        .await
+        .with_context(|| format!("listing routes of '{collection}' failed"))?;
+
+    let key = |p: &ScrolledPoint| {
+        let field = |k: &str| {
+            p.payload["metadata"][k]
+                .as_str()
+                .unwrap_or_default()
+                .to_string()
+        };
+        (
+            p.payload["repo"].as_str().unwrap_or_default().to_string(),
+            field("path"),
+            field("method"),
+        )
+    };
+    points.sort_by_key(key);
+    Ok(points)
+}
+
+/// Payload keys returned by `callers` and `dependencies`.
+const CALL_FIELDS: &[&str] = &[
+    "repo",
+    "file_path",
+    "line_start",
+    "metadata",
+    "call_targets",
+];
+
+/// HTTP calls (from other repos) resolved to the route `METHOD /path`, e.g.
+/// `POST /orders` or `GET /orders/{id}` (parameter names don't matter; a bare
+/// path matches every method). `repo` restricts the route to one service.
+pub async fn callers(
+    config: &Config,
+    collection: &str,
+    route: &str,
+    repo: &str,
+) -> Result<Vec<ScrolledPoint>> {
+    let (method, path) = match route.trim().split_once(' ') {
+        Some((method, path)) => (Some(method.to_ascii_uppercase()), path.trim()),
+        None => (None, route.trim()),
+    };
+    let path = normalize_path(path);
+
+    // the endpoints named by `route`, then the calls linked to them
+    let endpoint_ids: Vec<String> = routes(config, collection, repo)
+        .await?
+        .into_iter()
+        .filter(|p| {
+            let meta = &p.payload["metadata"];
+            normalize_path(meta["path"].as_str().unwrap_or_default()) == path
+                && method
+                    .as_deref()
+                    .is_none_or(|m| meta["method"] == m || meta["method"] == "ANY")
+        })
+        .map(|p| p.id)
+        .collect();
+    if endpoint_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let qdrant =
+        QdrantClient::new(&config.qdrant.url)?.with_timeout_secs(config.qdrant.timeout_secs)?;
+    let filter = json!({
+        "must": [
+            { "key": "type", "match": { "value": "http_call" } },
+            { "key": "call_targets[].point_id", "match": { "any": endpoint_ids } },
+        ]
+    });
+    let mut points = qdrant
+        .scroll_points(collection.trim(), filter, CALL_FIELDS)
+        .await
+        .with_context(|| format!("listing callers of '{route}' failed"))?;
+    points.sort_by_key(call_location);
+    Ok(points)
+}
+
+/// The HTTP calls made by `repo`, grouped by the repo they reach; calls not
+/// resolved to any indexed service are grouped under `EXTERNAL`.
+pub async fn dependencies(
+    config: &Config,
+    collection: &str,
+    repo: &str,
+) -> Result<BTreeMap<String, Vec<ScrolledPoint>>> {
+    let qdrant =
+        QdrantClient::new(&config.qdrant.url)?.with_timeout_secs(config.qdrant.timeout_secs)?;
+    let filter = json!({
+        "must": [
+            { "key": "type", "match": { "value": "http_call" } },
+            { "key": "repo", "match": { "value": repo.trim() } },
+        ]
+    });
+    let mut points = qdrant
+        .scroll_points(collection.trim(), filter, CALL_FIELDS)
+        .await
+        .with_context(|| format!("listing dependencies of '{repo}' failed"))?;
+    points.sort_by_key(call_location);
+
+    let mut grouped: BTreeMap<String, Vec<ScrolledPoint>> = BTreeMap::new();
+    for p in points {
+        let mut targets: Vec<String> = p.payload["call_targets"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|t| t["repo"].as_str().map(str::to_string))
+            .collect();
+        targets.dedup();
+        if targets.is_empty() {
+            targets.push(EXTERNAL.to_string());
+        }
+        for target in targets {
+            grouped.entry(target).or_default().push(p.clone());
+        }
+    }
+    Ok(grouped)
+}
+
+/// Payload keys returned by `topic_usages`.
+const TOPIC_FIELDS: &[&str] = &[
+    "repo",
+    "file_path",
+    "line_start",
+    "metadata",
+    "publishers",
+    "subscribers",
+];
+
+/// Usages of `topic` with the given role (`publish` / `subscribe`), sorted by
+/// repo and location. NATS wildcards count: `orders.*` subscribers consume
+/// `orders.created`, and asking for `orders.*` lists the concrete subjects'
+/// usages too.
+pub async fn topic_usages(
+    config: &Config,
+    collection: &str,
+    topic: &str,
+    role: &str,
+) -> Result<Vec<ScrolledPoint>> {
+    let qdrant =
+        QdrantClient::new(&config.qdrant.url)?.with_timeout_secs(config.qdrant.timeout_secs)?;
+    let filter = json!({
+        "must": [
+            { "key": "type", "match": { "value": "topic" } },
+            { "key": "metadata.role", "match": { "value": role } },
+        ]
+    });
+    let mut points = qdrant
+        .scroll_points(collection.trim(), filter, TOPIC_FIELDS)
+        .await
+        .with_context(|| format!("listing usages of '{topic}' failed"))?;
+    points.retain(|p| {
+        let used = p.payload["metadata"]["topic"].as_str().unwrap_or_default();
+        topics_overlap(topic.trim(), used)
+    });
+    points.sort_by_key(call_location);
+    Ok(points)
+}
+
+/// Payload keys returned by `table_usages`.
+const TABLE_FIELDS: &[&str] = &["repo", "file_path", "line_start", "type", "metadata"];
+
+/// Definitions (`table` points: migrations, changelogs, diesel schemas) and
+/// queries (`sql_query` points) of `table`, definitions first, each sorted by
+/// repo and location. `ledger_entries` also matches `payments.ledger_entries`.
+pub async fn table_usages(
+    config: &Config,
+    collection: &str,
+    table: &str,
+) -> Result<Vec<ScrolledPoint>> {
+    let qdrant =
+        QdrantClient::new(&config.qdrant.url)?.with_timeout_secs(config.qdrant.timeout_secs)?;
+    let filter = json!({
+        "must": [{ "key": "type", "match": { "any": ["table", "sql_query"] } }]
+    });
+    let mut points = qdrant
+        .scroll_points(collection.trim(), filter, TABLE_FIELDS)
+        .await
+        .with_context(|| format!("listing usages of table '{table}' failed"))?;
+    let table = table.trim();
+    points.retain(|p| {
+        let meta = &p.payload["metadata"];
+        match meta["table"].as_str() {
+            Some(name) => {
+                let name = match meta["schema"].as_str() {
+                    Some(schema) => format!("{schema}.{name}"),
+                    None => name.to_string(),
+                };
+                same_table(table, &name)
+            }
+            None => meta["tables"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .any(|t| same_table(table, t)),
+        }
+    });
+    points.sort_by_key(|p| (p.payload["type"] != "table", call_location(p)));
+    Ok(points)
+}
+
+/// Payload keys a template can use (see `transform::embedding_template`).
+const PREVIEW_FIELDS: &[&str] = &[
+    "repo",
+    "file_path",
+    "symbol_name",
+    "type",
+    "code",
+    "line_start",
+    "line_end",
+    "parent_type",
+    "module_path",
+    "qualified_name",
+    "annotations",
+    "annotation_names",
+    "metadata",
+    "signature",
+    "doc_comment",
+];
+
+/// A point and the text its template renders for embedding.
+pub struct EmbeddingPreview {
+    pub point: ScrolledPoint,
+    pub template: String,
+    pub input: String,
+}
+
+/// Embedding inputs of the points (chunks included) named `qualified_name`,
+/// rendered with the templates of `template_dir`, or of
+/// `embedder.template_dir` without one.
+pub async fn preview_embedding(
+    config: &Config,
+    collection: &str,
+    qualified_name: &str,
+    template_dir: Option<&Path>,
+) -> Result<Vec<EmbeddingPreview>> {
+    let templates =
+        EmbeddingTemplates::load(template_dir.or(config.embedder.template_dir.as_deref()))?;
+    let registry = LanguageRegistry::default();
+    let qdrant =
+        QdrantClient::new(&config.qdrant.url)?.with_timeout_secs(config.qdrant.timeout_secs)?;
+    let filter = json!({
+        "must": [{ "key": "qualified_name", "match": { "value": qualified_name.trim() } }]
+    });
+    let mut points = qdrant
+        .scroll_points(collection.trim(), filter, PREVIEW_FIELDS)
+        .await
+        .with_context(|| format!("lookup of '{qualified_name}' failed"))?;
+    points.sort_by_key(call_location);
+
+    points
+        .into_iter()
+        .map(|point| {
+            let file_path = point.payload["file_path"].as_str().unwrap_or_default();
+            let language = registry.for_path(Path::new(file_path)).map(|l| l.name());
+            let kind = point.payload["type"].as_str().unwrap_or_default();
+            let template = templates.select(kind, language).to_string();
+            let input = templates
+                .render(&template, &point.payload, language)
+                .with_context(|| format!("rendering template '{template}' failed"))?;
+            Ok(EmbeddingPreview {
+                point,
+                template,
+                input,
+            })
+        })
+        .collect()
+}
+
+/// `dependencies` group of calls that reach no indexed service.
+pub const EXTERNAL: &str = "(external)";
+
+/// Repo, file and line of a point, for stable listings.
+fn call_location(p: &ScrolledPoint) -> (String, String, u64) {
+    (
+        p.payload["repo"].as_str().unwrap_or_default().to_string(),
+        p.payload["file_path"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        p.payload["line_start"].as_u64().unwrap_or_default(),
+    )
 }
 
 #[derive(Debug, Deserialize)]
@@ -123,7 +594,132 @@ struct QdrantPoints {
 
 #[derive(Debug, Deserialize)]
 pub struct QdrantPoint {
-    id: String,
-    score: f32,
-    payload: Value,
+    pub id: String,
+    pub score: f32,
+    pub payload: Value,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_merges_chunks_of_a_symbol() {
+        let chunk = |id: &str, index: u64, lines: (u64, u64), code: &str| ScrolledPoint {
+            id: id.into(),
+            payload: json!({
+                "repo": "billing",
+                "file_path": "src/ledger.rs",
+                "line_start": lines.0,
+                "line_end": lines.1,
+                "code": code,
+                "metadata": {
+                    "chunk_index": index,
+                    "chunk_count": 2,
+                    "chunk_of": "billing::ledger::post",
+                    "parent_lines": [10, 14],
+                },
+            }),
+        };
+        let other = ScrolledPoint {
+            id: "c".into(),
+            payload: json!({ "repo": "billing", "code": "fn other() {}", "metadata": {} }),
+        };
+        let merged = merge_chunks(vec![
+            chunk("b", 1, (12, 14), "    let b = 2;\n    a + b\n}"),
+            other,
+            chunk(
+                "a",
+                0,
+                (10, 12),
+                "fn post() {\n    let a = 1;\n    let b = 2;",
+            ),
+        ]);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].id, "b");
+        assert_eq!(
+            merged[0].payload["code"],
+            "fn post() {\n    let a = 1;\n    let b = 2;\n    a + b\n}"
+        );
+        assert_eq!(merged[0].payload["line_start"], 10);
+        assert_eq!(merged[0].payload["line_end"], 14);
+        assert_eq!(merged[0].payload["metadata"], json!({}));
+        assert_eq!(merged[1].payload["code"], "fn other() {}");
+    }
+
+    #[test]
+    fn chunks_stitch_back_into_the_exact_symbol() {
+        use crate::ingest::chunker::{ChunkLimits, chunk_documents};
+        use crate::ingest::language::{LanguageSupport, SourceFile};
+        use crate::ingest::rust_parser::RustLanguage;
+        use crate::transform::doc_normalizer::DocNormalizer;
+
+        let literal = "x".repeat(150);
+        let source = format!(
+            "pub struct Ledger;\n\nimpl Ledger {{\n    pub fn settle(&self, entries: &[i64]) -> i64 {{\n        \
+             let mut total = 0;\n\n        for e in entries {{\n            total += e;\n        }}\n    \n        \
+             let note = \"{literal}\";\n        if total > 0 {{\n            println!(\"{{note}}\");\n        }}\n\n        \
+             total\n    }}\n}}\n"
+        );
+        let file = SourceFile {
+            repo: "billing",
+            file_path: "src/ledger.rs",
+            source: &source,
+        };
+        let docs = RustLanguage.parse_file(&file, false).unwrap();
+        let settle = docs
+            .iter()
+            .find(|d| d.symbol_name == "settle")
+            .unwrap()
+            .clone();
+        let limits = ChunkLimits {
+            max_chars: 60,
+            overlap_lines: 1,
+        };
+        let chunks: Vec<Value> = DocNormalizer::default()
+            .normalize_all(chunk_documents(&RustLanguage, &file, docs, limits))
+            .into_iter()
+            .filter(|n| n.symbol_name == "settle")
+            .map(|n| {
+                json!({
+                    "line_start": n.line_start,
+                    "line_end": n.line_end,
+                    "code": n.code,
+                    "metadata": n.metadata,
+                })
+            })
+            .collect();
+        assert!(chunks.len() > 3);
+
+        let mut payload = chunks[0].clone();
+        stitch(&mut payload, &chunks);
+        assert_eq!(payload["code"], settle.code);
+        assert_eq!(payload["line_start"], settle.line_start);
+        assert_eq!(payload["line_end"], settle.line_end);
+    }
+
+    #[test]
+    fn stitch_glues_pieces_of_a_long_line() {
+        let chunk = |index: u64, lines: (u64, u64), code: &str| {
+            json!({
+                "line_start": lines.0,
+                "line_end": lines.1,
+                "code": code,
+                "metadata": { "chunk_index": index },
+            })
+        };
+        let chunks = [
+            chunk(0, (1, 1), "const BLOB: &str ="),
+            chunk(1, (2, 2), "    \"aaaa"),
+            chunk(2, (2, 2), "bbbb"),
+            chunk(3, (2, 2), "cc\";"),
+            chunk(4, (3, 3), "// end"),
+        ];
+        let mut payload = json!({ "metadata": { "parent_lines": [1, 3] } });
+        stitch(&mut payload, &chunks);
+        assert_eq!(
+            payload["code"],
+            "const BLOB: &str =\n    \"aaaabbbbcc\";\n// end"
+        );
+    }
 }
@@ -0,0 +1,377 @@
+//! chunker.rs
+//!
+//! Splits documents whose code is too large to embed into overlapping chunks.
+//!
+//! Cuts fall on sub-block boundaries of the document's syntax tree: a node over
+//! the limit is replaced by its children (a function by the statements of its
+//! body, a `match` by its arms, an `impl` or class by its members) and
+//! consecutive lines are packed into chunks of at most `max_chars`, cut at the
+//! last boundary that fits. Formats without a grammar (and file summaries) are
+//! cut at blank lines outside fenced (```` ``` ```` / `~~~`) and AsciiDoc
+//! delimited blocks; a block with no boundary that fits is cut between lines,
+//! and a line over the limit on its own (minified or generated code) into
+//! pieces at char boundaries. Each chunk starts `overlap_lines` before the end
+//! of the previous one.
+//!
+//! A chunk is a copy of its parent document (same name, kind, signature and
+//! qualified_name) narrowed to its lines, kept verbatim, with `chunk_index`,
+//! `chunk_count`, `chunk_of` (the parent's qualified name) and `parent_lines`
+//! added to the metadata. Retrieval stitches them back into the exact code
+//! (see `inference::reassemble_chunks`).
+//!
+//! Documents looked up by their metadata (endpoints, calls, topics, tables,
+//! queries, deployments) stay one point each; their code is cut at the limit.
+
+use crate::ingest::language::{LanguageSupport, SourceFile};
+use crate::ingest::markdown_parser::is_asciidoc_delimiter;
+use crate::ingest::rust_parser::{Document, DocumentKind, with_parser};
+use serde_json::json;
+use std::collections::BTreeSet;
+use tree_sitter::Node;
+
+/// Chunk size settings (see `IndexingConfig`).
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkLimits {
+    pub max_chars: usize,
+    pub overlap_lines: usize,
+}
+
+/// Replaces every oversized document of `file` by its chunks. The file is only
+/// parsed again when one of them is.
+pub fn chunk_documents(
+    language: &dyn LanguageSupport,
+    file: &SourceFile,
+    docs: Vec<Document>,
+    limits: ChunkLimits,
+) -> Vec<Document> {
+    if docs.iter().all(|d| d.code.len() <= limits.max_chars) {
+        return docs;
+    }
+    let tree = language
+        .grammar()
+        .and_then(|g| with_parser(g, |p| p.parse_tree(file.source)).ok());
+    let root = tree.as_ref().map(|t| t.root_node());
+
+    let mut out = Vec::with_capacity(docs.len());
+    for doc in docs {
+        if doc.code.len() <= limits.max_chars {
+            out.push(doc);
+        } else if !is_chunked(&doc.kind) {
+            out.push(capped(doc, limits.max_chars));
+        } else {
+            let boundaries = match root.and_then(|r| node_of(file.source, r, &doc)) {
+                Some(node) => ast_boundaries(node, &doc, limits.max_chars),
+                None => paragraph_boundaries(&doc),
+            };
+            out.extend(split(doc, &boundaries, limits));
+        }
+    }
+    out
+}
+
+/// Kinds queried by their metadata (route inventory, call graph, ...), where
+/// several points per symbol would show up as duplicates.
+fn is_chunked(kind: &DocumentKind) -> bool {
+    !matches!(
+        kind,
+        DocumentKind::Endpoint
+            | DocumentKind::HttpCall
+            | DocumentKind::Topic
+            | DocumentKind::Table
+            | DocumentKind::SqlQuery
+            | DocumentKind::Deployment
+    )
+}
+
+/// `doc` with its code cut at the last line break within `max_chars`.
+fn capped(mut doc: Document, max_chars: usize) -> Document {
+    let end = char_floor(&doc.code, max_chars);
+    let end = doc.code[..end].rfind('\n').unwrap_or(end);
+    doc.code.truncate(end);
+    doc
+}
+
+/// The largest char boundary of `s` at or below `index`.
+fn char_floor(s: &str, index: usize) -> usize {
+    let mut end = index.min(s.len());
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    end
+}
+
+/// The smallest node spanning `doc.code`, which must start on `line_start`.
+fn node_of<'t>(source: &str, root: Node<'t>, doc: &Document) -> Option<Node<'t>> {
+    let line_offset: usize = source
+        .split_inclusive('\n')
+        .take(doc.line_start.saturating_sub(1) as usize)
+        .map(str::len)
+        .sum();
+    let start = line_offset + source.get(line_offset..)?.find(doc.code.as_str())?;
+    if source[line_offset..start].contains('\n') {
+        return None;
+    }
+    root.descendant_for_byte_range(start, start + doc.code.len())
+}
+
+/// Lines (relative to the document's first) where a sub-block starts, going
+/// down into every node over the limit.
+fn ast_boundaries(node: Node, doc: &Document, max_chars: usize) -> BTreeSet<usize> {
+    let first_row = doc.line_start.saturating_sub(1) as usize;
+    let mut rows = BTreeSet::new();
+    let mut stack = vec![node];
+    while let Some(n) = stack.pop() {
+        rows.insert(n.start_position().row);
+        if n.byte_range().len() > max_chars {
+            let mut cursor = n.walk();
+            stack.extend(n.children(&mut cursor));
+        }
+    }
+    rows.into_iter()
+        .filter_map(|r| r.checked_sub(first_row))
+        .collect()
+}
+
+/// The first line and every line after a blank one, except inside fenced or
+/// (in AsciiDoc documents) delimited blocks, which stay whole.
+fn paragraph_boundaries(doc: &Document) -> BTreeSet<usize> {
+    let asciidoc = doc.metadata.get("format").is_some_and(|f| f == "asciidoc");
+    let lines: Vec<&str> = doc.code.lines().collect();
+    let mut boundaries = BTreeSet::new();
+    let mut open: Option<&str> = None;
+    for (i, line) in lines.iter().enumerate() {
+        if open.is_none() && (i == 0 || lines[i - 1].trim().is_empty()) {
+            boundaries.insert(i);
+        }
+        if let Some(delimiter) = block_delimiter(line, asciidoc) {
+            match open {
+                None => open = Some(delimiter),
+                Some(o) if o == delimiter => open = None,
+                Some(_) => {}
+            }
+        }
+    }
+    boundaries
+}
+
+/// The delimiter `line` opens or closes a block with: the mark run of a
+/// ```` ``` ```` / `~~~` fence (info string dropped) or an AsciiDoc
+/// delimiter line.
+fn block_delimiter(line: &str, asciidoc: bool) -> Option<&str> {
+    let trimmed = line.trim();
+    if asciidoc && is_asciidoc_delimiter(trimmed) {
+        return Some(trimmed);
+    }
+    let mark = trimmed.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let run = trimmed.len() - trimmed.trim_start_matches(mark).len();
+    (run >= 3).then(|| &trimmed[..run])
+}
+
+/// Line ranges (`start..end`, overlap included) of the chunks of `lines`.
+fn chunk_ranges(
+    lines: &[&str],
+    boundaries: &BTreeSet<usize>,
+    limits: ChunkLimits,
+) -> Vec<(usize, usize)> {
+    // offsets[i]: size of lines[..i], newlines included
+    let mut offsets = vec![0];
+    for line in lines {
+        offsets.push(offsets.last().unwrap() + line.len() + 1);
+    }
+    let fits = |from: usize, to: usize| offsets[to] - offsets[from] <= limits.max_chars;
+
+    let mut ranges = Vec::new();
+    // first line of the chunk, and first line no earlier chunk holds
+    let (mut start, mut fresh) = (0, 0);
+    while fresh < lines.len() {
+        let end = if fits(start, lines.len()) {
+            lines.len()
+        } else {
+            boundaries
+                .range(fresh + 1..lines.len())
+                .rev()
+                .find(|&&b| fits(start, b))
+                .copied()
+                .or_else(|| (fresh + 1..lines.len()).rev().find(|&e| fits(start, e)))
+                .unwrap_or(fresh + 1)
+        };
+        if !fits(start, end) {
+            // one line too long even without overlap: alone (`split` cuts it)
+            start = fresh;
+        }
+        ranges.push((start, end));
+        fresh = end;
+        start = end.saturating_sub(limits.overlap_lines).max(start);
+    }
+    ranges
+}
+
+/// The chunks of `doc`, each its lines verbatim so they stitch back into the
+/// exact code; a line over the limit becomes several chunks spanning that
+/// line.
+fn split(doc: Document, boundaries: &BTreeSet<usize>, limits: ChunkLimits) -> Vec<Document> {
+    let lines: Vec<&str> = doc.code.split('\n').collect();
+    // (first line, end line, code) per chunk
+    let pieces: Vec<(usize, usize, String)> = chunk_ranges(&lines, boundaries, limits)
+        .into_iter()
+        .flat_map(|(from, to)| {
+            hard_cut(&lines[from..to].join("\n"), limits.max_chars)
+                .into_iter()
+                .map(move |code| (from, to, code))
+        })
+        .collect();
+
+    let parent = doc
+        .qualified_name
+        .clone()
+        .unwrap_or_else(|| doc.symbol_name.clone());
+    let count = pieces.len();
+    pieces
+        .into_iter()
+        .enumerate()
+        .map(|(i, (from, to, code))| {
+            let mut chunk = doc.clone();
+            chunk.code = code;
+            chunk.line_start = doc.line_start + from as u32;
+            chunk.line_end = doc.line_start + to as u32 - 1;
+            chunk.metadata.insert("chunk_index".into(), json!(i));
+            chunk.metadata.insert("chunk_count".into(), json!(count));
+            chunk.metadata.insert("chunk_of".into(), json!(parent));
+            chunk
+                .metadata
+                .insert("parent_lines".into(), json!([doc.line_start, doc.line_end]));
+            chunk
+        })
+        .collect()
+}
+
+/// `code` cut at char boundaries into pieces of at most `max_chars` (at least
+/// one char each); whole when it fits.
+fn hard_cut(code: &str, max_chars: usize) -> Vec<String> {
+    if code.len() <= max_chars {
+        return vec![code.to_string()];
+    }
+    let mut pieces = Vec::new();
+    let mut rest = code;
+    while !rest.is_empty() {
+        let end = match char_floor(rest, max_chars) {
+            0 => rest.chars().next().map_or(rest.len(), char::len_utf8),
+            end => end,
+        };
+        pieces.push(rest[..end].to_string());
+        rest = &rest[end..];
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::markdown_parser::MarkdownLanguage;
+    use crate::ingest::rust_parser::RustLanguage;
+
+    const LIMITS: ChunkLimits = ChunkLimits {
+        max_chars: 120,
+        overlap_lines: 1,
+    };
+
+    fn chunks(language: &dyn LanguageSupport, path: &str, src: &str) -> Vec<Document> {
+        let file = SourceFile {
+            repo: "billing",
+            file_path: path,
+            source: src,
+        };
+        let docs = language.parse_file(&file, false).unwrap();
+        chunk_documents(language, &file, docs, LIMITS)
+    }
+
+    #[test]
+    fn splits_a_function_at_match_arms() {
+        let src = "\
+fn small() {}
+
+fn status(code: u16) -> &'static str {
+    match code {
+        200 => {
+            let text = \"ok\";
+            text
+        }
+        404 => {
+            let text = \"not found\";
+            text
+        }
+        _ => {
+            let text = \"something else entirely\";
+            text
+        }
+    }
+}
+";
+        let docs = chunks(&RustLanguage, "src/lib.rs", src);
+        assert_eq!(docs[0].symbol_name, "small");
+        assert!(docs[0].metadata.get("chunk_index").is_none());
+
+        let parts: Vec<&Document> = docs.iter().filter(|d| d.symbol_name == "status").collect();
+        assert!(parts.len() > 1);
+        for (i, part) in parts.iter().enumerate() {
+            assert!(part.code.len() <= LIMITS.max_chars, "{}", part.code);
+            assert_eq!(part.metadata["chunk_index"], i);
+            assert_eq!(part.metadata["chunk_count"], parts.len());
+            assert_eq!(part.metadata["chunk_of"], "billing::status");
+            assert_eq!(part.metadata["parent_lines"], json!([3, 18]));
+            assert_eq!(part.qualified_name.as_deref(), Some("billing::status"));
+        }
+        assert_eq!(parts[0].line_start, 3);
+        assert_eq!(parts.last().unwrap().line_end, 18);
+        // cuts fall on arms, each chunk overlapping the previous one's last line
+        for pair in parts.windows(2) {
+            assert_eq!(pair[1].line_start, pair[0].line_end);
+        }
+        assert!(parts[1..].iter().all(|p| {
+            p.code
+                .lines()
+                .nth(1)
+                .unwrap()
+                .trim_start()
+                .ends_with("=> {")
+        }));
+    }
+
+    #[test]
+    fn splits_text_at_blank_lines() {
+        let para = |n: usize| format!("Paragraph {n} explains one part of the design.\n");
+        let src = format!("# Design\n\n{}\n{}\n{}", para(1), para(2), para(3));
+        let docs = chunks(&MarkdownLanguage, "docs/design.md", &src);
+        assert!(docs.len() > 1);
+        assert!(docs.iter().all(|d| d.code.len() <= LIMITS.max_chars));
+        assert!(docs[1].code.ends_with("part of the design."));
+        assert_eq!(docs[0].metadata["chunk_of"], docs[1].metadata["chunk_of"]);
+    }
+
+    #[test]
+    fn cuts_a_line_over_the_limit() {
+        let literal = "é".repeat(150);
+        let src = format!("fn blob() -> &'static str {{\n    \"{literal}\"\n}}\n");
+        let docs = chunks(&RustLanguage, "src/blob.rs", &src);
+        assert!(docs.len() > 2);
+        assert!(docs.iter().all(|d| d.code.len() <= LIMITS.max_chars));
+        let long: Vec<&Document> = docs.iter().filter(|d| d.line_start == 2).collect();
+        assert!(long.len() > 1 && long.iter().all(|d| d.line_end == 2));
+        let rejoined: String = long.iter().map(|d| d.code.as_str()).collect();
+        assert_eq!(rejoined, format!("    \"{literal}\""));
+    }
+
+    #[test]
+    fn keeps_fenced_blocks_whole() {
+        let fence = "```rust\nlet total = 1;\n\nlet tax = 2;\n```";
+        let src = format!(
+            "# Usage\n\nCall the ledger once per entry, then settle the batch at the end of the day.\n\n{fence}\n\n\
+             Settlement runs nightly and posts totals.\n\n\
+             Failed postings are retried the next night.\n"
+        );
+        let docs = chunks(&MarkdownLanguage, "docs/usage.md", &src);
+        assert!(docs.len() > 1);
+        assert!(docs.iter().all(|d| d.code.len() <= LIMITS.max_chars));
+        assert!(docs.iter().any(|d| d.code.contains(fence)), "{docs:#?}");
+    }
+}
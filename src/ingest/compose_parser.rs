@@ -0,0 +1,236 @@
+//! compose_parser.rs
+//!
+//! Docker Compose support for the language registry. Claims
+//! `docker-compose*.{yml,yaml}` and `compose*.{yml,yaml}` files and overrides
+//! `parse_file` (compose files are data, not code).
+//!
+//! Each entry of `services` yields one `deployment` document (see
+//! `manifest.rs`) with its `image`, `build` context, `ports` (short
+//! `published:target` or long syntax) and `expose`d ports, `environment`,
+//! `depends_on` (list or map) plus `links`, `deploy.resources`,
+//! `deploy.replicas` and `entrypoint` + `command`.
+//!
+//! code is the service's block of the original file; line ranges are located
+//! by key and indentation (best-effort; 1..=1 when not found).
+
+use crate::ingest::language::{LanguageSupport, SourceFile, file_name_lower};
+use crate::ingest::manifest::{
+    Deployment, deployment_document, env_vars, scalar, scalars, to_json,
+};
+use crate::ingest::openapi_parser::{block_end, find_key};
+use crate::ingest::rust_parser::{Document, RustParserError, with_file_summary};
+use serde_norway::Value;
+use std::path::Path;
+
+pub struct ComposeLanguage;
+
+impl LanguageSupport for ComposeLanguage {
+    fn name(&self) -> &'static str {
+        "compose"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["yml", "yaml"]
+    }
+
+    /// Only compose file names; other YAML is left alone.
+    fn matches(&self, path: &Path) -> bool {
+        let name = file_name_lower(path);
+        self.has_extension(path)
+            && (name.starts_with("docker-compose") || name.starts_with("compose"))
+    }
+
+    fn parse_file(
+        &self,
+        file: &SourceFile,
+        include_filename_doc: bool,
+    ) -> Result<Vec<Document>, RustParserError> {
+        let compose: Value =
+            serde_norway::from_str(file.source).map_err(|e| RustParserError::InvalidFormat {
+                format: "compose file",
+                message: e.to_string(),
+            })?;
+
+        let mut out = Vec::new();
+        let Some(Value::Mapping(services)) = compose.get("services") else {
+            return Ok(with_file_summary(file, include_filename_doc, out));
+        };
+        let lines: Vec<&str> = file.source.lines().collect();
+        for (name, service) in services {
+            let Some(name) = scalar(Some(name)) else {
+                continue;
+            };
+            let (start, end) = service_lines(&lines, &name);
+            let code = lines
+                .get(start - 1..end)
+                .map(|block| block.join("\n"))
+                .unwrap_or_default();
+            out.push(deployment_document(
+                file,
+                read_service(name, service),
+                code,
+                (start as u32, end as u32),
+            ));
+        }
+        Ok(with_file_summary(file, include_filename_doc, out))
+    }
+}
+
+fn read_service(name: String, service: &Value) -> Deployment {
+    let build = match service.get("build") {
+        Some(build @ Value::Mapping(_)) => {
+            let context = scalar(build.get("context"));
+            let dockerfile = scalar(build.get("dockerfile"));
+            match (context, dockerfile) {
+                (Some(c), Some(d)) => Some(format!("{c} ({d})")),
+                (context, dockerfile) => context.or(dockerfile),
+            }
+        }
+        other => scalar(other),
+    };
+
+    let mut ports: Vec<String> = match service.get("ports") {
+        Some(Value::Sequence(items)) => items.iter().filter_map(port).collect(),
+        _ => Vec::new(),
+    };
+    ports.extend(scalars(service.get("expose")));
+
+    let mut depends_on = match service.get("depends_on") {
+        Some(Value::Mapping(map)) => map.keys().filter_map(|k| scalar(Some(k))).collect(),
+        other => scalars(other),
+    };
+    for link in scalars(service.get("links")) {
+        let target = link.split(':').next().unwrap_or_default().to_string();
+        if !depends_on.contains(&target) {
+            depends_on.push(target);
+        }
+    }
+
+    let deploy = service.get("deploy");
+    let command: Vec<String> = ["entrypoint", "command"]
+        .into_iter()
+        .flat_map(|key| scalars(service.get(key)))
+        .collect();
+
+    Deployment {
+        name,
+        source: "compose",
+        images: scalars(service.get("image")),
+        build,
+        ports,
+        env: env_vars(service.get("environment")),
+        depends_on,
+        resources: to_json(deploy.and_then(|d| d.get("resources"))),
+        replicas: deploy
+            .and_then(|d| d.get("replicas"))
+            .and_then(Value::as_u64),
+        command: (!command.is_empty()).then(|| command.join(" ")),
+        ..Deployment::default()
+    }
+}
+
+/// Short syntax as written (`8080:8080`, `127.0.0.1:80:80/tcp`); long syntax
+/// as `published:target`.
+fn port(entry: &Value) -> Option<String> {
+    if let Some(short) = scalar(Some(entry)) {
+        return Some(short);
+    }
+    let target = scalar(entry.get("target"))?;
+    Some(match scalar(entry.get("published")) {
+        Some(published) => format!("{published}:{target}"),
+        None => target,
+    })
+}
+
+/// 1-based line range of `services.<name>`.
+fn service_lines(lines: &[&str], name: &str) -> (usize, usize) {
+    let indent = |l: &str| l.len() - l.trim_start().len();
+    let Some(services) = find_key(lines, 0, lines.len(), "services") else {
+        return (1, 1);
+    };
+    let services_end = block_end(lines, services) + 1;
+    let Some(child_indent) = lines[services + 1..services_end]
+        .iter()
+        .find(|l| !l.trim().is_empty() && !l.trim_start().starts_with('#'))
+        .map(|l| indent(l))
+    else {
+        return (1, 1);
+    };
+    let mut from = services + 1;
+    while let Some(at) = find_key(lines, from, services_end, name) {
+        if indent(lines[at]) == child_indent {
+            return (at + 1, block_end(lines, at) + 1);
+        }
+        from = at + 1;
+    }
+    (1, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn services_become_deployments() {
+        let src = "\
+version: '3.8'
+services:
+  db:
+    image: postgres:16
+    environment:
+      POSTGRES_DB: shop
+  orders:
+    build:
+      context: ./orders
+      dockerfile: Dockerfile
+    ports:
+      - \"8080:8080\"
+      - target: 9090
+        published: 19090
+    environment:
+      - DATABASE_URL=postgres://shop:secret@db:5432/shop
+      - PAYMENTS_URL=http://payments:8081
+    depends_on:
+      db:
+        condition: service_healthy
+    links:
+      - kafka:broker
+    deploy:
+      replicas: 2
+      resources:
+        limits:
+          memory: 512M
+    command: [\"./orders\", \"--port\", \"8080\"]
+";
+        let file = SourceFile {
+            repo: "shop",
+            file_path: "docker-compose.yml",
+            source: src,
+        };
+        let docs = ComposeLanguage.parse_file(&file, false).unwrap();
+        assert_eq!(docs.len(), 2);
+        assert_eq!(
+            (
+                docs[0].symbol_name.as_str(),
+                docs[0].line_start,
+                docs[0].line_end
+            ),
+            ("db", 3, 6)
+        );
+
+        let orders = &docs[1];
+        assert_eq!((orders.line_start, orders.line_end), (7, 28));
+        assert_eq!(
+            orders.signature.as_deref(),
+            Some(
+                "orders (compose): build ./orders (Dockerfile), ports 8080:8080, 19090:9090, depends on db, kafka"
+            )
+        );
+        let meta = &orders.metadata;
+        assert_eq!(meta["service_hosts"], json!(["db", "payments"]));
+        assert_eq!(meta["replicas"], 2);
+        assert_eq!(meta["resources"], json!({ "limits": { "memory": "512M" } }));
+        assert_eq!(meta["command"], "./orders --port 8080");
+    }
+}
@@ -0,0 +1,228 @@
+//! dockerfile_parser.rs
+//!
+//! Dockerfile support for the language registry. Claims `Dockerfile`,
+//! `Dockerfile.<suffix>`, `*.dockerfile` and `Containerfile`, and overrides
+//! `parse_file` with a line reader (instructions, `\` continuations, comments).
+//!
+//! Each file yields one `deployment` document (see `manifest.rs`) named after
+//! its directory (the repo at the root), with the suffix appended for
+//! `Dockerfile.worker` / `worker.dockerfile`. It records the base images
+//! (`FROM`, build stages excluded), `EXPOSE`d ports, `ENV` variables and the
+//! final `ENTRYPOINT` + `CMD`; code is the whole file.
+
+use crate::ingest::language::{LanguageSupport, SourceFile, file_name_lower};
+use crate::ingest::manifest::{Deployment, deployment_document};
+use crate::ingest::rust_parser::{Document, RustParserError, with_file_summary};
+use std::path::Path;
+
+pub struct DockerfileLanguage;
+
+impl LanguageSupport for DockerfileLanguage {
+    fn name(&self) -> &'static str {
+        "dockerfile"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["dockerfile"]
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        let name = file_name_lower(path);
+        name == "dockerfile"
+            || name == "containerfile"
+            || name.starts_with("dockerfile.")
+            || name.ends_with(".dockerfile")
+    }
+
+    fn parse_file(
+        &self,
+        file: &SourceFile,
+        include_filename_doc: bool,
+    ) -> Result<Vec<Document>, RustParserError> {
+        let mut out = Vec::new();
+        let deployment = Deployment {
+            name: service_name(file),
+            source: "dockerfile",
+            ..read_instructions(file.source)
+        };
+        let line_end = file.source.lines().count().max(1) as u32;
+        out.push(deployment_document(
+            file,
+            deployment,
+            file.source.to_string(),
+            (1, line_end),
+        ));
+        Ok(with_file_summary(file, include_filename_doc, out))
+    }
+}
+
+/// Directory name (repo name at the root), plus the file's suffix if any.
+fn service_name(file: &SourceFile) -> String {
+    let path = Path::new(file.file_path);
+    let base = path
+        .parent()
+        .and_then(|p| p.file_name())
+        .and_then(|n| n.to_str())
+        .unwrap_or(file.repo);
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    let lower = name.to_ascii_lowercase();
+    let suffix = if lower.starts_with("dockerfile.") {
+        &name["dockerfile.".len()..]
+    } else if lower.ends_with(".dockerfile") {
+        &name[..name.len() - ".dockerfile".len()]
+    } else {
+        ""
+    };
+    match suffix {
+        "" => base.to_string(),
+        suffix => format!("{base}-{suffix}"),
+    }
+}
+
+/// Images, ports, env and command of a Dockerfile.
+fn read_instructions(source: &str) -> Deployment {
+    let mut deployment = Deployment::default();
+    let mut stages: Vec<String> = Vec::new();
+    let mut entrypoint = None;
+    let mut cmd = None;
+
+    for line in logical_lines(source) {
+        let (instruction, args) = line.split_once(char::is_whitespace).unwrap_or((&line, ""));
+        let args = args.trim();
+        match instruction.to_ascii_uppercase().as_str() {
+            "FROM" => {
+                let words: Vec<&str> = args
+                    .split_whitespace()
+                    .filter(|w| !w.starts_with("--"))
+                    .collect();
+                let Some(image) = words.first() else {
+                    continue;
+                };
+                if *image != "scratch" && !stages.iter().any(|s| s == image) {
+                    deployment.images.push(image.to_string());
+                }
+                if let [_, as_kw, stage, ..] = words.as_slice()
+                    && as_kw.eq_ignore_ascii_case("as")
+                {
+                    stages.push(stage.to_string());
+                }
+            }
+            "EXPOSE" => deployment
+                .ports
+                .extend(args.split_whitespace().map(str::to_string)),
+            "ENV" => match args.split_once(char::is_whitespace) {
+                // legacy `ENV NAME value`
+                Some((name, value)) if !name.contains('=') => {
+                    deployment.env.insert(name.into(), unquote(value.trim()));
+                }
+                _ => {
+                    for pair in args.split_whitespace() {
+                        let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+                        deployment.env.insert(k.into(), unquote(v));
+                    }
+                }
+            },
+            "ENTRYPOINT" => entrypoint = Some(exec_form(args)),
+            "CMD" => cmd = Some(exec_form(args)),
+            _ => {}
+        }
+    }
+    let command: Vec<String> = entrypoint.into_iter().chain(cmd).collect();
+    deployment.command = (!command.is_empty()).then(|| command.join(" "));
+    deployment
+}
+
+/// Instructions with `\` continuations joined; comments and blanks dropped.
+fn logical_lines(source: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut current = String::new();
+    for line in source.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') || (trimmed.is_empty() && current.is_empty()) {
+            continue;
+        }
+        match trimmed.strip_suffix('\\') {
+            Some(part) => {
+                current.push_str(part.trim());
+                current.push(' ');
+            }
+            None => {
+                current.push_str(trimmed);
+                out.push(std::mem::take(&mut current).trim().to_string());
+            }
+        }
+    }
+    if !current.trim().is_empty() {
+        out.push(current.trim().to_string());
+    }
+    out
+}
+
+/// `["java", "-jar", "app.jar"]` → `java -jar app.jar`; shell form as is.
+fn exec_form(args: &str) -> String {
+    match serde_json::from_str::<Vec<String>>(args) {
+        Ok(words) => words.join(" "),
+        Err(_) => args.to_string(),
+    }
+}
+
+fn unquote(value: &str) -> String {
+    value.trim_matches(['"', '\'']).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_dockerfile_names() {
+        let lang = DockerfileLanguage;
+        assert!(lang.matches(Path::new("Dockerfile")));
+        assert!(lang.matches(Path::new("docker/Dockerfile.worker")));
+        assert!(lang.matches(Path::new("build/api.dockerfile")));
+        assert!(lang.matches(Path::new("Containerfile")));
+        assert!(!lang.matches(Path::new("docs/dockerfile.md.txt/README")));
+    }
+
+    #[test]
+    fn multi_stage_build() {
+        let src = "\
+# build
+FROM --platform=linux/amd64 gradle:8-jdk17 AS build
+COPY . .
+RUN gradle bootJar
+
+FROM eclipse-temurin:17-jre
+COPY --from=build /app/build/libs/app.jar /app.jar
+ENV SPRING_PROFILES_ACTIVE=prod \\
+    KAFKA_BOOTSTRAP_SERVERS=kafka:9092
+ENV JAVA_OPTS -Xmx512m
+EXPOSE 8080 9090/tcp
+ENTRYPOINT [\"java\", \"-jar\", \"/app.jar\"]
+";
+        let file = SourceFile {
+            repo: "orders",
+            file_path: "Dockerfile.worker",
+            source: src,
+        };
+        let docs = DockerfileLanguage.parse_file(&file, false).unwrap();
+        assert_eq!(docs.len(), 1);
+        let doc = &docs[0];
+        assert_eq!(doc.symbol_name, "orders-worker");
+        assert_eq!((doc.line_start, doc.line_end), (1, 12));
+        assert_eq!(
+            doc.metadata["images"],
+            serde_json::json!(["gradle:8-jdk17", "eclipse-temurin:17-jre"])
+        );
+        assert_eq!(
+            doc.metadata["ports"],
+            serde_json::json!(["8080", "9090/tcp"])
+        );
+        assert_eq!(doc.metadata["env"]["JAVA_OPTS"], "-Xmx512m");
+        assert_eq!(doc.metadata["service_hosts"], serde_json::json!(["kafka"]));
+        assert_eq!(doc.metadata["command"], "java -jar /app.jar");
+    }
+}
@@ -0,0 +1,284 @@
+//! go_parser.rs
+//!
+//! Go support for the language registry (tree-sitter-go).
+//! Documents include:
+//! - functions
+//! - methods (parent_type = receiver base type: `func (s *Server) Run()` → `Server`)
+//! - `type` declarations: structs, interfaces; any other named type or alias
+//!   is a type_alias
+//! - Kafka (kafka-go) / NATS topic usages as `topic` documents (see `messaging.rs`)
+//! - SQL string literals as `sql_query` documents (see `sql.rs`)
+//!
+//! Notes:
+//! - Doc comments are the `//` lines directly above the declaration (Go doc
+//!   convention); `//go:` directives among them are dropped.
+//! - In a grouped `type ( ... )` block each spec is its own document with its
+//!   own comment; a lone `type X ...` spans the whole declaration.
+//! - Signatures are the declaration header without its body, whitespace-normalized.
+//! - qualified_name is `package` + receiver + name, `.`-joined (`orders.Server.Run`).
+
+use crate::ingest::language::{LanguageSupport, SourceFile};
+use crate::ingest::messaging;
+use crate::ingest::rust_parser::{
+    Document, DocumentKind, ParseLanguage, child_text_by_kind, line_comment_block_above, lines_of,
+    node_text, normalize_ws,
+};
+use crate::ingest::sql;
+use tree_sitter::Node;
+
+pub struct GoLanguage;
+
+impl LanguageSupport for GoLanguage {
+    fn name(&self) -> &'static str {
+        "go"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["go"]
+    }
+
+    fn grammar(&self) -> Option<ParseLanguage> {
+        Some(ParseLanguage::Go)
+    }
+
+    fn visit(&self, file: &SourceFile, node: Node, out: &mut Vec<Document>) {
+        let doc = match node.kind() {
+            "function_declaration" => extract_function(file, node, None),
+            "method_declaration" => {
+                let receiver = receiver_type(file.source, node);
+                extract_function(file, node, receiver)
+            }
+            "type_spec" | "type_alias" => extract_type_spec(file, node),
+            "call_expression" | "composite_literal" => {
+                messaging::go_topic_usage(file, node, out);
+                None
+            }
+            "interpreted_string_literal" | "raw_string_literal" => {
+                sql::sql_query(file, node, out);
+                None
+            }
+            _ => None,
+        };
+        out.extend(doc);
+    }
+
+    /// The `package` clause.
+    fn file_module(&self, file: &SourceFile, root: Node) -> Option<String> {
+        (0..root.child_count())
+            .filter_map(|i| root.child(i))
+            .find(|ch| ch.kind() == "package_clause")
+            .and_then(|clause| child_text_by_kind(file.source, clause, "package_identifier"))
+    }
+
+    fn path_separator(&self) -> &'static str {
+        "."
+    }
+
+    fn import_kinds(&self) -> &'static [&'static str] {
+        &["import_declaration"]
+    }
+
+    /// The package comment (the `//` block above `package`).
+    fn module_doc(&self, file: &SourceFile, root: Node) -> Option<String> {
+        (0..root.child_count())
+            .filter_map(|i| root.child(i))
+            .find(|ch| ch.kind() == "package_clause")
+            .and_then(|clause| doc_comment(file.source, clause))
+    }
+}
+
+fn extract_function(file: &SourceFile, node: Node, receiver: Option<String>) -> Option<Document> {
+    let name = node.child_by_field_name("name")?;
+    let kind = if receiver.is_some() {
+        DocumentKind::Method
+    } else {
+        DocumentKind::Function
+    };
+    let header_end = node
+        .child_by_field_name("body")
+        .map_or(node.end_byte(), |b| b.start_byte());
+
+    Some(build_document(
+        file,
+        node,
+        node_text(file.source, name),
+        kind,
+        normalize_ws(&file.source[node.start_byte()..header_end]),
+        receiver,
+    ))
+}
+
+fn extract_type_spec(file: &SourceFile, spec: Node) -> Option<Document> {
+    let source = file.source;
+    let name = spec.child_by_field_name("name")?;
+    let ty = spec.child_by_field_name("type")?;
+    let kind = match (spec.kind(), ty.kind()) {
+        ("type_spec", "struct_type") => DocumentKind::Struct,
+        ("type_spec", "interface_type") => DocumentKind::Interface,
+        _ => DocumentKind::TypeAlias,
+    };
+
+    // `type X struct {...}` → the whole declaration; grouped specs stand alone
+    let outer = spec
+        .parent()
+        .filter(|d| d.kind() == "type_declaration" && d.named_child_count() == 1)
+        .unwrap_or(spec);
+    let header_end = match ty.kind() {
+        "struct_type" | "interface_type" => type_body_start(ty).unwrap_or(ty.end_byte()),
+        _ => spec.end_byte(),
+    };
+
+    Some(build_document(
+        file,
+        outer,
+        node_text(source, name),
+        kind,
+        normalize_ws(&source[outer.start_byte()..header_end]),
+        None,
+    ))
+}
+
+fn build_document(
+    file: &SourceFile,
+    node: Node,
+    symbol_name: String,
+    kind: DocumentKind,
+    signature: String,
+    parent_type: Option<String>,
+) -> Document {
+    let (line_start, line_end) = lines_of(&node);
+    Document {
+        repo: file.repo.to_string(),
+        file_path: file.file_path.to_string(),
+        symbol_name,
+        kind,
+        signature: Some(signature),
+        doc_comment: doc_comment(file.source, node),
+        code: node_text(file.source, node),
+        parent_type,
+        module_path: None,
+        qualified_name: None,
+        annotations: Vec::new(),
+        metadata: Default::default(),
+        line_start,
+        line_end,
+    }
+}
+
+/// `//` comment block above `node` without `//go:` directives.
+fn doc_comment(source: &str, node: Node) -> Option<String> {
+    let block = line_comment_block_above(source, node.start_byte(), "//")?;
+    let lines: Vec<&str> = block.lines().filter(|l| !l.starts_with("go:")).collect();
+    let joined = lines.join("\n").trim_matches('\n').to_string();
+    (!joined.is_empty()).then_some(joined)
+}
+
+/// Base type of a method receiver: pointer and type arguments stripped
+/// (`(s *Server)` → `Server`, `(l *List[T])` → `List`).
+fn receiver_type(source: &str, method: Node) -> Option<String> {
+    let params = method.child_by_field_name("receiver")?;
+    let param = (0..params.named_child_count())
+        .filter_map(|i| params.named_child(i))
+        .find(|p| p.kind() == "parameter_declaration")?;
+    let mut ty = param.child_by_field_name("type")?;
+    loop {
+        ty = match ty.kind() {
+            "pointer_type" => ty.named_child(0)?,
+            "generic_type" => ty.child_by_field_name("type")?,
+            "parenthesized_type" => ty.named_child(0)?,
+            _ => break,
+        };
+    }
+    Some(node_text(source, ty))
+}
+
+/// Start of the `{` of a struct/interface type.
+fn type_body_start(ty: Node) -> Option<usize> {
+    (0..ty.child_count())
+        .filter_map(|i| ty.child(i))
+        .find(|ch| ch.kind() == "{" || ch.kind() == "field_declaration_list")
+        .map(|ch| ch.start_byte())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::language::fixtures::{find, parse};
+
+    #[test]
+    fn functions_methods_and_types() {
+        let src = r#"package server
+
+import "net/http"
+
+// Server serves the orders API.
+// It is safe for concurrent use.
+type Server struct {
+	mux *http.ServeMux
+}
+
+// Store persists orders.
+type Store interface {
+	Get(id string) (Order, error)
+}
+
+type (
+	// OrderID identifies an order.
+	OrderID string
+	Alias = OrderID
+)
+
+// New builds a Server.
+//
+//go:noinline
+func New(store Store) *Server {
+	return &Server{}
+}
+
+// Run starts listening.
+func (s *Server) Run(addr string) error {
+	return http.ListenAndServe(addr, s.mux)
+}
+
+func (l List[T]) Len() int { return 0 }
+
+// detached comment
+
+func helper() {}
+"#;
+        let docs = parse(&GoLanguage, "internal/server/server.go", src);
+
+        let server = find(&docs, "Server");
+        assert_eq!(server.kind, DocumentKind::Struct);
+        assert_eq!(server.signature.as_deref(), Some("type Server struct"));
+        assert_eq!(
+            server.doc_comment.as_deref(),
+            Some("Server serves the orders API.\nIt is safe for concurrent use.")
+        );
+        assert_eq!(find(&docs, "Store").kind, DocumentKind::Interface);
+
+        let id = find(&docs, "OrderID");
+        assert_eq!(id.kind, DocumentKind::TypeAlias);
+        assert_eq!(
+            id.doc_comment.as_deref(),
+            Some("OrderID identifies an order.")
+        );
+        assert_eq!(find(&docs, "Alias").kind, DocumentKind::TypeAlias);
+
+        let new = find(&docs, "New");
+        assert_eq!(new.kind, DocumentKind::Function);
+        assert_eq!(new.doc_comment.as_deref(), Some("New builds a Server."));
+        assert_eq!(
+            new.signature.as_deref(),
+            Some("func New(store Store) *Server")
+        );
+
+        let run = find(&docs, "Run");
+        assert_eq!(run.kind, DocumentKind::Method);
+        assert_eq!(run.parent_type.as_deref(), Some("Server"));
+        assert_eq!(run.qualified_name.as_deref(), Some("server.Server.Run"));
+        assert_eq!(find(&docs, "Len").parent_type.as_deref(), Some("List"));
+
+        assert_eq!(find(&docs, "helper").doc_comment, None);
+    }
+}
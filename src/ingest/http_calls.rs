@@ -0,0 +1,728 @@
+//! http_calls.rs
+//!
+//! Outbound HTTP client calls, emitted as `http_call` documents by the
+//! language that parses the file (called from its `visit`, like `routes.rs`):
+//! - Rust reqwest: `client.post(url)`, `reqwest::get(url)`,
+//!   `client.request(Method::PUT, url)`
+//! - JS/TS: `fetch(url, { method: "POST" })`, `axios.get(url)`,
+//!   `ordersApi.post(url, body)`
+//! - Kotlin: Ktor `client.get(url)`, OkHttp `Request.Builder().url(url).post(body)`
+//! - Java: `restTemplate.postForObject(url, ...)`, `exchange(url, HttpMethod.PUT, ...)`,
+//!   OkHttp
+//!
+//! Clients are recognized by their receiver (`client`, `http`, `axios`,
+//! `...Api`, `restTemplate`, `Request.Builder`), and the URL must be readable:
+//! a literal, a `format!`/`String.format`/template string or a `+` concatenation
+//! starting with a base URL expression, a scheme or `/`.
+//!
+//! symbol_name is `POST /orders/{}` (path params collapsed to `{}`); metadata:
+//! `method`, `path`, `url` (the template, expressions in braces:
+//! `{self.base_url}/orders/{id}`), `host` (literal URLs), `base_url_key` (the
+//! config key or variable providing the base URL: `ORDERS_URL` from
+//! `process.env.ORDERS_URL` / `env::var("ORDERS_URL")`, else the last segment
+//! of the expression), `client` and `caller` (enclosing function). Calls are
+//! matched against other repos' endpoints after indexing (`transform::call_graph`).
+
+use crate::ingest::language::SourceFile;
+use crate::ingest::routes::{http_method, named_children};
+use crate::ingest::rust_parser::{
+    Document, DocumentKind, child_text_by_kind, field_text, lines_of, node_text, normalize_ws,
+    slice_source,
+};
+use serde_json::json;
+use tree_sitter::Node;
+
+/// Part of a URL expression.
+#[derive(Debug, Clone, PartialEq)]
+enum Piece {
+    Lit(String),
+    Expr(String),
+}
+
+/// A URL expression read from code.
+#[derive(Debug, PartialEq)]
+struct UrlTemplate {
+    url: String,
+    host: Option<String>,
+    base_url_key: Option<String>,
+    path: String,
+}
+
+struct Call<'a> {
+    node: Node<'a>,
+    method: String,
+    url: Node<'a>,
+    client: &'static str,
+}
+
+// ---- Rust ----
+
+/// reqwest calls.
+pub(crate) fn rust_http_call(file: &SourceFile, node: Node, out: &mut Vec<Document>) {
+    let source = file.source;
+    if node.kind() != "call_expression" {
+        return;
+    }
+    let (Some(function), Some(args)) = (
+        node.child_by_field_name("function"),
+        node.child_by_field_name("arguments"),
+    ) else {
+        return;
+    };
+    let args: Vec<Node> = named_children(args).collect();
+    let call = match function.kind() {
+        // reqwest::get(url)
+        "scoped_identifier" if node_text(source, function) == "reqwest::get" => {
+            args.first().map(|url| ("GET".to_string(), *url))
+        }
+        "field_expression" => {
+            let receiver = field_text(source, function, "value");
+            let field = field_text(source, function, "field");
+            if !is_client_name(&receiver) {
+                None
+            } else if field == "request" {
+                // client.request(Method::POST, url)
+                let method = args
+                    .first()
+                    .and_then(|m| http_method(last_segment(&node_text(source, *m))));
+                method.zip(args.get(1).copied())
+            } else {
+                http_method(&field).zip(args.first().copied())
+            }
+        }
+        _ => None,
+    };
+    if let Some((method, url)) = call {
+        push_call(
+            file,
+            Call {
+                node,
+                method,
+                url,
+                client: "reqwest",
+            },
+            out,
+        );
+    }
+}
+
+// ---- JS/TS ----
+
+/// `fetch` and axios-style clients.
+pub(crate) fn js_http_call(file: &SourceFile, node: Node, out: &mut Vec<Document>) {
+    let source = file.source;
+    if node.kind() != "call_expression" {
+        return;
+    }
+    let (Some(function), Some(args)) = (
+        node.child_by_field_name("function"),
+        node.child_by_field_name("arguments"),
+    ) else {
+        return;
+    };
+    let args: Vec<Node> = named_children(args).collect();
+    let Some(url) = args.first().copied() else {
+        return;
+    };
+    let call = match function.kind() {
+        "identifier" if node_text(source, function) == "fetch" => {
+            let method = args
+                .get(1)
+                .and_then(|options| fetch_method(source, *options))
+                .unwrap_or_else(|| "GET".into());
+            Some((method, "fetch"))
+        }
+        "member_expression" => {
+            let object = field_text(source, function, "object");
+            let property = field_text(source, function, "property");
+            (is_client_name(&object) || object.to_ascii_lowercase().ends_with("api"))
+                .then(|| http_method(&property))
+                .flatten()
+                .map(|m| (m, "axios"))
+        }
+        _ => None,
+    };
+    if let Some((method, client)) = call {
+        push_call(
+            file,
+            Call {
+                node,
+                method,
+                url,
+                client,
+            },
+            out,
+        );
+    }
+}
+
+/// `method` of a `fetch` options object literal.
+fn fetch_method(source: &str, options: Node) -> Option<String> {
+    if options.kind() != "object" {
+        return None;
+    }
+    named_children(options)
+        .filter(|p| p.kind() == "pair")
+        .find(|p| {
+            p.child_by_field_name("key")
+                .is_some_and(|k| node_text(source, k).trim_matches(['"', '\'']) == "method")
+        })
+        .and_then(|p| p.child_by_field_name("value"))
+        .and_then(|v| http_method(node_text(source, v).trim_matches(['"', '\'', '`'])))
+}
+
+// ---- Kotlin ----
+
+/// Ktor `HttpClient` and OkHttp calls.
+pub(crate) fn kotlin_http_call(file: &SourceFile, node: Node, out: &mut Vec<Document>) {
+    let source = file.source;
+    if node.kind() != "call_expression" {
+        return;
+    }
+    let Some(navigation) = node
+        .named_child(0)
+        .filter(|n| n.kind() == "navigation_expression")
+    else {
+        return;
+    };
+    let receiver = navigation
+        .named_child(0)
+        .map(|r| node_text(source, r))
+        .unwrap_or_default();
+    let Some(name) = navigation
+        .named_child(1)
+        .and_then(|suffix| child_text_by_kind(source, suffix, "simple_identifier"))
+    else {
+        return;
+    };
+    let Some(url) = named_children(node)
+        .filter(|c| c.kind() == "call_suffix")
+        .flat_map(named_children)
+        .filter(|c| c.kind() == "value_arguments")
+        .flat_map(named_children)
+        .find_map(|arg| arg.named_child(0))
+    else {
+        return;
+    };
+
+    let call = if name == "url" && receiver.contains("Request.Builder") {
+        Some((okhttp_method(source, node), "okhttp"))
+    } else if is_client_name(&receiver) {
+        http_method(&name).map(|m| (m, "ktor"))
+    } else {
+        None
+    };
+    if let Some((method, client)) = call {
+        push_call(
+            file,
+            Call {
+                node,
+                method,
+                url,
+                client,
+            },
+            out,
+        );
+    }
+}
+
+// ---- Java ----
+
+const REST_TEMPLATE_METHODS: &[(&str, &str)] = &[
+    ("getForObject", "GET"),
+    ("getForEntity", "GET"),
+    ("postForObject", "POST"),
+    ("postForEntity", "POST"),
+    ("postForLocation", "POST"),
+    ("put", "PUT"),
+    ("patchForObject", "PATCH"),
+    ("delete", "DELETE"),
+    ("exchange", ""),
+];
+
+/// RestTemplate and OkHttp calls.
+pub(crate) fn java_http_call(file: &SourceFile, node: Node, out: &mut Vec<Document>) {
+    let source = file.source;
+    if node.kind() != "method_invocation" {
+        return;
+    }
+    let (Some(object), Some(name), Some(args)) = (
+        node.child_by_field_name("object"),
+        node.child_by_field_name("name"),
+        node.child_by_field_name("arguments"),
+    ) else {
+        return;
+    };
+    let object = node_text(source, object);
+    let name = node_text(source, name);
+    let args: Vec<Node> = named_children(args).collect();
+    let Some(url) = args.first().copied() else {
+        return;
+    };
+
+    let call = if name == "url" && object.contains("Request.Builder") {
+        Some((okhttp_method(source, node), "okhttp"))
+    } else if object.to_ascii_lowercase().ends_with("template") {
+        REST_TEMPLATE_METHODS
+            .iter()
+            .find(|(n, _)| *n == name)
+            .and_then(|(_, method)| match *method {
+                // exchange(url, HttpMethod.POST, ...)
+                "" => args
+                    .get(1)
+                    .and_then(|m| http_method(last_segment(&node_text(source, *m)))),
+                m => Some(m.to_string()),
+            })
+            .map(|m| (m, "resttemplate"))
+    } else {
+        None
+    };
+    if let Some((method, client)) = call {
+        push_call(
+            file,
+            Call {
+                node,
+                method,
+                url,
+                client,
+            },
+            out,
+        );
+    }
+}
+
+/// Method of an OkHttp `Request.Builder().url(..)` chain: the first
+/// `.post(`/`.put(`/... after the `url` call, else GET.
+fn okhttp_method(source: &str, url_call: Node) -> String {
+    let mut top = url_call;
+    while let Some(parent) = top.parent()
+        && matches!(
+            parent.kind(),
+            "method_invocation" | "navigation_expression" | "call_expression"
+        )
+        && parent.named_child(0) == Some(top)
+    {
+        top = parent;
+    }
+    let rest = &source[url_call.end_byte()..top.end_byte()];
+    ["post", "put", "patch", "delete", "head"]
+        .into_iter()
+        .filter_map(|m| rest.find(&format!(".{m}(")).map(|at| (at, m)))
+        .min()
+        .map_or_else(|| "GET".into(), |(_, m)| m.to_ascii_uppercase())
+}
+
+// ---- shared ----
+
+/// Receiver names that denote an HTTP client (`client`, `self.http`,
+/// `httpClient`, `axios`, `reqwest::Client::new()`).
+fn is_client_name(receiver: &str) -> bool {
+    let lower = receiver.to_ascii_lowercase();
+    lower.contains("client") || lower.contains("http") || lower.contains("axios")
+}
+
+fn push_call(file: &SourceFile, call: Call, out: &mut Vec<Document>) {
+    let source = file.source;
+    let mut pieces = Vec::new();
+    url_pieces(source, call.url, &mut pieces);
+    let Some(url) = url_template(pieces) else {
+        return;
+    };
+    let symbol_name = format!("{} {}", call.method, url.path);
+    let (line_start, line_end) = lines_of(&call.node);
+
+    let mut metadata = serde_json::Map::new();
+    metadata.insert("method".into(), json!(call.method));
+    metadata.insert("path".into(), json!(url.path));
+    metadata.insert("url".into(), json!(url.url));
+    metadata.insert("host".into(), json!(url.host));
+    metadata.insert("base_url_key".into(), json!(url.base_url_key));
+    metadata.insert("client".into(), json!(call.client));
+    metadata.insert("caller".into(), json!(caller(source, call.node)));
+
+    out.push(Document {
+        repo: file.repo.to_string(),
+        file_path: file.file_path.to_string(),
+        symbol_name,
+        kind: DocumentKind::HttpCall,
+        signature: Some(format!("{} {}", call.method, url.url)),
+        doc_comment: None,
+        code: slice_source(source, call.node.byte_range()),
+        parent_type: None,
+        module_path: None,
+        qualified_name: None,
+        annotations: Vec::new(),
+        metadata,
+        line_start,
+        line_end,
+    });
+}
+
+/// Flattens a URL expression into literal and expression pieces.
+fn url_pieces(source: &str, node: Node, out: &mut Vec<Piece>) {
+    match node.kind() {
+        "string_literal" | "string" | "template_string" => {
+            for ch in named_children(node) {
+                match ch.kind() {
+                    "string_content" | "string_fragment" | "escape_sequence" => {
+                        out.push(Piece::Lit(node_text(source, ch)))
+                    }
+                    _ => out.push(Piece::Expr(
+                        node_text(source, ch)
+                            .trim_start_matches('$')
+                            .trim_start_matches('{')
+                            .trim_end_matches('}')
+                            .trim()
+                            .to_string(),
+                    )),
+                }
+            }
+        }
+        "binary_expression" | "additive_expression"
+            if node.child(1).is_some_and(|op| node_text(source, op) == "+") =>
+        {
+            for ch in named_children(node) {
+                url_pieces(source, ch, out);
+            }
+        }
+        "reference_expression" | "parenthesized_expression" | "value_argument" => {
+            if let Some(inner) = named_children(node).last() {
+                url_pieces(source, inner, out);
+            }
+        }
+        // format!("{}/orders/{id}", base)
+        "macro_invocation"
+            if child_text_by_kind(source, node, "identifier").as_deref() == Some("format") =>
+        {
+            format_pieces(source, node, out);
+        }
+        // String.format("%s/orders", base)
+        "method_invocation"
+            if node
+                .child_by_field_name("name")
+                .is_some_and(|n| node_text(source, n) == "format") =>
+        {
+            printf_pieces(source, node, out);
+        }
+        _ => out.push(Piece::Expr(normalize_ws(&node_text(source, node)))),
+    }
+}
+
+/// Pieces of a Rust `format!` call: `{}`/`{0}` take the arguments, `{name}`
+/// names an inline variable.
+fn format_pieces(source: &str, macro_node: Node, out: &mut Vec<Piece>) {
+    let Some(tokens) = named_children(macro_node).find(|c| c.kind() == "token_tree") else {
+        return;
+    };
+    let Some(template) = tokens
+        .named_child(0)
+        .filter(|t| t.kind() == "string_literal")
+    else {
+        return;
+    };
+    let template = node_text(source, template);
+    let template = template.trim_matches('"');
+
+    // arguments: token runs between top-level commas
+    let mut args: Vec<String> = Vec::new();
+    let mut current: Option<(usize, usize)> = None;
+    for i in 0..tokens.child_count() {
+        let Some(tok) = tokens.child(i) else { continue };
+        if tok.start_byte() <= tokens.start_byte() || tok.end_byte() >= tokens.end_byte() {
+            continue; // delimiters
+        }
+        if tok.kind() == "," {
+            args.extend(current.take().map(|(s, e)| normalize_ws(&source[s..e])));
+        } else {
+            current = Some(
+                current.map_or((tok.start_byte(), tok.end_byte()), |(s, _)| {
+                    (s, tok.end_byte())
+                }),
+            );
+        }
+    }
+    args.extend(current.map(|(s, e)| normalize_ws(&source[s..e])));
+    let args = args.get(1..).unwrap_or_default();
+
+    let mut next = 0;
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        if rest[open..].starts_with("{{") {
+            out.push(Piece::Lit(rest[..open + 1].to_string()));
+            rest = &rest[open + 2..];
+            continue;
+        }
+        out.push(Piece::Lit(rest[..open].to_string()));
+        let close = rest[open..].find('}').map_or(rest.len(), |c| open + c);
+        let spec = &rest[open + 1..close];
+        let name = spec.split(':').next().unwrap_or_default();
+        let expr = if name.is_empty() {
+            next += 1;
+            args.get(next - 1).cloned()
+        } else if let Ok(i) = name.parse::<usize>() {
+            args.get(i).cloned()
+        } else {
+            Some(name.to_string())
+        };
+        out.push(Piece::Expr(expr.unwrap_or_default()));
+        rest = rest.get(close + 1..).unwrap_or_default();
+    }
+    out.push(Piece::Lit(rest.to_string()));
+}
+
+/// Pieces of a Java `String.format`: `%s`/`%d` take the arguments.
+fn printf_pieces(source: &str, call: Node, out: &mut Vec<Piece>) {
+    let Some(args) = call.child_by_field_name("arguments") else {
+        return;
+    };
+    let args: Vec<Node> = named_children(args).collect();
+    let Some(template) = args.first().filter(|t| t.kind() == "string_literal") else {
+        return;
+    };
+    let template = node_text(source, *template);
+    let mut values = args[1..].iter();
+    for (i, part) in template.trim_matches('"').split('%').enumerate() {
+        if i == 0 {
+            out.push(Piece::Lit(part.to_string()));
+            continue;
+        }
+        let mut chars = part.chars();
+        chars.next(); // conversion (`s`, `d`, ...)
+        let value = values.next().map(|v| normalize_ws(&node_text(source, *v)));
+        out.push(Piece::Expr(value.unwrap_or_default()));
+        out.push(Piece::Lit(chars.as_str().to_string()));
+    }
+}
+
+/// Reads base URL, host and path from the pieces of a URL expression. `None`
+/// when the URL is not recognizable (a bare variable, a relative name).
+fn url_template(pieces: Vec<Piece>) -> Option<UrlTemplate> {
+    // merge literals, drop empty ones
+    let mut merged: Vec<Piece> = Vec::new();
+    for piece in pieces {
+        match (merged.last_mut(), piece) {
+            (_, Piece::Lit(l)) if l.is_empty() => {}
+            (Some(Piece::Lit(prev)), Piece::Lit(l)) => prev.push_str(&l),
+            (_, p) => merged.push(p),
+        }
+    }
+    let url: String = merged
+        .iter()
+        .map(|p| match p {
+            Piece::Lit(l) => l.clone(),
+            Piece::Expr(e) => format!("{{{e}}}"),
+        })
+        .collect();
+
+    match merged.first()? {
+        Piece::Expr(base) if merged.len() > 1 => Some(UrlTemplate {
+            base_url_key: Some(config_key(base)),
+            host: None,
+            path: clean_path(&render_path(&merged[1..])),
+            url,
+        }),
+        Piece::Lit(l) if l.starts_with("http://") || l.starts_with("https://") => {
+            // `http://orders:8080/orders/{}`: host up to the first `/` after the scheme
+            let after_scheme = |s: &str| s[s.find("://").map_or(0, |i| i + 3)..].to_string();
+            let rendered = after_scheme(&render_path(&merged));
+            let path_start = rendered.find('/').unwrap_or(rendered.len());
+            let full = after_scheme(&url);
+            let host = full[..full.find('/').unwrap_or(full.len())].to_string();
+            Some(UrlTemplate {
+                path: clean_path(&rendered[path_start..]),
+                host: Some(host),
+                base_url_key: None,
+                url,
+            })
+        }
+        Piece::Lit(l) if l.starts_with('/') => Some(UrlTemplate {
+            path: clean_path(&render_path(&merged)),
+            host: None,
+            base_url_key: None,
+            url,
+        }),
+        _ => None,
+    }
+}
+
+fn render_path(pieces: &[Piece]) -> String {
+    pieces
+        .iter()
+        .map(|p| match p {
+            Piece::Lit(l) => l.clone(),
+            Piece::Expr(_) => "{}".into(),
+        })
+        .collect()
+}
+
+/// Query string dropped, leading `/` ensured.
+fn clean_path(path: &str) -> String {
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    format!("/{}", path.trim_start_matches('/'))
+}
+
+/// Config key behind a base URL expression: `process.env.ORDERS_URL` and
+/// `env::var("ORDERS_URL")` → `ORDERS_URL`; otherwise the expression's last
+/// segment (`self.config.orders_url` → `orders_url`).
+fn config_key(expr: &str) -> String {
+    if let Some(start) = expr.find('"') {
+        let quoted = &expr[start + 1..];
+        if let Some(end) = quoted.find('"') {
+            return quoted[..end].to_string();
+        }
+    }
+    last_segment(expr.trim_end_matches("()")).to_string()
+}
+
+fn last_segment(path: &str) -> &str {
+    path.rsplit(['.', ':']).next().unwrap_or(path).trim()
+}
+
+/// Name of the function/method enclosing `node`.
+pub(crate) fn caller(source: &str, node: Node) -> Option<String> {
+    let mut cur = node.parent();
+    while let Some(n) = cur {
+        match n.kind() {
+            "function_item"
+            | "function_declaration"
+            | "function_definition"
+            | "method_definition"
+            | "method_declaration"
+            | "constructor_declaration" => {
+                return n
+                    .child_by_field_name("name")
+                    .map(|name| node_text(source, name))
+                    .or_else(|| child_text_by_kind(source, n, "simple_identifier"));
+            }
+            "variable_declarator" => {
+                if let Some(name) = n.child_by_field_name("name") {
+                    return Some(node_text(source, name));
+                }
+            }
+            _ => {}
+        }
+        cur = n.parent();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::java_parser::JavaLanguage;
+    use crate::ingest::kotlin_parser::KotlinLanguage;
+    use crate::ingest::language::LanguageSupport;
+    use crate::ingest::rust_parser::RustLanguage;
+    use crate::ingest::ts_parser::ScriptLanguage;
+
+    fn calls(language: &dyn LanguageSupport, file_path: &str, source: &str) -> Vec<Document> {
+        let file = SourceFile {
+            repo: "billing",
+            file_path,
+            source,
+        };
+        language
+            .parse_file(&file, false)
+            .expect("parse")
+            .into_iter()
+            .filter(|d| d.kind == DocumentKind::HttpCall)
+            .collect()
+    }
+
+    fn summary(docs: &[Document]) -> Vec<(String, String, String)> {
+        docs.iter()
+            .map(|d| {
+                let field = |k: &str| d.metadata[k].as_str().unwrap_or("-").to_string();
+                (
+                    d.symbol_name.clone(),
+                    field("base_url_key"),
+                    field("client"),
+                )
+            })
+            .collect()
+    }
+
+    fn row(call: &str, key: &str, client: &str) -> (String, String, String) {
+        (call.into(), key.into(), client.into())
+    }
+
+    #[test]
+    fn rust_reqwest_calls() {
+        let src = r#"
+impl OrdersClient {
+    pub async fn create(&self, order: &Order) -> Result<()> {
+        self.http.post(format!("{}/orders", self.base_url)).json(order).send().await?;
+        Ok(())
+    }
+    pub async fn get(&self, id: &str) -> Result<Order> {
+        let base = std::env::var("ORDERS_URL")?;
+        self.client.get(&format!("{base}/orders/{id}")).send().await?.json().await
+    }
+}
+fn ping() { reqwest::get("http://orders:8080/health?full=1"); }
+fn unrelated(map: HashMap<String, String>) { map.get(&key); cache.get("x"); }
+"#;
+        let docs = calls(&RustLanguage, "src/orders.rs", src);
+        assert_eq!(
+            summary(&docs),
+            vec![
+                row("POST /orders", "base_url", "reqwest"),
+                row("GET /orders/{}", "base", "reqwest"),
+                row("GET /health", "-", "reqwest"),
+            ]
+        );
+        assert_eq!(docs[0].metadata["url"], "{self.base_url}/orders");
+        assert_eq!(docs[0].metadata["caller"], "create");
+        assert_eq!(docs[2].metadata["host"], "orders:8080");
+    }
+
+    #[test]
+    fn js_fetch_and_axios_calls() {
+        let src = r#"
+export async function charge(id: string) {
+  await fetch(`${process.env.PAYMENTS_URL}/payments/${id}/capture`, { method: 'POST' });
+  const order = await axios.get(ORDERS_URL + '/orders/' + id);
+  return ordersApi.delete(`/orders/${id}`);
+}
+"#;
+        let docs = calls(&ScriptLanguage::TYPESCRIPT, "src/charge.ts", src);
+        assert_eq!(
+            summary(&docs),
+            vec![
+                row("POST /payments/{}/capture", "PAYMENTS_URL", "fetch"),
+                row("GET /orders/{}", "ORDERS_URL", "axios"),
+                row("DELETE /orders/{}", "-", "axios"),
+            ]
+        );
+        assert_eq!(docs[0].metadata["caller"], "charge");
+    }
+
+    #[test]
+    fn kotlin_and_java_clients() {
+        let kt = "class Orders(private val client: HttpClient) {\n    suspend fun create(o: Order) = client.post(\"$baseUrl/orders\") { setBody(o) }\n    fun legacy() = http.newCall(Request.Builder().url(\"http://orders/orders/$id\").put(body).build())\n}\n";
+        assert_eq!(
+            summary(&calls(&KotlinLanguage, "src/Orders.kt", kt)),
+            vec![
+                row("POST /orders", "baseUrl", "ktor"),
+                row("PUT /orders/{}", "-", "okhttp"),
+            ]
+        );
+
+        let java = r#"class OrdersClient {
+    void create(Order o) {
+        restTemplate.postForObject(ordersUrl + "/orders", o, Order.class);
+        restTemplate.exchange(String.format("%s/orders/%s", ordersUrl, id), HttpMethod.PATCH, entity, Void.class);
+        Request r = new Request.Builder().url(ordersUrl + "/orders").build();
+    }
+}
+"#;
+        assert_eq!(
+            summary(&calls(&JavaLanguage, "src/OrdersClient.java", java)),
+            vec![
+                row("POST /orders", "ordersUrl", "resttemplate"),
+                row("PATCH /orders/{}", "ordersUrl", "resttemplate"),
+                row("GET /orders", "ordersUrl", "okhttp"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,296 @@
+//! java_parser.rs
+//!
+//! Java support for the language registry (tree-sitter-java).
+//! Documents include:
+//! - classes, interfaces, records, enums (nested ones get parent_type)
+//! - methods and constructors (parent_type = `.`-joined enclosing type chain,
+//!   e.g. `OrderController.Request`)
+//! - one `endpoint` per route of a Spring `@GetMapping`/`@RequestMapping`
+//!   method (see `routes.rs`)
+//! - RestTemplate / OkHttp calls as `http_call` documents (see `http_calls.rs`)
+//! - `@KafkaListener`s and Kafka / NATS client usages as `topic` documents
+//!   (see `messaging.rs`)
+//! - SQL string literals as `sql_query` documents (see `sql.rs`)
+//!
+//! Notes:
+//! - Members of anonymous classes and types local to a method body are skipped.
+//! - Annotations (`@RestController`, `@GetMapping("/orders")`,
+//!   `@KafkaListener(topics = "orders")`) stay in the code and signature and
+//!   are also recorded as `annotations` (name + argument text), so they can be
+//!   filtered on at query time (`query --annotation GetMapping`).
+//! - Doc comments are the Javadoc `/** ... */` block directly above the
+//!   declaration (annotations are part of the declaration node, below it).
+//! - Signatures are the declaration header without its body, whitespace-normalized.
+//! - qualified_name is `package` + type chain + name, `.`-joined
+//!   (`com.shop.orders.OrderController.list`).
+
+use crate::ingest::http_calls;
+use crate::ingest::language::{LanguageSupport, SourceFile};
+use crate::ingest::messaging;
+use crate::ingest::routes;
+use crate::ingest::rust_parser::{
+    Annotation, Document, DocumentKind, ParseLanguage, block_doc_comment_above, lines_of,
+    node_text, normalize_ws,
+};
+use crate::ingest::sql;
+use tree_sitter::Node;
+
+pub struct JavaLanguage;
+
+impl LanguageSupport for JavaLanguage {
+    fn name(&self) -> &'static str {
+        "java"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["java"]
+    }
+
+    fn grammar(&self) -> Option<ParseLanguage> {
+        Some(ParseLanguage::Java)
+    }
+
+    fn visit(&self, file: &SourceFile, node: Node, out: &mut Vec<Document>) {
+        let kind = match node.kind() {
+            "class_declaration" => DocumentKind::Class,
+            "interface_declaration" => DocumentKind::Interface,
+            "record_declaration" => DocumentKind::Record,
+            "enum_declaration" => DocumentKind::Enum,
+            "method_declaration" | "constructor_declaration" => DocumentKind::Method,
+            "method_invocation" | "object_creation_expression" | "annotation" => {
+                http_calls::java_http_call(file, node, out);
+                messaging::java_topic_usage(file, node, out);
+                return;
+            }
+            // JDBC / JPA queries (`@Query("...")`, text blocks)
+            "string_literal" => {
+                sql::sql_query(file, node, out);
+                return;
+            }
+            _ => return,
+        };
+        let Some(doc) = extract_declaration(file, node, kind) else {
+            return;
+        };
+        // Spring `@GetMapping`/`@RequestMapping` methods also yield their routes
+        let mut routes = Vec::new();
+        if node.kind() == "method_declaration" {
+            routes::spring_routes(file, node, &doc, &mut routes);
+        }
+        out.push(doc);
+        out.extend(routes);
+    }
+
+    /// The `package` declaration, if any.
+    fn file_module(&self, file: &SourceFile, root: Node) -> Option<String> {
+        let package = (0..root.named_child_count())
+            .filter_map(|i| root.named_child(i))
+            .find(|ch| ch.kind() == "package_declaration")?;
+        (0..package.named_child_count())
+            .filter_map(|i| package.named_child(i))
+            .find(|ch| matches!(ch.kind(), "identifier" | "scoped_identifier"))
+            .map(|name| normalize_ws(&node_text(file.source, name)))
+    }
+
+    fn path_separator(&self) -> &'static str {
+        "."
+    }
+
+    fn import_kinds(&self) -> &'static [&'static str] {
+        &["import_declaration"]
+    }
+}
+
+fn extract_declaration(file: &SourceFile, node: Node, kind: DocumentKind) -> Option<Document> {
+    let source = file.source;
+    // `None`: local or anonymous type member
+    let parent_type = enclosing_types(source, node)?;
+    if kind == DocumentKind::Method && parent_type.is_none() {
+        return None;
+    }
+    let name = node.child_by_field_name("name")?;
+    let header_end = node
+        .child_by_field_name("body")
+        .map_or(node.end_byte(), |b| b.start_byte());
+    let signature = normalize_ws(&source[node.start_byte()..header_end])
+        .trim_end_matches(';')
+        .trim_end()
+        .to_string();
+    let (line_start, line_end) = lines_of(&node);
+
+    Some(Document {
+        repo: file.repo.to_string(),
+        file_path: file.file_path.to_string(),
+        symbol_name: node_text(source, name),
+        kind,
+        signature: Some(signature),
+        doc_comment: block_doc_comment_above(source, node),
+        code: node_text(source, node),
+        parent_type,
+        module_path: None,
+        qualified_name: None,
+        annotations: annotations(source, node),
+        metadata: Default::default(),
+        line_start,
+        line_end,
+    })
+}
+
+/// `.`-joined names of the type declarations whose body contains `node`,
+/// outermost first. Returns `None` inside method bodies and anonymous classes.
+fn enclosing_types(source: &str, node: Node) -> Option<Option<String>> {
+    let mut names = Vec::new();
+    let mut cur = node.parent();
+    while let Some(n) = cur {
+        match n.kind() {
+            "class_declaration"
+            | "interface_declaration"
+            | "record_declaration"
+            | "enum_declaration"
+            | "annotation_type_declaration" => {
+                if let Some(name) = n.child_by_field_name("name") {
+                    names.push(node_text(source, name));
+                }
+            }
+            "method_declaration"
+            | "constructor_declaration"
+            | "lambda_expression"
+            | "object_creation_expression"
+            | "block" => return None,
+            _ => {}
+        }
+        cur = n.parent();
+    }
+    if names.is_empty() {
+        return Some(None);
+    }
+    names.reverse();
+    Some(Some(names.join(".")))
+}
+
+/// Annotations in the declaration's `modifiers`, in source order.
+pub(crate) fn annotations(source: &str, decl: Node) -> Vec<Annotation> {
+    let Some(modifiers) = (0..decl.named_child_count())
+        .filter_map(|i| decl.named_child(i))
+        .find(|ch| ch.kind() == "modifiers")
+    else {
+        return Vec::new();
+    };
+    (0..modifiers.named_child_count())
+        .filter_map(|i| modifiers.named_child(i))
+        .filter(|a| matches!(a.kind(), "annotation" | "marker_annotation"))
+        .filter_map(|a| {
+            let name = a.child_by_field_name("name")?;
+            let arguments = a.child_by_field_name("arguments").map(|args| {
+                let text = node_text(source, args);
+                let inner = text
+                    .strip_prefix('(')
+                    .and_then(|t| t.strip_suffix(')'))
+                    .unwrap_or(&text);
+                normalize_ws(inner)
+            });
+            Some(Annotation {
+                name: normalize_ws(&node_text(source, name)),
+                arguments,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::language::fixtures::{find, parse};
+
+    #[test]
+    fn types_methods_and_annotations() {
+        let src = r#"package com.shop.orders;
+
+import org.springframework.web.bind.annotation.*;
+
+/**
+ * Orders HTTP API.
+ */
+@RestController
+@RequestMapping("/orders")
+public class OrderController {
+    private final OrderService service;
+
+    public OrderController(OrderService service) {
+        this.service = service;
+    }
+
+    /** Lists orders. */
+    @GetMapping(value = "/{id}", produces = "application/json")
+    public Order get(@PathVariable String id) {
+        Runnable r = new Runnable() {
+            public void run() {}
+        };
+        return service.get(id);
+    }
+
+    public record Request(String sku, int qty) {}
+
+    enum Status { OPEN, CLOSED }
+}
+
+interface OrderEvents {
+    @KafkaListener(topics = "order.created", groupId = "billing")
+    void onCreated(OrderCreated event);
+}
+"#;
+        let docs = parse(
+            &JavaLanguage,
+            "src/main/java/com/shop/orders/OrderController.java",
+            src,
+        );
+        let names: Vec<&str> = docs.iter().map(|d| d.symbol_name.as_str()).collect();
+        assert!(!names.contains(&"run"));
+
+        let ctrl = find(&docs, "OrderController");
+        assert_eq!(ctrl.kind, DocumentKind::Class);
+        assert_eq!(ctrl.doc_comment.as_deref(), Some("Orders HTTP API."));
+        let ctrl_annotations: Vec<&str> =
+            ctrl.annotations.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(ctrl_annotations, vec!["RestController", "RequestMapping"]);
+        assert_eq!(
+            ctrl.annotations[1].arguments.as_deref(),
+            Some(r#""/orders""#)
+        );
+
+        let get = find(&docs, "get");
+        assert_eq!(get.kind, DocumentKind::Method);
+        assert_eq!(get.parent_type.as_deref(), Some("OrderController"));
+        assert_eq!(get.doc_comment.as_deref(), Some("Lists orders."));
+        assert_eq!(get.annotations[0].name, "GetMapping");
+        assert_eq!(
+            get.annotations[0].arguments.as_deref(),
+            Some(r#"value = "/{id}", produces = "application/json""#)
+        );
+        assert_eq!(
+            get.qualified_name.as_deref(),
+            Some("com.shop.orders.OrderController.get")
+        );
+
+        let ctor = docs
+            .iter()
+            .find(|d| d.kind == DocumentKind::Method && d.symbol_name == "OrderController")
+            .unwrap();
+        assert_eq!(ctor.parent_type.as_deref(), Some("OrderController"));
+
+        let request = find(&docs, "Request");
+        assert_eq!(request.kind, DocumentKind::Record);
+        assert_eq!(request.parent_type.as_deref(), Some("OrderController"));
+        assert_eq!(find(&docs, "Status").kind, DocumentKind::Enum);
+        assert_eq!(find(&docs, "OrderEvents").kind, DocumentKind::Interface);
+
+        let listener = find(&docs, "onCreated");
+        assert_eq!(
+            listener.signature.as_deref(),
+            Some(
+                r#"@KafkaListener(topics = "order.created", groupId = "billing") void onCreated(OrderCreated event)"#
+            )
+        );
+        assert_eq!(listener.annotations[0].name, "KafkaListener");
+    }
+}
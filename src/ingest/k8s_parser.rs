@@ -0,0 +1,462 @@
+//! k8s_parser.rs
+//!
+//! Kubernetes manifest and Helm values support for the language registry.
+//! Both override `parse_file` (manifests are data, not code) and yield
+//! `deployment` documents (see `manifest.rs`).
+//!
+//! `KubernetesLanguage` claims YAML files under a `k8s` / `kubernetes` /
+//! `kube` / `manifests` / `deploy(ment)(s)` / `overlays` directory and
+//! `*.k8s.{yml,yaml}` files, but not Helm `templates/` (Go templates are not
+//! YAML). Every document of a multi-document file is read:
+//! - workloads (`Deployment`, `StatefulSet`, `DaemonSet`, `ReplicaSet`, `Job`,
+//!   `CronJob`, `Pod`): container (and init container) images,
+//!   `containerPort`s, the containers' env (`valueFrom` as
+//!   `secret:<name>/<key>` etc.), the first container's `resources` and
+//!   `command` + `args`, `replicas`
+//! - `Service`: ports as `port:targetPort`
+//! - `Ingress`: the backend services as `depends_on`
+//!
+//! code is the object's YAML document, kind and namespace are kept.
+//!
+//! `HelmValuesLanguage` claims `values*.{yml,yaml}`. The chart (named by
+//! `nameOverride` / `fullnameOverride`, else its directory) yields one document
+//! from `image.repository:tag`, `service.port` / `targetPort`, `env` /
+//! `extraEnv`, `resources`, `replicaCount`, `command` / `args`; enabled
+//! subcharts (`postgresql.enabled: true`) are its `depends_on`. Top-level keys
+//! with their own `image.repository` (one values file for several components)
+//! yield a document each.
+
+use crate::ingest::language::{LanguageSupport, SourceFile, file_name_lower};
+use crate::ingest::manifest::{
+    Deployment, deployment_document, env_vars, scalar, scalars, to_json, yaml_documents,
+};
+use crate::ingest::openapi_parser::{block_end, find_key};
+use crate::ingest::rust_parser::{Document, RustParserError, with_file_summary};
+use serde_norway::Value;
+use std::path::Path;
+
+const MANIFEST_DIRS: &[&str] = &[
+    "k8s",
+    "kubernetes",
+    "kube",
+    "manifests",
+    "deploy",
+    "deployment",
+    "deployments",
+    "overlays",
+];
+
+pub struct KubernetesLanguage;
+
+impl LanguageSupport for KubernetesLanguage {
+    fn name(&self) -> &'static str {
+        "kubernetes"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["yml", "yaml"]
+    }
+
+    /// Only YAML in manifest directories; other YAML is left alone.
+    fn matches(&self, path: &Path) -> bool {
+        let dirs: Vec<String> = path
+            .parent()
+            .into_iter()
+            .flat_map(|p| p.iter())
+            .filter_map(|c| c.to_str())
+            .map(str::to_ascii_lowercase)
+            .collect();
+        let name = file_name_lower(path);
+        self.has_extension(path)
+            && !dirs.iter().any(|d| d == "templates")
+            && (name.contains(".k8s.") || dirs.iter().any(|d| MANIFEST_DIRS.contains(&d.as_str())))
+    }
+
+    fn parse_file(
+        &self,
+        file: &SourceFile,
+        include_filename_doc: bool,
+    ) -> Result<Vec<Document>, RustParserError> {
+        let documents = yaml_documents(file.source, "Kubernetes manifest")?;
+
+        let mut out = Vec::new();
+        let lines: Vec<&str> = file.source.lines().collect();
+        for (object, (start, end)) in documents {
+            let objects = match object.get("kind").and_then(Value::as_str) {
+                // `kubectl get -o yaml` output
+                Some("List") => list_items(object.get("items")),
+                _ => vec![object],
+            };
+            for object in objects {
+                let Some(deployment) = read_object(&object) else {
+                    continue;
+                };
+                let code = lines
+                    .get(start as usize - 1..end as usize)
+                    .map(|block| block.join("\n"))
+                    .unwrap_or_default();
+                out.push(deployment_document(file, deployment, code, (start, end)));
+            }
+        }
+        Ok(with_file_summary(file, include_filename_doc, out))
+    }
+}
+
+fn list_items(items: Option<&Value>) -> Vec<Value> {
+    match items {
+        Some(Value::Sequence(items)) => items.clone(),
+        _ => Vec::new(),
+    }
+}
+
+/// The deployment described by one Kubernetes object, if it describes one.
+fn read_object(object: &Value) -> Option<Deployment> {
+    let kind = object.get("kind")?.as_str()?;
+    let metadata = object.get("metadata");
+    let mut deployment = Deployment {
+        name: scalar(metadata?.get("name"))?,
+        source: "kubernetes",
+        kind: Some(kind.to_string()),
+        namespace: scalar(metadata.and_then(|m| m.get("namespace"))),
+        ..Deployment::default()
+    };
+    let spec = object.get("spec");
+
+    match kind {
+        "Service" => {
+            let ports = match spec.and_then(|s| s.get("ports")) {
+                Some(Value::Sequence(ports)) => ports.as_slice(),
+                _ => &[],
+            };
+            deployment.ports = ports
+                .iter()
+                .filter_map(|p| {
+                    let port = scalar(p.get("port"))?;
+                    Some(match scalar(p.get("targetPort")) {
+                        Some(target) => format!("{port}:{target}"),
+                        None => port,
+                    })
+                })
+                .collect();
+        }
+        "Ingress" => {
+            let mut backends = Vec::new();
+            collect_backends(spec, &mut backends);
+            backends.dedup();
+            deployment.depends_on = backends;
+        }
+        _ => {
+            let pod = match kind {
+                "Pod" => spec,
+                "CronJob" => spec
+                    .and_then(|s| s.get("jobTemplate"))
+                    .and_then(|j| j.get("spec"))
+                    .and_then(|s| s.get("template"))
+                    .and_then(|t| t.get("spec")),
+                "Deployment" | "StatefulSet" | "DaemonSet" | "ReplicaSet" | "Job" => spec
+                    .and_then(|s| s.get("template"))
+                    .and_then(|t| t.get("spec")),
+                _ => None,
+            }?;
+            read_pod(pod, &mut deployment);
+            deployment.replicas = spec.and_then(|s| s.get("replicas")).and_then(Value::as_u64);
+        }
+    }
+    Some(deployment)
+}
+
+fn read_pod(pod: &Value, deployment: &mut Deployment) {
+    let containers = match pod.get("containers") {
+        Some(Value::Sequence(containers)) => containers.as_slice(),
+        _ => &[],
+    };
+    let init_containers = match pod.get("initContainers") {
+        Some(Value::Sequence(containers)) => containers.as_slice(),
+        _ => &[],
+    };
+    deployment.images = init_containers
+        .iter()
+        .chain(containers)
+        .filter_map(|c| scalar(c.get("image")))
+        .collect();
+    for container in containers {
+        if let Some(Value::Sequence(ports)) = container.get("ports") {
+            deployment
+                .ports
+                .extend(ports.iter().filter_map(|p| scalar(p.get("containerPort"))));
+        }
+        deployment.env.extend(env_vars(container.get("env")));
+    }
+    if let Some(main) = containers.first() {
+        deployment.resources = to_json(main.get("resources"));
+        let command: Vec<String> = ["command", "args"]
+            .into_iter()
+            .flat_map(|key| scalars(main.get(key)))
+            .collect();
+        deployment.command = (!command.is_empty()).then(|| command.join(" "));
+    }
+}
+
+/// `service.name` (networking.k8s.io/v1) / `serviceName` (v1beta1) of every
+/// backend under an Ingress spec.
+fn collect_backends(value: Option<&Value>, out: &mut Vec<String>) {
+    match value {
+        Some(Value::Mapping(map)) => {
+            for (key, child) in map {
+                match key.as_str() {
+                    Some("service") => out.extend(scalar(child.get("name"))),
+                    Some("serviceName") => out.extend(scalar(Some(child))),
+                    _ => collect_backends(Some(child), out),
+                }
+            }
+        }
+        Some(Value::Sequence(items)) => {
+            for item in items {
+                collect_backends(Some(item), out);
+            }
+        }
+        _ => {}
+    }
+}
+
+pub struct HelmValuesLanguage;
+
+impl LanguageSupport for HelmValuesLanguage {
+    fn name(&self) -> &'static str {
+        "helm"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["yml", "yaml"]
+    }
+
+    /// `values.yaml`, `values-prod.yaml`, `values.staging.yml`.
+    fn matches(&self, path: &Path) -> bool {
+        self.has_extension(path) && file_name_lower(path).starts_with("values")
+    }
+
+    fn parse_file(
+        &self,
+        file: &SourceFile,
+        include_filename_doc: bool,
+    ) -> Result<Vec<Document>, RustParserError> {
+        let values: Value =
+            serde_norway::from_str(file.source).map_err(|e| RustParserError::InvalidFormat {
+                format: "Helm values",
+                message: e.to_string(),
+            })?;
+
+        let mut out = Vec::new();
+        let Value::Mapping(top) = &values else {
+            return Ok(with_file_summary(file, include_filename_doc, out));
+        };
+        let lines: Vec<&str> = file.source.lines().collect();
+
+        let chart = scalar(values.get("nameOverride"))
+            .or_else(|| scalar(values.get("fullnameOverride")))
+            .unwrap_or_else(|| chart_name(file));
+        let mut main = read_values(chart, &values);
+        for (key, child) in top {
+            let Some(key) = scalar(Some(key)) else {
+                continue;
+            };
+            if child
+                .get("image")
+                .and_then(|i| i.get("repository"))
+                .is_some()
+            {
+                let (start, end) = find_key(&lines, 0, lines.len(), &key)
+                    .map(|at| (at + 1, block_end(&lines, at) + 1))
+                    .unwrap_or((1, 1));
+                let code = lines
+                    .get(start - 1..end)
+                    .map(|block| block.join("\n"))
+                    .unwrap_or_default();
+                out.push(deployment_document(
+                    file,
+                    read_values(key, child),
+                    code,
+                    (start as u32, end as u32),
+                ));
+            } else if child.get("enabled").and_then(Value::as_bool) == Some(true) {
+                main.depends_on.push(key);
+            }
+        }
+        let line_end = lines.len().max(1) as u32;
+        out.insert(
+            0,
+            deployment_document(file, main, file.source.to_string(), (1, line_end)),
+        );
+        Ok(with_file_summary(file, include_filename_doc, out))
+    }
+}
+
+/// The chart directory (repo at the root).
+fn chart_name(file: &SourceFile) -> String {
+    Path::new(file.file_path)
+        .parent()
+        .and_then(|p| p.file_name())
+        .and_then(|n| n.to_str())
+        .unwrap_or(file.repo)
+        .to_string()
+}
+
+fn read_values(name: String, values: &Value) -> Deployment {
+    let image = values.get("image");
+    let images = scalar(image.and_then(|i| i.get("repository")))
+        .map(|repo| match scalar(image.and_then(|i| i.get("tag"))) {
+            Some(tag) if !tag.is_empty() => format!("{repo}:{tag}"),
+            _ => repo,
+        })
+        .into_iter()
+        .collect();
+
+    let service = values.get("service");
+    let ports = scalar(service.and_then(|s| s.get("port")))
+        .map(
+            |port| match scalar(service.and_then(|s| s.get("targetPort"))) {
+                Some(target) => format!("{port}:{target}"),
+                None => port,
+            },
+        )
+        .into_iter()
+        .collect();
+
+    let mut env = env_vars(values.get("env"));
+    env.extend(env_vars(values.get("extraEnv")));
+    let command: Vec<String> = ["command", "args"]
+        .into_iter()
+        .flat_map(|key| scalars(values.get(key)))
+        .collect();
+
+    Deployment {
+        name,
+        source: "helm",
+        images,
+        ports,
+        env,
+        resources: to_json(values.get("resources"))
+            .filter(|r| r.as_object().is_some_and(|o| !o.is_empty())),
+        replicas: values.get("replicaCount").and_then(Value::as_u64),
+        command: (!command.is_empty()).then(|| command.join(" ")),
+        ..Deployment::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parse(lang: &dyn LanguageSupport, file_path: &str, source: &str) -> Vec<Document> {
+        let file = SourceFile {
+            repo: "orders",
+            file_path,
+            source,
+        };
+        lang.parse_file(&file, false).expect("parse")
+    }
+
+    #[test]
+    fn matches_manifest_locations() {
+        let k8s = KubernetesLanguage;
+        assert!(k8s.matches(Path::new("k8s/deployment.yaml")));
+        assert!(k8s.matches(Path::new("deploy/overlays/prod/service.yml")));
+        assert!(k8s.matches(Path::new("orders.k8s.yaml")));
+        assert!(!k8s.matches(Path::new("charts/orders/templates/deployment.yaml")));
+        assert!(!k8s.matches(Path::new(".github/workflows/ci.yml")));
+        assert!(HelmValuesLanguage.matches(Path::new("charts/orders/values-prod.yaml")));
+        assert!(!HelmValuesLanguage.matches(Path::new("charts/orders/Chart.yaml")));
+    }
+
+    #[test]
+    fn workloads_and_services() {
+        let src = "\
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: orders
+  namespace: shop
+spec:
+  replicas: 3
+  template:
+    spec:
+      containers:
+        - name: orders
+          image: registry.local/orders:1.4.0
+          args: [\"--port\", \"8080\"]
+          ports:
+            - containerPort: 8080
+          env:
+            - name: PAYMENTS_URL
+              value: http://payments.shop.svc.cluster.local:8081
+            - name: DB_PASSWORD
+              valueFrom:
+                secretKeyRef:
+                  name: orders-db
+                  key: password
+          resources:
+            limits:
+              cpu: 500m
+---
+apiVersion: v1
+kind: Service
+metadata:
+  name: orders
+spec:
+  ports:
+    - port: 80
+      targetPort: 8080
+";
+        let docs = parse(&KubernetesLanguage, "k8s/orders.yaml", src);
+        assert_eq!(docs.len(), 2);
+
+        let deployment = &docs[0];
+        assert_eq!((deployment.line_start, deployment.line_end), (1, 26));
+        let meta = &deployment.metadata;
+        assert_eq!(meta["kind"], "Deployment");
+        assert_eq!(meta["namespace"], "shop");
+        assert_eq!(meta["replicas"], 3);
+        assert_eq!(meta["ports"], json!(["8080"]));
+        assert_eq!(meta["env"]["DB_PASSWORD"], "secret:orders-db/password");
+        assert_eq!(meta["service_hosts"], json!(["payments"]));
+        assert_eq!(meta["resources"], json!({ "limits": { "cpu": "500m" } }));
+        assert_eq!(meta["command"], "--port 8080");
+
+        assert_eq!(
+            docs[1].signature.as_deref(),
+            Some("orders (Service): ports 80:8080")
+        );
+        assert_eq!((docs[1].line_start, docs[1].line_end), (28, 35));
+    }
+
+    #[test]
+    fn helm_values() {
+        let src = "\
+replicaCount: 2
+image:
+  repository: shop/orders
+  tag: \"1.4.0\"
+service:
+  port: 80
+  targetPort: 8080
+env:
+  KAFKA_BROKERS: kafka:9092
+resources: {}
+postgresql:
+  enabled: true
+worker:
+  image:
+    repository: shop/orders-worker
+";
+        let docs = parse(&HelmValuesLanguage, "charts/orders/values.yaml", src);
+        let names: Vec<&str> = docs.iter().map(|d| d.symbol_name.as_str()).collect();
+        assert_eq!(names, vec!["orders", "worker"]);
+        assert_eq!(
+            docs[0].signature.as_deref(),
+            Some("orders (helm): image shop/orders:1.4.0, ports 80:8080, depends on postgresql")
+        );
+        assert_eq!(docs[0].metadata["resources"], json!(null));
+        assert_eq!((docs[1].line_start, docs[1].line_end), (13, 15));
+    }
+}
@@ -0,0 +1,338 @@
+//! kotlin_parser.rs
+//!
+//! Kotlin support for the language registry (tree-sitter-kotlin).
+//! Documents include:
+//! - classes (`class`, `data class`, ...), interfaces, enum classes
+//! - objects and companion objects (unnamed companions are called `Companion`)
+//! - functions; inside a class/object body they become methods
+//! - Ktor routes (`get("/orders") { ... }` in a routing block) as `endpoint`
+//!   documents (see `routes.rs`)
+//! - Ktor `HttpClient` / OkHttp calls as `http_call` documents (see `http_calls.rs`)
+//! - `@KafkaListener`s and Kafka / NATS client usages as `topic` documents
+//!   (see `messaging.rs`)
+//! - SQL string literals as `sql_query` documents (see `sql.rs`)
+//!
+//! Notes:
+//! - parent_type is the `.`-joined chain of enclosing types (`Order.Factory`);
+//!   for extension functions it is the receiver type (`fun String.slug()` →
+//!   `String`), wherever the function is declared.
+//! - Doc comments are the KDoc `/** ... */` block directly above the declaration
+//!   (annotations are part of the declaration node, so they sit below the KDoc).
+//! - Signatures are the declaration header without its body, whitespace-normalized.
+//! - qualified_name is `package` + parent_type + name, `.`-joined
+//!   (`com.shop.orders.Order.total`).
+
+use crate::ingest::http_calls;
+use crate::ingest::language::{LanguageSupport, SourceFile};
+use crate::ingest::messaging;
+use crate::ingest::routes;
+use crate::ingest::rust_parser::{
+    Document, DocumentKind, ParseLanguage, block_doc_comment_above, child_text_by_kind, lines_of,
+    node_text, normalize_ws,
+};
+use crate::ingest::sql;
+use tree_sitter::Node;
+
+pub struct KotlinLanguage;
+
+impl LanguageSupport for KotlinLanguage {
+    fn name(&self) -> &'static str {
+        "kotlin"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["kt", "kts"]
+    }
+
+    fn grammar(&self) -> Option<ParseLanguage> {
+        Some(ParseLanguage::Kotlin)
+    }
+
+    fn visit(&self, file: &SourceFile, node: Node, out: &mut Vec<Document>) {
+        let doc = match node.kind() {
+            "class_declaration" | "object_declaration" | "companion_object" => {
+                extract_type_declaration(file, node)
+            }
+            "function_declaration" => extract_function(file, node),
+            "call_expression" => {
+                routes::ktor_route(file, node, out);
+                http_calls::kotlin_http_call(file, node, out);
+                messaging::kotlin_topic_usage(file, node, out);
+                None
+            }
+            "annotation" => {
+                messaging::kotlin_topic_usage(file, node, out);
+                None
+            }
+            "string_literal" => {
+                sql::sql_query(file, node, out);
+                None
+            }
+            _ => None,
+        };
+        out.extend(doc);
+    }
+
+    /// The `package` declaration, if any.
+    fn file_module(&self, file: &SourceFile, root: Node) -> Option<String> {
+        (0..root.child_count())
+            .filter_map(|i| root.child(i))
+            .find(|ch| ch.kind() == "package_header")
+            .and_then(|header| child_text_by_kind(file.source, header, "identifier"))
+            .map(|pkg| normalize_ws(&pkg))
+    }
+
+    fn path_separator(&self) -> &'static str {
+        "."
+    }
+
+    fn import_kinds(&self) -> &'static [&'static str] {
+        &["import_list"]
+    }
+}
+
+fn extract_type_declaration(file: &SourceFile, node: Node) -> Option<Document> {
+    let source = file.source;
+    let kind = match node.kind() {
+        "object_declaration" | "companion_object" => DocumentKind::Object,
+        _ if has_token(node, "interface") => DocumentKind::Interface,
+        _ if has_token(node, "enum") => DocumentKind::Enum,
+        _ => DocumentKind::Class,
+    };
+    let name = type_name(source, node)?;
+
+    Some(build_document(
+        file,
+        node,
+        name,
+        kind,
+        enclosing_type_path(source, node),
+    ))
+}
+
+fn extract_function(file: &SourceFile, node: Node) -> Option<Document> {
+    let source = file.source;
+    let name = child_text_by_kind(source, node, "simple_identifier")?;
+    let enclosing = enclosing_type_path(source, node);
+    let kind = if enclosing.is_some() {
+        DocumentKind::Method
+    } else {
+        DocumentKind::Function
+    };
+    let parent_type = extension_receiver(source, node).or(enclosing);
+
+    Some(build_document(file, node, name, kind, parent_type))
+}
+
+fn build_document(
+    file: &SourceFile,
+    node: Node,
+    symbol_name: String,
+    kind: DocumentKind,
+    parent_type: Option<String>,
+) -> Document {
+    let (line_start, line_end) = lines_of(&node);
+    Document {
+        repo: file.repo.to_string(),
+        file_path: file.file_path.to_string(),
+        symbol_name,
+        kind,
+        signature: Some(declaration_header(file.source, node)),
+        doc_comment: block_doc_comment_above(file.source, node),
+        code: node_text(file.source, node),
+        parent_type,
+        module_path: None,
+        qualified_name: None,
+        annotations: Vec::new(),
+        metadata: Default::default(),
+        line_start,
+        line_end,
+    }
+}
+
+/// Declared name of a class/object; unnamed companion objects are `Companion`.
+fn type_name(source: &str, node: Node) -> Option<String> {
+    child_text_by_kind(source, node, "type_identifier")
+        .or_else(|| (node.kind() == "companion_object").then(|| "Companion".to_string()))
+}
+
+/// `.`-joined names of the classes/objects whose body contains `node`,
+/// outermost first. Stops at an enclosing function: local declarations are
+/// not members.
+fn enclosing_type_path(source: &str, node: Node) -> Option<String> {
+    let mut names = Vec::new();
+    let mut cur = node.parent();
+    while let Some(n) = cur {
+        match n.kind() {
+            "class_declaration" | "object_declaration" | "companion_object" => {
+                names.extend(type_name(source, n));
+            }
+            "function_declaration" | "function_body" | "lambda_literal" => break,
+            _ => {}
+        }
+        cur = n.parent();
+    }
+    if names.is_empty() {
+        return None;
+    }
+    names.reverse();
+    Some(names.join("."))
+}
+
+/// Receiver type of an extension function: the type node right before the
+/// `.` that precedes the function name.
+pub(crate) fn extension_receiver(source: &str, func: Node) -> Option<String> {
+    let mut receiver = None;
+    for i in 0..func.child_count() {
+        let ch = func.child(i)?;
+        match ch.kind() {
+            "simple_identifier" => break,
+            "." => return receiver,
+            "user_type" | "nullable_type" | "parenthesized_type" | "function_type" => {
+                receiver = Some(normalize_ws(&node_text(source, ch)));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Declaration text up to (excluding) its body.
+fn declaration_header(source: &str, node: Node) -> String {
+    let mut cutoff = node.end_byte();
+    for i in 0..node.child_count() {
+        if let Some(ch) = node.child(i)
+            && matches!(
+                ch.kind(),
+                "class_body" | "enum_class_body" | "function_body"
+            )
+        {
+            cutoff = ch.start_byte();
+            break;
+        }
+    }
+    normalize_ws(&source[node.start_byte()..cutoff])
+}
+
+fn has_token(node: Node, kind: &str) -> bool {
+    (0..node.child_count())
+        .filter_map(|i| node.child(i))
+        .any(|ch| ch.kind() == kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::language::fixtures::{find, parse};
+
+    #[test]
+    fn classes_objects_and_members() {
+        let src = r#"package com.shop.orders
+
+import io.ktor.server.Application
+
+/**
+ * Order aggregate.
+ */
+@Serializable
+data class Order(val id: String) : Base() {
+    /** Sum of all lines. */
+    fun total(x: Int): Int = x
+
+    companion object Factory {
+        fun create(): Order = Order("1")
+    }
+}
+
+interface OrderRepo {
+    suspend fun find(id: String): Order?
+}
+
+object Registry {
+    fun register() {}
+}
+
+enum class Status { OPEN, CLOSED }
+"#;
+        let docs = parse(&KotlinLanguage, "src/main/kotlin/Order.kt", src);
+
+        let order = find(&docs, "Order");
+        assert_eq!(order.kind, DocumentKind::Class);
+        assert_eq!(order.doc_comment.as_deref(), Some("Order aggregate."));
+        assert_eq!(
+            order.signature.as_deref(),
+            Some("@Serializable data class Order(val id: String) : Base()")
+        );
+
+        let total = find(&docs, "total");
+        assert_eq!(total.kind, DocumentKind::Method);
+        assert_eq!(total.parent_type.as_deref(), Some("Order"));
+        assert_eq!(total.doc_comment.as_deref(), Some("Sum of all lines."));
+
+        let factory = find(&docs, "Factory");
+        assert_eq!(factory.kind, DocumentKind::Object);
+        assert_eq!(factory.parent_type.as_deref(), Some("Order"));
+        let create = find(&docs, "create");
+        assert_eq!(create.parent_type.as_deref(), Some("Order.Factory"));
+
+        assert_eq!(find(&docs, "OrderRepo").kind, DocumentKind::Interface);
+        assert_eq!(
+            find(&docs, "find").parent_type.as_deref(),
+            Some("OrderRepo")
+        );
+        assert_eq!(find(&docs, "Registry").kind, DocumentKind::Object);
+        assert_eq!(find(&docs, "Status").kind, DocumentKind::Enum);
+        assert!(find(&docs, "Order").line_start > 1);
+        assert_eq!(
+            create.qualified_name.as_deref(),
+            Some("com.shop.orders.Order.Factory.create")
+        );
+    }
+
+    #[test]
+    fn extension_functions_and_unnamed_companion() {
+        let src = r#"
+/** Slug form. */
+fun String.toSlug(): String = lowercase()
+
+fun <T> List<T>.second(): T = this[1]
+
+fun main() {}
+
+class Money {
+    companion object {
+        fun zero() = Money()
+    }
+    fun Int.cents(): Money = Money()
+}
+"#;
+        let docs = parse(&KotlinLanguage, "src/main/kotlin/Order.kt", src);
+
+        let slug = find(&docs, "toSlug");
+        assert_eq!(slug.kind, DocumentKind::Function);
+        assert_eq!(slug.parent_type.as_deref(), Some("String"));
+        assert_eq!(slug.doc_comment.as_deref(), Some("Slug form."));
+        assert_eq!(
+            find(&docs, "second").parent_type.as_deref(),
+            Some("List<T>")
+        );
+
+        let main = find(&docs, "main");
+        assert_eq!(main.kind, DocumentKind::Function);
+        // no `package` declaration
+        assert_eq!(main.qualified_name.as_deref(), Some("main"));
+        assert_eq!(main.parent_type, None);
+        assert_eq!(main.doc_comment, None);
+
+        let companion = find(&docs, "Companion");
+        assert_eq!(companion.kind, DocumentKind::Object);
+        assert_eq!(
+            find(&docs, "zero").parent_type.as_deref(),
+            Some("Money.Companion")
+        );
+
+        let cents = find(&docs, "cents");
+        assert_eq!(cents.kind, DocumentKind::Method);
+        assert_eq!(cents.parent_type.as_deref(), Some("Int"));
+    }
+}
@@ -0,0 +1,319 @@
+//! language.rs
+//!
+//! Language registry shared by the scanner (which files to read) and the
+//! indexing loop (how to turn them into Documents).
+//!
+//! A language is one `LanguageSupport` impl:
+//! - `extensions()` / `matches()` decide which files it owns (custom matchers
+//!   build on `has_extension()` and `file_name_lower()`)
+//! - `grammar()` picks the tree-sitter grammar (one cached `CodeParser` per
+//!   grammar and thread, see `with_parser`)
+//! - `visit()` holds the language-specific extraction rules, called for every
+//!   node of the parsed tree
+//! - `file_module()` / `path_separator()` build each Document's `qualified_name`
+//! - `import_kinds()` / `module_doc()` feed the file summary document
+//! - formats without a tree-sitter grammar override `parse_file()` instead
+//!
+//! Adding a language = implementing the trait + one `register` call in
+//! `LanguageRegistry::default()`. The first registered match wins, so more
+//! specific matchers must be registered before generic ones.
+
+use crate::index::id_generator::symbol_segments;
+use crate::ingest::compose_parser::ComposeLanguage;
+use crate::ingest::dockerfile_parser::DockerfileLanguage;
+use crate::ingest::go_parser::GoLanguage;
+use crate::ingest::java_parser::JavaLanguage;
+use crate::ingest::k8s_parser::{HelmValuesLanguage, KubernetesLanguage};
+use crate::ingest::kotlin_parser::KotlinLanguage;
+use crate::ingest::markdown_parser::{AsciiDocLanguage, MarkdownLanguage};
+use crate::ingest::openapi_parser::OpenApiLanguage;
+use crate::ingest::proto_parser::ProtoLanguage;
+use crate::ingest::python_parser::PythonLanguage;
+use crate::ingest::rust_parser::RustLanguage;
+use crate::ingest::rust_parser::{
+    Document, DocumentKind, ParseLanguage, RustParserError, build_file_summary, with_parser,
+};
+use crate::ingest::spring_config_parser::SpringConfigLanguage;
+use crate::ingest::sql_parser::{LiquibaseLanguage, SqlLanguage};
+use crate::ingest::ts_parser::ScriptLanguage;
+use std::path::Path;
+use tree_sitter::Node;
+
+/// The file being parsed, handed to extraction rules.
+#[derive(Debug, Clone, Copy)]
+pub struct SourceFile<'a> {
+    pub repo: &'a str,
+    /// Relative to the repo root.
+    pub file_path: &'a str,
+    pub source: &'a str,
+}
+
+/// Test fixtures shared by the per-language parser tests.
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use super::{LanguageSupport, SourceFile};
+    use crate::ingest::rust_parser::Document;
+
+    /// Parse `source` as `file_path` of repo `orders`, without a file summary.
+    pub(crate) fn parse(
+        language: &dyn LanguageSupport,
+        file_path: &str,
+        source: &str,
+    ) -> Vec<Document> {
+        let file = SourceFile {
+            repo: "orders",
+            file_path,
+            source,
+        };
+        language.parse_file(&file, false).expect("parse")
+    }
+
+    /// The document named `name`.
+    pub(crate) fn find<'a>(docs: &'a [Document], name: &str) -> &'a Document {
+        docs.iter()
+            .find(|d| d.symbol_name == name)
+            .unwrap_or_else(|| panic!("no document for {name}"))
+    }
+}
+
+pub trait LanguageSupport: Send + Sync {
+    /// Short lowercase name, e.g. `rust`.
+    fn name(&self) -> &'static str;
+
+    /// File extensions (without the dot) owned by this language.
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// Whether this language handles `path`. Defaults to an extension match.
+    fn matches(&self, path: &Path) -> bool {
+        self.has_extension(path)
+    }
+
+    /// Whether `path` has one of `extensions()`; the base of custom `matches`.
+    fn has_extension(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|x| x.to_str())
+            .is_some_and(|ext| self.extensions().contains(&ext))
+    }
+
+    /// Tree-sitter grammar, if the language is parsed with tree-sitter.
+    fn grammar(&self) -> Option<ParseLanguage> {
+        None
+    }
+
+    /// Extraction rules: inspect one node (visited in DFS pre-order) and push
+    /// any Documents it yields.
+    fn visit(&self, _file: &SourceFile, _node: Node, _out: &mut Vec<Document>) {}
+
+    /// Module/package every symbol of the file lives in (derived from the file
+    /// location or a `package` declaration); first segment of `qualified_name`.
+    fn file_module(&self, _file: &SourceFile, _root: Node) -> Option<String> {
+        None
+    }
+
+    /// Separator between `qualified_name` segments.
+    fn path_separator(&self) -> &'static str {
+        "::"
+    }
+
+    /// Kinds of the top-level nodes holding imports (`use_declaration`, ...),
+    /// listed in the file summary.
+    fn import_kinds(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// The file's own documentation (Rust `//!` lines, a Python module
+    /// docstring, ...), the file summary's doc comment.
+    fn module_doc(&self, _file: &SourceFile, _root: Node) -> Option<String> {
+        None
+    }
+
+    /// Parse a file into Documents.
+    fn parse_file(
+        &self,
+        file: &SourceFile,
+        include_filename_doc: bool,
+    ) -> Result<Vec<Document>, RustParserError> {
+        match self.grammar() {
+            Some(grammar) => {
+                with_parser(grammar, |p| p.parse_file(self, file, include_filename_doc))
+            }
+            None if include_filename_doc => Ok(vec![build_file_summary(file, None, &[], &[])]),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Lowercased file name of `path`, for name-based `matches` rules.
+pub fn file_name_lower(path: &Path) -> String {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// Joins file module and the symbol segments of `doc` (the ones its point id
+/// is keyed on, see `id_generator::symbol_segments`) with `sep`; filename
+/// documents are named after their module.
+pub fn qualified_name(file_module: Option<&str>, doc: &Document, sep: &str) -> String {
+    if doc.kind == DocumentKind::Filename {
+        return file_module.unwrap_or(&doc.file_path).to_string();
+    }
+
+    let mut parts: Vec<String> = file_module
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .map(str::to_string)
+        .into_iter()
+        .collect();
+    parts.extend(symbol_segments(
+        doc.module_path.as_deref(),
+        doc.parent_type.as_deref(),
+        &doc.symbol_name,
+        doc.kind.as_str(),
+    ));
+    parts.join(sep)
+}
+
+/// Ordered set of supported languages.
+pub struct LanguageRegistry {
+    languages: Vec<Box<dyn LanguageSupport>>,
+}
+
+impl Default for LanguageRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register(RustLanguage);
+        registry.register(KotlinLanguage);
+        registry.register(ScriptLanguage::TYPESCRIPT);
+        registry.register(ScriptLanguage::TSX);
+        registry.register(ScriptLanguage::JAVASCRIPT);
+        registry.register(GoLanguage);
+        registry.register(PythonLanguage);
+        registry.register(JavaLanguage);
+        registry.register(ProtoLanguage);
+        registry.register(OpenApiLanguage);
+        registry.register(SpringConfigLanguage);
+        registry.register(SqlLanguage);
+        registry.register(LiquibaseLanguage);
+        registry.register(DockerfileLanguage);
+        registry.register(ComposeLanguage);
+        registry.register(HelmValuesLanguage);
+        registry.register(KubernetesLanguage);
+        registry.register(MarkdownLanguage);
+        registry.register(AsciiDocLanguage);
+        registry
+    }
+}
+
+impl LanguageRegistry {
+    pub fn empty() -> Self {
+        Self {
+            languages: Vec::new(),
+        }
+    }
+
+    pub fn register<L: LanguageSupport + 'static>(&mut self, language: L) {
+        self.languages.push(Box::new(language));
+    }
+
+    /// The language owning `path`, if any.
+    pub fn for_path(&self, path: &Path) -> Option<&dyn LanguageSupport> {
+        self.languages
+            .iter()
+            .find(|l| l.matches(path))
+            .map(|l| l.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_real_extensions_only() {
+        let registry = LanguageRegistry::default();
+        let name = |p: &str| registry.for_path(Path::new(p)).map(|l| l.name());
+        assert_eq!(name("src/lib.rs"), Some("rust"));
+        assert_eq!(name("src/App.kt"), Some("kotlin"));
+        assert_eq!(name("web/index.ts"), Some("typescript"));
+        assert_eq!(name("web/index.js"), Some("javascript"));
+        assert_eq!(name("web/App.tsx"), Some("tsx"));
+        assert_eq!(name("web/App.jsx"), Some("javascript"));
+        assert_eq!(name("web/server.mjs"), Some("javascript"));
+        assert_eq!(name("cmd/api/main.go"), Some("go"));
+        assert_eq!(name("app/routes.py"), Some("python"));
+        assert_eq!(name("src/main/java/App.java"), Some("java"));
+        assert_eq!(name("proto/orders.proto"), Some("protobuf"));
+        assert_eq!(name("api/openapi.yaml"), Some("openapi"));
+        assert_eq!(
+            name("src/main/resources/application.yml"),
+            Some("spring_config")
+        );
+        assert_eq!(name("migrations/0001_init.sql"), Some("sql"));
+        assert_eq!(
+            name("src/main/resources/db/changelog/db.changelog-master.yaml"),
+            Some("liquibase")
+        );
+        assert_eq!(name("Dockerfile"), Some("dockerfile"));
+        assert_eq!(name("docker-compose.yml"), Some("compose"));
+        assert_eq!(name("charts/orders/values.yaml"), Some("helm"));
+        assert_eq!(name("k8s/deployment.yaml"), Some("kubernetes"));
+        assert_eq!(name("README.md"), Some("markdown"));
+        assert_eq!(name("docs/adr/0001-record.adoc"), Some("asciidoc"));
+        assert_eq!(name("config/app.yaml"), None);
+        // suffix matches that are not extensions
+        assert_eq!(name("docs/changelogs"), None);
+        assert_eq!(name("assets/fonts.woff"), None);
+    }
+
+    #[test]
+    fn first_registered_match_wins() {
+        struct Special;
+        impl LanguageSupport for Special {
+            fn name(&self) -> &'static str {
+                "special"
+            }
+            fn extensions(&self) -> &'static [&'static str] {
+                &[]
+            }
+            fn matches(&self, path: &Path) -> bool {
+                path.ends_with("build.rs")
+            }
+        }
+
+        let mut registry = LanguageRegistry::empty();
+        registry.register(Special);
+        registry.register(RustLanguage);
+        let name = |p: &str| registry.for_path(Path::new(p)).map(|l| l.name());
+        assert_eq!(name("build.rs"), Some("special"));
+        assert_eq!(name("src/main.rs"), Some("rust"));
+    }
+
+    #[test]
+    fn qualified_name_extends_the_point_id_symbol() {
+        use crate::index::id_generator::qualified_symbol;
+
+        let src = "mod inner { pub struct Foo; }\n\
+                   impl std::fmt::Display for Foo {\n    fn fmt(&self) {}\n}\n\
+                   impl Foo {\n    fn new() -> Self { Foo }\n}\n";
+        let file = SourceFile {
+            repo: "billing",
+            file_path: "src/ledger.rs",
+            source: src,
+        };
+        let docs = RustLanguage.parse_file(&file, false).unwrap();
+        assert!(docs.iter().any(|d| d.kind == DocumentKind::Impl));
+        for d in &docs {
+            let symbol = qualified_symbol(
+                d.module_path.as_deref(),
+                d.parent_type.as_deref(),
+                &d.symbol_name,
+                d.kind.as_str(),
+            );
+            assert_eq!(
+                d.qualified_name.as_deref(),
+                Some(format!("billing::ledger::{symbol}").as_str())
+            );
+        }
+    }
+}
@@ -0,0 +1,288 @@
+//! manifest.rs
+//!
+//! Shared pieces of the deployment manifest formats (`dockerfile_parser.rs`,
+//! `compose_parser.rs`, `k8s_parser.rs`): each describes how one service runs
+//! as a `Deployment`, turned into a `deployment` document here.
+//!
+//! symbol_name and qualified_name are the service name (compose service,
+//! Kubernetes object, chart, or the Dockerfile's directory). metadata:
+//! - `source` (`dockerfile` / `compose` / `kubernetes` / `helm`), `kind`
+//!   (Kubernetes kind), `namespace`
+//! - `images`, `build`, `ports` (`published:target` / `port:targetPort` /
+//!   container ports), `env` (`{NAME: value}`; secrets as `secret:<name>/<key>`)
+//! - `depends_on` (declared: compose `depends_on`/`links`) and `service_hosts`
+//!   (hosts the service reaches through its env/command: `kafka` from
+//!   `kafka:9092`, `orders` from `http://orders.shop.svc.cluster.local`)
+//! - `resources` (`{limits, requests}` as written), `replicas`, `command`
+//!
+//! The signature is a one-line summary (`orders (compose): image
+//! shop/orders:1.4, ports 8080:8080, depends on db, kafka`).
+
+use crate::ingest::language::SourceFile;
+use crate::ingest::rust_parser::{Document, DocumentKind, RustParserError};
+use serde::Deserialize;
+use serde_json::{Value as JsonValue, json};
+use serde_norway::Value;
+use std::collections::BTreeMap;
+
+/// How one service is built/run, as far as one manifest tells.
+#[derive(Debug, Default)]
+pub(crate) struct Deployment {
+    pub name: String,
+    pub source: &'static str,
+    pub kind: Option<String>,
+    pub namespace: Option<String>,
+    pub images: Vec<String>,
+    pub build: Option<String>,
+    pub ports: Vec<String>,
+    pub env: BTreeMap<String, String>,
+    pub depends_on: Vec<String>,
+    pub resources: Option<JsonValue>,
+    pub replicas: Option<u64>,
+    pub command: Option<String>,
+}
+
+/// `deployment` document; `lines` is the 1-based range `code` was taken from.
+pub(crate) fn deployment_document(
+    file: &SourceFile,
+    deployment: Deployment,
+    code: String,
+    (line_start, line_end): (u32, u32),
+) -> Document {
+    let hosts: Vec<String> = service_hosts(
+        deployment
+            .env
+            .values()
+            .chain(deployment.command.iter())
+            .map(String::as_str),
+    )
+    .into_iter()
+    .filter(|h| *h != deployment.name)
+    .collect();
+
+    let mut summary = Vec::new();
+    if !deployment.images.is_empty() {
+        summary.push(format!("image {}", deployment.images.join(", ")));
+    }
+    if let Some(build) = &deployment.build {
+        summary.push(format!("build {build}"));
+    }
+    if !deployment.ports.is_empty() {
+        summary.push(format!("ports {}", deployment.ports.join(", ")));
+    }
+    if !deployment.depends_on.is_empty() {
+        summary.push(format!("depends on {}", deployment.depends_on.join(", ")));
+    }
+    let what = deployment.kind.as_deref().unwrap_or(deployment.source);
+    let signature = match summary.is_empty() {
+        true => format!("{} ({what})", deployment.name),
+        false => format!("{} ({what}): {}", deployment.name, summary.join(", ")),
+    };
+
+    let mut metadata = serde_json::Map::new();
+    metadata.insert("source".into(), json!(deployment.source));
+    metadata.insert("kind".into(), json!(deployment.kind));
+    metadata.insert("namespace".into(), json!(deployment.namespace));
+    metadata.insert("images".into(), json!(deployment.images));
+    metadata.insert("build".into(), json!(deployment.build));
+    metadata.insert("ports".into(), json!(deployment.ports));
+    metadata.insert("env".into(), json!(deployment.env));
+    metadata.insert("depends_on".into(), json!(deployment.depends_on));
+    metadata.insert("service_hosts".into(), json!(hosts));
+    metadata.insert("resources".into(), json!(deployment.resources));
+    metadata.insert("replicas".into(), json!(deployment.replicas));
+    metadata.insert("command".into(), json!(deployment.command));
+
+    Document {
+        repo: file.repo.to_string(),
+        file_path: file.file_path.to_string(),
+        qualified_name: Some(deployment.name.clone()),
+        symbol_name: deployment.name,
+        kind: DocumentKind::Deployment,
+        signature: Some(signature),
+        doc_comment: None,
+        code,
+        parent_type: None,
+        module_path: None,
+        annotations: Vec::new(),
+        metadata,
+        line_start,
+        line_end,
+    }
+}
+
+/// Hosts named in connection strings: `kafka:9092`, `http://orders:8080/x`,
+/// `postgres://u:p@db:5432/shop`, `orders.shop.svc.cluster.local` (→ `orders`).
+/// Bare words, IPs and `localhost` are not hosts.
+pub(crate) fn service_hosts<'a>(values: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut hosts: Vec<String> = values
+        .flat_map(|v| v.split([',', ' ', ';', '"', '\'']))
+        .filter_map(|token| {
+            let (scheme, rest) = match token.split_once("://") {
+                Some((_, rest)) => (true, rest),
+                None => (false, token),
+            };
+            let authority = rest.split(['/', '?']).next().unwrap_or_default();
+            let authority = authority.rsplit('@').next().unwrap_or_default();
+            let (host, port) = match authority.rsplit_once(':') {
+                Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => {
+                    (host, !port.is_empty())
+                }
+                _ => (authority, false),
+            };
+            let cluster_dns = host.contains(".svc");
+            let valid = !host.is_empty()
+                && host
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+                && host.chars().any(|c| c.is_ascii_alphabetic())
+                && host != "localhost";
+            (valid && (scheme || port || cluster_dns)).then(|| {
+                match cluster_dns {
+                    true => host.split('.').next().unwrap_or(host),
+                    false => host,
+                }
+                .to_string()
+            })
+        })
+        .collect();
+    hosts.sort();
+    hosts.dedup();
+    hosts
+}
+
+/// One YAML document of a file and its 1-based line range.
+pub(crate) type YamlDocument = (Value, (u32, u32));
+
+/// Every YAML document of a file (`---` separated).
+pub(crate) fn yaml_documents(
+    source: &str,
+    format: &'static str,
+) -> Result<Vec<YamlDocument>, RustParserError> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    for (i, line) in lines.iter().enumerate() {
+        if line.trim_end() == "---" || line.starts_with("--- ") {
+            ranges.push((start, i));
+            start = i + 1;
+        }
+    }
+    ranges.push((start, lines.len()));
+
+    let mut out = Vec::new();
+    for (start, end) in ranges {
+        let text = lines[start..end].join("\n");
+        if text.trim().is_empty() {
+            continue;
+        }
+        let value =
+            Value::deserialize(serde_norway::Deserializer::from_str(&text)).map_err(|e| {
+                RustParserError::InvalidFormat {
+                    format,
+                    message: e.to_string(),
+                }
+            })?;
+        if !value.is_null() {
+            out.push((value, (start as u32 + 1, end.max(start + 1) as u32)));
+        }
+    }
+    Ok(out)
+}
+
+/// `NAME=value` lists, `{NAME: value}` maps and Kubernetes `[{name, value |
+/// valueFrom}]` lists.
+pub(crate) fn env_vars(value: Option<&Value>) -> BTreeMap<String, String> {
+    let mut env = BTreeMap::new();
+    match value {
+        Some(Value::Mapping(map)) => {
+            for (k, v) in map {
+                if let Some(k) = scalar(Some(k)) {
+                    env.insert(k, scalar(Some(v)).unwrap_or_default());
+                }
+            }
+        }
+        Some(Value::Sequence(items)) => {
+            for item in items {
+                if let Some(name) = scalar(item.get("name")) {
+                    let value = scalar(item.get("value"))
+                        .or_else(|| value_from(item.get("valueFrom")))
+                        .unwrap_or_default();
+                    env.insert(name, value);
+                } else if let Some(pair) = scalar(Some(item)) {
+                    let (k, v) = pair.split_once('=').unwrap_or((&pair, ""));
+                    env.insert(k.to_string(), v.to_string());
+                }
+            }
+        }
+        _ => {}
+    }
+    env
+}
+
+/// `secretKeyRef` → `secret:<name>/<key>`, `configMapKeyRef` →
+/// `configmap:<name>/<key>`, `fieldRef` → `field:<path>`.
+fn value_from(value_from: Option<&Value>) -> Option<String> {
+    let value_from = value_from?;
+    let reference = |key: &str, label: &str| {
+        let r = value_from.get(key)?;
+        Some(format!(
+            "{label}:{}/{}",
+            scalar(r.get("name")).unwrap_or_default(),
+            scalar(r.get("key")).unwrap_or_default()
+        ))
+    };
+    reference("secretKeyRef", "secret")
+        .or_else(|| reference("configMapKeyRef", "configmap"))
+        .or_else(|| {
+            let path = scalar(value_from.get("fieldRef")?.get("fieldPath"))?;
+            Some(format!("field:{path}"))
+        })
+}
+
+/// A YAML mapping as JSON (resource limits and the like).
+pub(crate) fn to_json(value: Option<&Value>) -> Option<JsonValue> {
+    value
+        .filter(|v| !v.is_null())
+        .and_then(|v| serde_json::to_value(v).ok())
+}
+
+/// Strings, numbers and booleans as text.
+pub(crate) fn scalar(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Scalars of a sequence (or a lone scalar), e.g. a command given either way.
+pub(crate) fn scalars(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::Sequence(items)) => items.iter().filter_map(|i| scalar(Some(i))).collect(),
+        other => scalar(other).into_iter().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_service_hosts_in_connection_strings() {
+        let values = [
+            "kafka:9092,kafka-2:9092",
+            "postgres://shop:secret@db:5432/shop?sslmode=disable",
+            "http://orders.shop.svc.cluster.local/api",
+            "redis://localhost:6379",
+            "INFO",
+            "10.0.0.4:8080",
+            "java -jar app.jar --spring.profiles.active=prod",
+        ];
+        assert_eq!(
+            service_hosts(values.into_iter()),
+            vec!["db", "kafka", "kafka-2", "orders"]
+        );
+    }
+}
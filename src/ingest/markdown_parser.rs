@@ -0,0 +1,458 @@
+//! markdown_parser.rs
+//!
+//! Markdown (`.md`, `.markdown`) and AsciiDoc (`.adoc`, `.asciidoc`) support
+//! for the language registry: READMEs, `docs/` and architecture decision
+//! records. Both override `parse_file` and split the file by heading into
+//! `doc_section` documents; a section runs from its heading to the next one,
+//! so fenced / delimited code blocks always stay whole (headings inside them
+//! are not headings). Markdown is read with pulldown-cmark, AsciiDoc line by
+//! line (`=`-prefixed titles, `----` / `....` / `` ``` `` blocks).
+//!
+//! symbol_name is the heading, signature the heading path (`Architecture >
+//! Messaging`), qualified_name `<file>#<heading path>`. Text before the first
+//! heading is a section named after the file. Sections with nothing under
+//! their heading are skipped. metadata:
+//! - `format` (`markdown` / `asciidoc`), `heading_path`, `level`
+//! - `doc_type`: `readme`, `adr` (under `adr/` / `decisions/`, or named like
+//!   `0007-adr-use-kafka.md`) or `doc`; `title` (first level-1 heading)
+//! - `status` of an ADR (`## Status` section or `Status: accepted` line)
+//! - `code_languages` of the section's code blocks
+
+use crate::ingest::language::{LanguageSupport, SourceFile, file_name_lower};
+use crate::ingest::rust_parser::{Document, DocumentKind, RustParserError, with_file_summary};
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use serde_json::json;
+use std::path::Path;
+
+const ADR_DIRS: &[&str] = &["adr", "adrs", "decisions", "architecture-decisions"];
+
+/// A heading: level, text and 0-based line range (setext headings span two).
+struct Heading {
+    level: usize,
+    title: String,
+    lines: (usize, usize),
+}
+
+/// A code block's language at a 0-based line.
+struct CodeBlock {
+    line: usize,
+    language: String,
+}
+
+pub struct MarkdownLanguage;
+
+impl LanguageSupport for MarkdownLanguage {
+    fn name(&self) -> &'static str {
+        "markdown"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["md", "markdown"]
+    }
+
+    fn parse_file(
+        &self,
+        file: &SourceFile,
+        include_filename_doc: bool,
+    ) -> Result<Vec<Document>, RustParserError> {
+        let (headings, code_blocks) = markdown_outline(file.source);
+        Ok(sections(
+            file,
+            "markdown",
+            &headings,
+            &code_blocks,
+            include_filename_doc,
+        ))
+    }
+}
+
+pub struct AsciiDocLanguage;
+
+impl LanguageSupport for AsciiDocLanguage {
+    fn name(&self) -> &'static str {
+        "asciidoc"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["adoc", "asciidoc"]
+    }
+
+    fn parse_file(
+        &self,
+        file: &SourceFile,
+        include_filename_doc: bool,
+    ) -> Result<Vec<Document>, RustParserError> {
+        let (headings, code_blocks) = asciidoc_outline(file.source);
+        Ok(sections(
+            file,
+            "asciidoc",
+            &headings,
+            &code_blocks,
+            include_filename_doc,
+        ))
+    }
+}
+
+fn markdown_outline(source: &str) -> (Vec<Heading>, Vec<CodeBlock>) {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let line_of = |offset: usize| line_starts.partition_point(|&s| s <= offset) - 1;
+
+    let options = Options::ENABLE_YAML_STYLE_METADATA_BLOCKS
+        | Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS;
+    let mut headings = Vec::new();
+    let mut code_blocks = Vec::new();
+    let mut open: Option<Heading> = None;
+    for (event, range) in Parser::new_ext(source, options).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                let end = line_of(range.end.saturating_sub(1).max(range.start));
+                open = Some(Heading {
+                    level: level as usize,
+                    title: String::new(),
+                    lines: (line_of(range.start), end),
+                });
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(heading) = open.as_mut() {
+                    heading.title.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                headings.extend(open.take().map(|mut h| {
+                    h.title = h.title.trim().to_string();
+                    h
+                }));
+            }
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info))) => {
+                if let Some(language) = info.split([' ', ',', '{']).next().filter(|l| !l.is_empty())
+                {
+                    code_blocks.push(CodeBlock {
+                        line: line_of(range.start),
+                        language: language.to_string(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    (headings, code_blocks)
+}
+
+/// `----` / `....` / `++++` / `____` / `****` lines, which open and close
+/// AsciiDoc delimited blocks.
+pub(crate) fn is_asciidoc_delimiter(line: &str) -> bool {
+    let first = line.chars().next();
+    line.len() >= 4
+        && matches!(first, Some('-' | '.' | '+' | '_' | '*'))
+        && line.chars().all(|c| Some(c) == first)
+}
+
+/// `= Title` / `== Section` headings outside delimited blocks; block languages
+/// from `[source,java]` attribute lines.
+fn asciidoc_outline(source: &str) -> (Vec<Heading>, Vec<CodeBlock>) {
+    let mut headings = Vec::new();
+    let mut code_blocks = Vec::new();
+    let mut delimiter: Option<&str> = None;
+    let mut pending_language: Option<String> = None;
+    for (i, line) in source.lines().enumerate() {
+        let trimmed = line.trim_end();
+        let is_delimiter = trimmed.starts_with("```") || is_asciidoc_delimiter(trimmed);
+        if let Some(open) = delimiter {
+            if trimmed == open {
+                delimiter = None;
+            }
+            continue;
+        }
+        if is_delimiter {
+            delimiter = Some(if trimmed.starts_with("```") {
+                "```"
+            } else {
+                trimmed
+            });
+            let language = pending_language
+                .take()
+                .or_else(|| trimmed.strip_prefix("```").map(str::to_string))
+                .filter(|l| !l.is_empty());
+            code_blocks.extend(language.map(|language| CodeBlock { line: i, language }));
+            continue;
+        }
+        if let Some(attrs) = trimmed
+            .strip_prefix("[source")
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            pending_language = attrs
+                .trim_start_matches(',')
+                .split(',')
+                .next()
+                .map(|l| l.trim().to_string());
+            continue;
+        }
+        let level = trimmed.chars().take_while(|&c| c == '=').count();
+        if (1..=6).contains(&level) && trimmed[level..].starts_with(' ') {
+            headings.push(Heading {
+                level,
+                title: trimmed[level..].trim().to_string(),
+                lines: (i, i),
+            });
+        }
+        if !trimmed.is_empty() {
+            pending_language = None;
+        }
+    }
+    (headings, code_blocks)
+}
+
+/// One `doc_section` per non-empty section (plus the text before the first
+/// heading).
+fn sections(
+    file: &SourceFile,
+    format: &str,
+    headings: &[Heading],
+    code_blocks: &[CodeBlock],
+    include_filename_doc: bool,
+) -> Vec<Document> {
+    let lines: Vec<&str> = file.source.lines().collect();
+    let path = Path::new(file.file_path);
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(file.file_path);
+    let doc_type = doc_type(path);
+    let title = headings
+        .iter()
+        .find(|h| h.level == 1)
+        .map(|h| h.title.clone());
+    let status = (doc_type == "adr")
+        .then(|| adr_status(&lines, headings))
+        .flatten();
+
+    let mut out = Vec::new();
+
+    // (heading path, level, first line, first body line, end line exclusive)
+    let mut spans: Vec<(Vec<String>, usize, usize, usize, usize)> = Vec::new();
+    let first = headings.first().map_or(lines.len(), |h| h.lines.0);
+    spans.push((Vec::new(), 0, 0, 0, first));
+    let mut stack: Vec<(usize, String)> = Vec::new();
+    for (i, heading) in headings.iter().enumerate() {
+        stack.retain(|(level, _)| *level < heading.level);
+        stack.push((heading.level, heading.title.clone()));
+        let end = headings.get(i + 1).map_or(lines.len(), |h| h.lines.0);
+        spans.push((
+            stack.iter().map(|(_, t)| t.clone()).collect(),
+            heading.level,
+            heading.lines.0,
+            heading.lines.1 + 1,
+            end,
+        ));
+    }
+
+    for (heading_path, level, start, body, end) in spans {
+        let end = (body..end)
+            .rev()
+            .find(|&i| !lines[i].trim().is_empty())
+            .map_or(body, |i| i + 1);
+        if end <= body {
+            continue;
+        }
+        let languages: Vec<&str> = code_blocks
+            .iter()
+            .filter(|b| (start..end).contains(&b.line))
+            .map(|b| b.language.as_str())
+            .collect();
+        let symbol_name = heading_path.last().map_or(stem, String::as_str).to_string();
+        let signature = match heading_path.is_empty() {
+            true => stem.to_string(),
+            false => heading_path.join(" > "),
+        };
+
+        let mut metadata = serde_json::Map::new();
+        metadata.insert("format".into(), json!(format));
+        metadata.insert("heading_path".into(), json!(heading_path));
+        metadata.insert("level".into(), json!(level));
+        metadata.insert("doc_type".into(), json!(doc_type));
+        metadata.insert("title".into(), json!(title));
+        metadata.insert("status".into(), json!(status));
+        metadata.insert("code_languages".into(), json!(languages));
+
+        out.push(Document {
+            repo: file.repo.to_string(),
+            file_path: file.file_path.to_string(),
+            qualified_name: Some(format!("{}#{signature}", file.file_path)),
+            symbol_name,
+            kind: DocumentKind::DocSection,
+            signature: Some(signature),
+            doc_comment: None,
+            code: lines[start..end].join("\n"),
+            parent_type: None,
+            module_path: None,
+            annotations: Vec::new(),
+            metadata,
+            line_start: start as u32 + 1,
+            line_end: end as u32,
+        });
+    }
+    with_file_summary(file, include_filename_doc, out)
+}
+
+fn doc_type(path: &Path) -> &'static str {
+    let name = file_name_lower(path);
+    let in_adr_dir = path
+        .parent()
+        .into_iter()
+        .flat_map(|p| p.iter())
+        .filter_map(|c| c.to_str())
+        .any(|c| ADR_DIRS.contains(&c.to_ascii_lowercase().as_str()));
+    let numbered = name
+        .split_once('-')
+        .is_some_and(|(n, _)| n.len() >= 3 && n.chars().all(|c| c.is_ascii_digit()));
+    if name.starts_with("readme") {
+        "readme"
+    } else if in_adr_dir || (numbered && name.contains("adr")) {
+        "adr"
+    } else {
+        "doc"
+    }
+}
+
+/// First line of a `Status` section, or the value of a `Status:` line
+/// (`* Status: accepted`, `:status: Accepted`), lowercased.
+fn adr_status(lines: &[&str], headings: &[Heading]) -> Option<String> {
+    let from_section = headings
+        .iter()
+        .enumerate()
+        .find(|(_, h)| h.title.eq_ignore_ascii_case("status"))
+        .and_then(|(i, h)| {
+            let end = headings.get(i + 1).map_or(lines.len(), |n| n.lines.0);
+            lines[h.lines.1 + 1..end]
+                .iter()
+                .map(|l| l.trim())
+                .find(|l| !l.is_empty())
+        });
+    let from_line = || {
+        lines.iter().find_map(|l| {
+            let l = l.trim().trim_start_matches(['*', '-', ':', ' ']);
+            let rest = l.get(..6).filter(|s| s.eq_ignore_ascii_case("status"))?;
+            let value = l[rest.len()..].trim_start_matches([':', '*', ' ']).trim();
+            (!value.is_empty()).then_some(value)
+        })
+    };
+    from_section
+        .or_else(from_line)
+        .map(|s| s.trim_matches(['*', '_', '`']).to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(lang: &dyn LanguageSupport, file_path: &str, source: &str) -> Vec<Document> {
+        let file = SourceFile {
+            repo: "orders",
+            file_path,
+            source,
+        };
+        lang.parse_file(&file, false).expect("parse")
+    }
+
+    #[test]
+    fn markdown_sections_keep_code_blocks_whole() {
+        let src = "\
+Badges and intro.
+
+# Orders service
+
+Owns the order lifecycle.
+
+## Architecture
+
+### Messaging
+
+Publishes `order.created`:
+
+```rust
+// # not a heading
+producer.send(\"order.created\").await?;
+```
+
+## Running
+Run it with `cargo run`.
+";
+        let docs = parse(&MarkdownLanguage, "README.md", src);
+        let summary: Vec<(&str, &str, u32, u32)> = docs
+            .iter()
+            .map(|d| {
+                (
+                    d.symbol_name.as_str(),
+                    d.signature.as_deref().unwrap(),
+                    d.line_start,
+                    d.line_end,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("README", "README", 1, 1),
+                ("Orders service", "Orders service", 3, 5),
+                (
+                    "Messaging",
+                    "Orders service > Architecture > Messaging",
+                    9,
+                    16
+                ),
+                ("Running", "Orders service > Running", 18, 19),
+            ]
+        );
+        let messaging = &docs[2];
+        assert!(messaging.code.ends_with("await?;\n```"));
+        assert_eq!(
+            messaging.qualified_name.as_deref(),
+            Some("README.md#Orders service > Architecture > Messaging")
+        );
+        assert_eq!(messaging.metadata["code_languages"], json!(["rust"]));
+        assert_eq!(messaging.metadata["doc_type"], "readme");
+        assert_eq!(messaging.metadata["title"], "Orders service");
+    }
+
+    #[test]
+    fn adr_status_and_asciidoc() {
+        let src = "\
+# 7. Use Kafka for order events
+
+## Status
+
+Accepted
+
+## Context
+
+Billing polls the orders API.
+";
+        let docs = parse(&MarkdownLanguage, "docs/adr/0007-use-kafka.md", src);
+        assert_eq!(docs.len(), 2);
+        assert_eq!(docs[0].metadata["doc_type"], "adr");
+        assert_eq!(docs[1].metadata["status"], "accepted");
+
+        let adoc = "\
+= Orders Guide
+:status: Proposed
+
+== Deploying
+
+[source,yaml]
+----
+== not a heading
+image: shop/orders
+----
+";
+        let docs = parse(&AsciiDocLanguage, "docs/decisions/guide.adoc", adoc);
+        let names: Vec<&str> = docs.iter().map(|d| d.symbol_name.as_str()).collect();
+        assert_eq!(names, vec!["Orders Guide", "Deploying"]);
+        assert_eq!(docs[0].metadata["status"], "proposed");
+        assert_eq!(docs[1].metadata["code_languages"], json!(["yaml"]));
+        assert_eq!((docs[1].line_start, docs[1].line_end), (4, 10));
+    }
+}
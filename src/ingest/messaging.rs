@@ -0,0 +1,930 @@
+//! messaging.rs
+//!
+//! Message broker usages (Kafka, NATS), emitted as `topic` documents by the
+//! language that parses the file (called from its `visit`, like `routes.rs`):
+//! - Kafka producers: `kafkaTemplate.send("orders", event)`, kafkajs
+//!   `producer.send({ topic: "orders", messages })`, rdkafka
+//!   `FutureRecord::to("orders")`, `new ProducerRecord<>("orders", ...)`,
+//!   kafka-python/aiokafka `producer.send("orders", ...)`, kafka-go
+//!   `kafka.Message{Topic: "orders"}` / `kafka.WriterConfig{Topic: ...}`
+//! - Kafka consumers: `@KafkaListener(topics = "orders", groupId = "billing")`,
+//!   `consumer.subscribe(...)` (rdkafka, kafkajs, Java client, kafka-python),
+//!   `KafkaConsumer("orders", group_id="billing")`,
+//!   `kafka.ReaderConfig{Topic: "orders", GroupID: "billing"}`
+//! - NATS: `nc.publish("orders.created", ...)`, `nc.subscribe("orders.*")`,
+//!   `queue_subscribe`/`QueueSubscribe("subject", "queue")`, `{ queue }` options
+//!
+//! Clients are recognized by their receiver (`producer`, `consumer`,
+//! `kafkaTemplate`, `nc`, `nats`, `js`, ...). Topics must be string literals or
+//! constants assigned a literal in the same file (`const ORDERS_TOPIC =
+//! "orders"`). One document per topic of a usage; symbol_name and
+//! qualified_name are the topic. metadata: `topic`, `role` (`publish` /
+//! `subscribe`), `broker`, `group` (consumer or queue group; a Kafka consumer
+//! without one takes the file's only `group.id`/`groupId` setting),
+//! `message_type` (listener parameter type, or the payload's constructor when
+//! it is built in the call) and `caller` (enclosing function).
+//! Publisher/subscriber repo lists are filled in after indexing
+//! (`transform::topic_graph`).
+
+use crate::ingest::http_calls::caller;
+use crate::ingest::language::SourceFile;
+use crate::ingest::routes::named_children;
+use crate::ingest::rust_parser::{
+    Document, DocumentKind, child_text_by_kind, field_text, lines_of, node_text,
+};
+use serde_json::json;
+use tree_sitter::Node;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Role {
+    Publish,
+    Subscribe,
+}
+
+impl Role {
+    fn as_str(self) -> &'static str {
+        match self {
+            Role::Publish => "publish",
+            Role::Subscribe => "subscribe",
+        }
+    }
+}
+
+/// One topic a usage publishes to / subscribes to.
+#[derive(Debug)]
+pub(crate) struct Usage {
+    pub topic: String,
+    pub role: Role,
+    pub broker: &'static str,
+    pub group: Option<String>,
+    pub message_type: Option<String>,
+}
+
+/// A call, constructor, struct literal or annotation, reduced to what the
+/// rules look at.
+struct Call<'a> {
+    /// Text before the method name (`self.producer`, `kafka`), or empty.
+    receiver: String,
+    name: String,
+    args: Vec<Node<'a>>,
+    /// Named arguments: Python/Kotlin keywords, annotation elements, Go struct
+    /// literal fields.
+    keywords: Vec<(String, Node<'a>)>,
+}
+
+// ---- per-language call shapes ----
+
+/// rdkafka, async-nats.
+pub(crate) fn rust_topic_usage(file: &SourceFile, node: Node, out: &mut Vec<Document>) {
+    let source = file.source;
+    if node.kind() != "call_expression" {
+        return;
+    }
+    let (Some(function), Some(args)) = (
+        node.child_by_field_name("function"),
+        node.child_by_field_name("arguments"),
+    ) else {
+        return;
+    };
+    let (receiver, name) = match function.kind() {
+        "field_expression" => (
+            field_text(source, function, "value"),
+            field_text(source, function, "field"),
+        ),
+        "scoped_identifier" => (
+            field_text(source, function, "path"),
+            field_text(source, function, "name"),
+        ),
+        _ => return,
+    };
+    let call = Call {
+        receiver,
+        name,
+        args: named_children(args).collect(),
+        keywords: Vec::new(),
+    };
+    push_usages(file, node, &call, out);
+}
+
+/// kafkajs, nats.js.
+pub(crate) fn js_topic_usage(file: &SourceFile, node: Node, out: &mut Vec<Document>) {
+    let source = file.source;
+    if node.kind() != "call_expression" {
+        return;
+    }
+    let (Some(function), Some(args)) = (
+        node.child_by_field_name("function"),
+        node.child_by_field_name("arguments"),
+    ) else {
+        return;
+    };
+    if function.kind() != "member_expression" {
+        return;
+    }
+    let call = Call {
+        receiver: field_text(source, function, "object"),
+        name: field_text(source, function, "property"),
+        args: named_children(args).collect(),
+        keywords: Vec::new(),
+    };
+    push_usages(file, node, &call, out);
+}
+
+/// kafka-go struct literals, nats.go calls.
+pub(crate) fn go_topic_usage(file: &SourceFile, node: Node, out: &mut Vec<Document>) {
+    let source = file.source;
+    let call = match node.kind() {
+        "call_expression" => {
+            let (Some(function), Some(args)) = (
+                node.child_by_field_name("function"),
+                node.child_by_field_name("arguments"),
+            ) else {
+                return;
+            };
+            if function.kind() != "selector_expression" {
+                return;
+            }
+            Call {
+                receiver: field_text(source, function, "operand"),
+                name: field_text(source, function, "field"),
+                args: named_children(args).collect(),
+                keywords: Vec::new(),
+            }
+        }
+        // kafka.ReaderConfig{Topic: "orders", GroupID: "billing"}
+        "composite_literal" => {
+            let (Some(ty), Some(body)) = (
+                node.child_by_field_name("type"),
+                node.child_by_field_name("body"),
+            ) else {
+                return;
+            };
+            let keywords = named_children(body)
+                .filter(|e| e.kind() == "keyed_element")
+                .filter_map(|e| {
+                    let key = e.named_child(0)?;
+                    let value = e.named_child(1)?;
+                    Some((
+                        node_text(source, key),
+                        value.named_child(0).unwrap_or(value),
+                    ))
+                })
+                .collect();
+            Call {
+                receiver: field_text(source, ty, "package"),
+                name: field_text(source, ty, "name"),
+                args: Vec::new(),
+                keywords,
+            }
+        }
+        _ => return,
+    };
+    push_usages(file, node, &call, out);
+}
+
+/// kafka-python, aiokafka, confluent-kafka, nats-py.
+pub(crate) fn python_topic_usage(file: &SourceFile, node: Node, out: &mut Vec<Document>) {
+    let source = file.source;
+    if node.kind() != "call" {
+        return;
+    }
+    let (Some(function), Some(args)) = (
+        node.child_by_field_name("function"),
+        node.child_by_field_name("arguments"),
+    ) else {
+        return;
+    };
+    let (receiver, name) = match function.kind() {
+        "attribute" => (
+            field_text(source, function, "object"),
+            field_text(source, function, "attribute"),
+        ),
+        "identifier" => (String::new(), node_text(source, function)),
+        _ => return,
+    };
+    let (keywords, args): (Vec<Node>, Vec<Node>) =
+        named_children(args).partition(|a| a.kind() == "keyword_argument");
+    let keywords = keywords
+        .into_iter()
+        .filter_map(|k| {
+            Some((
+                field_text(source, k, "name"),
+                k.child_by_field_name("value")?,
+            ))
+        })
+        .collect();
+    let call = Call {
+        receiver,
+        name,
+        args,
+        keywords,
+    };
+    push_usages(file, node, &call, out);
+}
+
+/// Spring Kafka (`@KafkaListener`, `KafkaTemplate`), Kafka client, jnats.
+pub(crate) fn java_topic_usage(file: &SourceFile, node: Node, out: &mut Vec<Document>) {
+    let source = file.source;
+    let call = match node.kind() {
+        "method_invocation" => {
+            let Some(args) = node.child_by_field_name("arguments") else {
+                return;
+            };
+            Call {
+                receiver: field_text(source, node, "object"),
+                name: field_text(source, node, "name"),
+                args: named_children(args).collect(),
+                keywords: Vec::new(),
+            }
+        }
+        "object_creation_expression" => {
+            let (Some(ty), Some(args)) = (
+                node.child_by_field_name("type"),
+                node.child_by_field_name("arguments"),
+            ) else {
+                return;
+            };
+            let ty = node_text(source, ty);
+            Call {
+                receiver: String::new(),
+                name: last_segment(ty.split('<').next().unwrap_or_default()).to_string(),
+                args: named_children(args).collect(),
+                keywords: Vec::new(),
+            }
+        }
+        "annotation" => {
+            let Some(args) = node.child_by_field_name("arguments") else {
+                return;
+            };
+            let (pairs, values): (Vec<Node>, Vec<Node>) =
+                named_children(args).partition(|a| a.kind() == "element_value_pair");
+            Call {
+                receiver: String::new(),
+                name: last_segment(&field_text(source, node, "name")).to_string(),
+                args: values,
+                keywords: pairs
+                    .into_iter()
+                    .filter_map(|p| {
+                        Some((
+                            field_text(source, p, "key"),
+                            p.child_by_field_name("value")?,
+                        ))
+                    })
+                    .collect(),
+            }
+        }
+        _ => return,
+    };
+    push_usages(file, node, &call, out);
+}
+
+/// Spring Kafka, Kafka client, jnats; calls and annotations.
+pub(crate) fn kotlin_topic_usage(file: &SourceFile, node: Node, out: &mut Vec<Document>) {
+    let source = file.source;
+    let (receiver, name, arguments) = match node.kind() {
+        "call_expression" => {
+            let Some(navigation) = node
+                .named_child(0)
+                .filter(|n| n.kind() == "navigation_expression")
+            else {
+                return;
+            };
+            let receiver = navigation
+                .named_child(0)
+                .map(|r| node_text(source, r))
+                .unwrap_or_default();
+            let Some(name) = navigation
+                .named_child(1)
+                .and_then(|suffix| child_text_by_kind(source, suffix, "simple_identifier"))
+            else {
+                return;
+            };
+            let arguments = named_children(node)
+                .filter(|c| c.kind() == "call_suffix")
+                .flat_map(named_children)
+                .find(|c| c.kind() == "value_arguments");
+            (receiver, name, arguments)
+        }
+        // @KafkaListener(topics = ["orders"], groupId = "billing")
+        "annotation" => {
+            let Some(invocation) =
+                named_children(node).find(|c| c.kind() == "constructor_invocation")
+            else {
+                return;
+            };
+            let name = child_text_by_kind(source, invocation, "user_type").unwrap_or_default();
+            let arguments = named_children(invocation).find(|c| c.kind() == "value_arguments");
+            (String::new(), last_segment(&name).to_string(), arguments)
+        }
+        _ => return,
+    };
+
+    let mut call = Call {
+        receiver,
+        name,
+        args: Vec::new(),
+        keywords: Vec::new(),
+    };
+    for arg in arguments.into_iter().flat_map(named_children) {
+        let parts: Vec<Node> = named_children(arg).collect();
+        match parts.as_slice() {
+            [key, value] if key.kind() == "simple_identifier" => {
+                call.keywords.push((node_text(source, *key), *value))
+            }
+            [value, ..] => call.args.push(*value),
+            [] => {}
+        }
+    }
+    push_usages(file, node, &call, out);
+}
+
+// ---- shared rules ----
+
+fn push_usages(file: &SourceFile, node: Node, call: &Call, out: &mut Vec<Document>) {
+    let source = file.source;
+    let usages = usages(source, node, call);
+    if usages.is_empty() {
+        return;
+    }
+    // a listener annotation documents its whole method
+    let span = match node.kind() {
+        "annotation" => enclosing_declaration(node).unwrap_or(node),
+        _ => node,
+    };
+    let caller = caller(source, node);
+    for usage in usages {
+        let code = node_text(source, span);
+        out.push(topic_document(
+            file,
+            code,
+            lines_of(&span),
+            usage,
+            caller.clone(),
+        ));
+    }
+}
+
+fn usages(source: &str, node: Node, call: &Call) -> Vec<Usage> {
+    let receiver = call.receiver.to_ascii_lowercase();
+    let kafka_client = ["kafka", "producer", "consumer", "template"]
+        .iter()
+        .any(|k| receiver.contains(k));
+    let nats_client = receiver.contains("nats")
+        || receiver.contains("jetstream")
+        || matches!(last_segment(&receiver), "nc" | "js" | "conn" | "connection");
+    let keyword = |names: &[&str]| {
+        call.keywords
+            .iter()
+            .find(|(k, _)| names.contains(&k.as_str()))
+            .map(|(_, v)| *v)
+    };
+    let keyword_strings = |names: &[&str]| {
+        keyword(names)
+            .map(|v| strings(source, v))
+            .unwrap_or_default()
+    };
+
+    let (role, broker, topics, group, message_type) = match call.name.as_str() {
+        "KafkaListener" => {
+            let mut topics = keyword_strings(&["topics", "value"]);
+            topics.extend(call.args.iter().flat_map(|a| strings(source, *a)));
+            let group = keyword_strings(&["groupId", "id"]).into_iter().next();
+            let message_type = enclosing_declaration(node).and_then(|m| listener_type(source, m));
+            (Role::Subscribe, "kafka", topics, group, message_type)
+        }
+        // new ProducerRecord<>("orders", key, value), FutureRecord::to("orders")
+        "ProducerRecord" => {
+            let (topics, payload) = first_strings(source, &call.args);
+            (Role::Publish, "kafka", topics, None, payload)
+        }
+        "to" if call.receiver.ends_with("Record") => {
+            let (topics, _) = first_strings(source, &call.args);
+            (Role::Publish, "kafka", topics, None, None)
+        }
+        // KafkaConsumer("orders", "payments", group_id="billing")
+        "KafkaConsumer" | "AIOKafkaConsumer" => {
+            let topics = call.args.iter().flat_map(|a| strings(source, *a)).collect();
+            let group = keyword_strings(&["group_id"]).into_iter().next();
+            (Role::Subscribe, "kafka", topics, group, None)
+        }
+        "Message" | "Writer" | "WriterConfig" if call.receiver == "kafka" => (
+            Role::Publish,
+            "kafka",
+            keyword_strings(&["Topic"]),
+            None,
+            None,
+        ),
+        "Reader" | "ReaderConfig" if call.receiver == "kafka" => {
+            let mut topics = keyword_strings(&["Topic"]);
+            topics.extend(keyword_strings(&["GroupTopics"]));
+            let group = keyword_strings(&["GroupID"]).into_iter().next();
+            (Role::Subscribe, "kafka", topics, group, None)
+        }
+        "send" | "send_and_wait" | "produce" | "Produce"
+            if kafka_client && !receiver.contains("consumer") =>
+        {
+            let (mut topics, payload) = first_strings(source, &call.args);
+            topics.extend(option_strings(source, &call.args, &["topic"]));
+            (Role::Publish, "kafka", topics, None, payload)
+        }
+        "subscribe" if kafka_client => {
+            let mut topics: Vec<String> =
+                call.args.iter().flat_map(|a| strings(source, *a)).collect();
+            topics.extend(option_strings(source, &call.args, &["topic", "topics"]));
+            (Role::Subscribe, "kafka", topics, None, None)
+        }
+        "publish" | "Publish" | "publish_with_headers" if nats_client => {
+            let (topics, payload) = first_strings(source, &call.args);
+            (Role::Publish, "nats", topics, None, payload)
+        }
+        "subscribe" | "Subscribe" | "SubscribeSync" | "ChanSubscribe" | "queue_subscribe"
+        | "QueueSubscribe" | "QueueSubscribeSync"
+            if nats_client =>
+        {
+            let (topics, _) = first_strings(source, &call.args);
+            let queued = call.name.to_ascii_lowercase().starts_with("queue");
+            let group = if queued {
+                call.args
+                    .get(1)
+                    .and_then(|q| strings(source, *q).into_iter().next())
+            } else {
+                keyword_strings(&["queue"])
+                    .into_iter()
+                    .chain(option_strings(source, &call.args, &["queue"]))
+                    .next()
+            };
+            (Role::Subscribe, "nats", topics, group, None)
+        }
+        _ => return Vec::new(),
+    };
+
+    // Kafka consumers configured elsewhere in the file
+    let group = match (role, broker, group) {
+        (Role::Subscribe, "kafka", None) => file_group(source),
+        (_, _, group) => group,
+    };
+    let mut topics = topics;
+    topics.dedup();
+    topics
+        .into_iter()
+        .filter(|t| !t.is_empty())
+        .map(|topic| Usage {
+            topic,
+            role,
+            broker,
+            group: group.clone(),
+            message_type: message_type.clone(),
+        })
+        .collect()
+}
+
+/// `topic` document of one usage; `code` and `lines` are the usage's span.
+pub(crate) fn topic_document(
+    file: &SourceFile,
+    code: String,
+    (line_start, line_end): (u32, u32),
+    usage: Usage,
+    caller: Option<String>,
+) -> Document {
+    let mut details = vec![usage.broker.to_string()];
+    details.extend(usage.group.iter().map(|g| format!("group {g}")));
+    details.extend(usage.message_type.clone());
+    let signature = format!(
+        "{} {} ({})",
+        usage.role.as_str(),
+        usage.topic,
+        details.join(", ")
+    );
+    let mut metadata = serde_json::Map::new();
+    metadata.insert("topic".into(), json!(usage.topic));
+    metadata.insert("role".into(), json!(usage.role.as_str()));
+    metadata.insert("broker".into(), json!(usage.broker));
+    metadata.insert("group".into(), json!(usage.group));
+    metadata.insert("message_type".into(), json!(usage.message_type));
+    metadata.insert("caller".into(), json!(caller));
+
+    Document {
+        repo: file.repo.to_string(),
+        file_path: file.file_path.to_string(),
+        qualified_name: Some(usage.topic.clone()),
+        symbol_name: usage.topic,
+        kind: DocumentKind::Topic,
+        signature: Some(signature),
+        doc_comment: None,
+        code,
+        parent_type: None,
+        module_path: None,
+        annotations: Vec::new(),
+        metadata,
+        line_start,
+        line_end,
+    }
+}
+
+/// Topics of the first argument, and the payload type built in the others.
+fn first_strings(source: &str, args: &[Node]) -> (Vec<String>, Option<String>) {
+    let topics = args
+        .first()
+        .map(|a| strings(source, *a))
+        .unwrap_or_default();
+    let payload = args
+        .iter()
+        .skip(1)
+        .find_map(|a| constructed_type(source, *a));
+    (topics, payload)
+}
+
+/// Strings under `keys` of the JS object arguments (`{ topic: "orders" }`).
+fn option_strings(source: &str, args: &[Node], keys: &[&str]) -> Vec<String> {
+    args.iter()
+        .filter(|a| a.kind() == "object")
+        .flat_map(|o| named_children(*o))
+        .filter(|p| p.kind() == "pair")
+        .filter(|p| {
+            p.child_by_field_name("key")
+                .is_some_and(|k| keys.contains(&node_text(source, k).trim_matches(['"', '\''])))
+        })
+        .filter_map(|p| p.child_by_field_name("value"))
+        .flat_map(|v| strings(source, v))
+        .collect()
+}
+
+/// Functions that only wrap their string arguments (`List.of`, `"x".into()`).
+const STRING_WRAPPERS: &[&str] = &[
+    "of",
+    "asList",
+    "listOf",
+    "setOf",
+    "arrayOf",
+    "mutableListOf",
+    "from",
+    "to_string",
+    "to_owned",
+    "into",
+];
+
+/// String values of an argument: literals, arrays/lists of them, and
+/// constants assigned a literal in the same file. Interpolated strings and
+/// other expressions yield nothing.
+fn strings(source: &str, node: Node) -> Vec<String> {
+    match node.kind() {
+        "string_literal"
+        | "string"
+        | "template_string"
+        | "interpreted_string_literal"
+        | "raw_string_literal" => {
+            let interpolated = named_children(node).any(|c| {
+                matches!(
+                    c.kind(),
+                    "template_substitution"
+                        | "interpolation"
+                        | "interpolated_expression"
+                        | "interpolated_identifier"
+                )
+            });
+            if interpolated {
+                return Vec::new();
+            }
+            let raw = node_text(source, node);
+            let value = raw
+                .trim_start_matches(|c: char| c.is_ascii_alphabetic())
+                .trim_matches(['"', '\'', '`', '#']);
+            // Spring placeholders (`${app.topic}`) are resolved at runtime
+            if value.contains("${") {
+                return Vec::new();
+            }
+            vec![value.to_string()]
+        }
+        "array_expression"
+        | "array"
+        | "collection_literal"
+        | "list"
+        | "tuple"
+        | "array_initializer"
+        | "element_value_array_initializer"
+        | "reference_expression"
+        | "parenthesized_expression"
+        | "literal_value"
+        | "literal_element"
+        | "value_argument"
+        | "argument_list"
+        | "arguments"
+        | "value_arguments"
+        | "call_suffix" => named_children(node)
+            .flat_map(|c| strings(source, c))
+            .collect(),
+        "composite_literal" => node
+            .child_by_field_name("body")
+            .map(|b| strings(source, b))
+            .unwrap_or_default(),
+        "call_expression" | "method_invocation" | "call" => {
+            let callee = node_text(source, node);
+            let callee = callee.split('(').next().unwrap_or_default();
+            if !STRING_WRAPPERS.contains(&last_segment(callee)) {
+                return Vec::new();
+            }
+            named_children(node)
+                .flat_map(|c| match c.kind() {
+                    // "orders".to_string()
+                    "field_expression" | "navigation_expression" => c
+                        .named_child(0)
+                        .map(|v| strings(source, v))
+                        .unwrap_or_default(),
+                    _ => strings(source, c),
+                })
+                .collect()
+        }
+        "identifier"
+        | "simple_identifier"
+        | "scoped_identifier"
+        | "field_expression"
+        | "member_expression"
+        | "field_access"
+        | "selector_expression"
+        | "attribute"
+        | "navigation_expression" => constant_value(source, last_segment(&node_text(source, node)))
+            .into_iter()
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Literal assigned to `name` in the file: `const ORDERS: &str = "orders";`,
+/// `static final String ORDERS = "orders";`, `ORDERS = 'orders'`.
+fn constant_value(source: &str, name: &str) -> Option<String> {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    if name.is_empty() || !name.chars().all(is_ident) {
+        return None;
+    }
+    for (at, _) in source.match_indices(name) {
+        if source[..at].chars().next_back().is_some_and(is_ident) {
+            continue;
+        }
+        let line = source[at + name.len()..].lines().next().unwrap_or_default();
+        let Some(eq) = line.find('=') else {
+            continue;
+        };
+        // the type annotation between name and `=`, if any
+        let decl = line[..eq].trim_end();
+        if decl.starts_with(is_ident)
+            || decl.ends_with(['!', '<', '>', '+', '-', '*', '/'])
+            || decl.contains(['(', '"', '\''])
+        {
+            continue;
+        }
+        let value = line[eq + 1..].trim_start();
+        let Some(quote) = value
+            .chars()
+            .next()
+            .filter(|q| matches!(q, '"' | '\'' | '`'))
+        else {
+            continue;
+        };
+        if let Some(end) = value[1..].find(quote) {
+            return Some(value[1..1 + end].to_string());
+        }
+    }
+    None
+}
+
+/// The consumer group a file configures, when it names exactly one
+/// (`"group.id", "billing"`, `groupId: 'billing'`, `group_id="billing"`).
+fn file_group(source: &str) -> Option<String> {
+    let mut groups: Vec<String> = Vec::new();
+    for key in ["group.id", "groupId", "group_id", "GroupID", "group-id"] {
+        for (at, _) in source.match_indices(key) {
+            let rest = &source[at + key.len()..];
+            let rest = rest.strip_prefix(['"', '\'']).unwrap_or(rest);
+            let value = rest.trim_start_matches([' ', '\t', ':', '=', ',']);
+            let Some(quote) = value.chars().next().filter(|q| matches!(q, '"' | '\'')) else {
+                continue;
+            };
+            if let Some(end) = value[1..].find(quote) {
+                groups.push(value[1..1 + end].to_string());
+            }
+        }
+    }
+    groups.sort();
+    groups.dedup();
+    match groups.as_slice() {
+        [only] => Some(only.clone()),
+        _ => None,
+    }
+}
+
+/// Type constructed by an argument: `new OrderCreated(..)`, `OrderCreated { .. }`,
+/// `&OrderCreated{..}`, `OrderCreated(..)` (Kotlin/Python, capitalized callee).
+fn constructed_type(source: &str, node: Node) -> Option<String> {
+    let name = match node.kind() {
+        "object_creation_expression" | "new_expression" => node
+            .child_by_field_name("type")
+            .or_else(|| node.child_by_field_name("constructor"))
+            .map(|t| node_text(source, t))?,
+        "struct_expression" => field_text(source, node, "name"),
+        "composite_literal" => field_text(source, node, "type"),
+        "reference_expression" | "unary_expression" | "value_argument" => {
+            return node
+                .named_child(node.named_child_count().checked_sub(1)?)
+                .and_then(|inner| constructed_type(source, inner));
+        }
+        "call_expression" | "call" => {
+            let callee = node.named_child(0).map(|c| node_text(source, c))?;
+            callee
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_')
+                .then_some(callee)?
+        }
+        _ => return None,
+    };
+    let name = last_segment(name.split('<').next().unwrap_or_default()).to_string();
+    name.starts_with(|c: char| c.is_ascii_uppercase())
+        .then_some(name)
+}
+
+/// Type of a listener method's first parameter; the record/message wrapper
+/// is unwrapped (`ConsumerRecord<String, OrderCreated>` → `OrderCreated`).
+fn listener_type(source: &str, method: Node) -> Option<String> {
+    let parameter = named_children(method)
+        .filter(|c| matches!(c.kind(), "formal_parameters" | "function_value_parameters"))
+        .flat_map(named_children)
+        .find(|p| matches!(p.kind(), "formal_parameter" | "parameter"))?;
+    let ty = parameter
+        .child_by_field_name("type")
+        .or_else(|| named_children(parameter).find(|c| c.kind().ends_with("_type")))
+        .map(|t| node_text(source, t))?;
+    let ty = match ty.split_once('<') {
+        Some((outer, args))
+            if matches!(last_segment(outer), "ConsumerRecord" | "Message" | "List") =>
+        {
+            args.trim_end_matches('>')
+                .rsplit(',')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        }
+        _ => ty,
+    };
+    Some(ty)
+}
+
+/// Method/function declaration an annotation belongs to.
+fn enclosing_declaration(annotation: Node) -> Option<Node> {
+    let mut cur = annotation.parent();
+    while let Some(n) = cur {
+        if matches!(n.kind(), "method_declaration" | "function_declaration") {
+            return Some(n);
+        }
+        if n.kind() != "modifiers" {
+            return None;
+        }
+        cur = n.parent();
+    }
+    None
+}
+
+fn last_segment(path: &str) -> &str {
+    path.rsplit(['.', ':']).next().unwrap_or(path).trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::go_parser::GoLanguage;
+    use crate::ingest::java_parser::JavaLanguage;
+    use crate::ingest::kotlin_parser::KotlinLanguage;
+    use crate::ingest::language::LanguageSupport;
+    use crate::ingest::python_parser::PythonLanguage;
+    use crate::ingest::rust_parser::RustLanguage;
+    use crate::ingest::ts_parser::ScriptLanguage;
+
+    fn topics(language: &dyn LanguageSupport, file_path: &str, source: &str) -> Vec<String> {
+        let file = SourceFile {
+            repo: "billing",
+            file_path,
+            source,
+        };
+        language
+            .parse_file(&file, false)
+            .expect("parse")
+            .into_iter()
+            .filter(|d| d.kind == DocumentKind::Topic)
+            .map(|d| {
+                let field = |k: &str| d.metadata[k].as_str().unwrap_or("-").to_string();
+                format!(
+                    "{} {} {} {} {}",
+                    field("role"),
+                    d.symbol_name,
+                    field("broker"),
+                    field("group"),
+                    field("message_type")
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn spring_kafka_listeners_and_templates() {
+        let java = r#"class OrderEvents {
+    static final String PAID = "order.paid";
+
+    @KafkaListener(topics = {"order.created", "order.updated"}, groupId = "billing")
+    public void on(ConsumerRecord<String, OrderCreated> record) {}
+
+    void paid(Order o) {
+        kafkaTemplate.send(PAID, new OrderPaid(o.id()));
+        producer.send(new ProducerRecord<>("audit", o.id(), o));
+    }
+}
+"#;
+        assert_eq!(
+            topics(&JavaLanguage, "src/OrderEvents.java", java),
+            vec![
+                "subscribe order.created kafka billing OrderCreated",
+                "subscribe order.updated kafka billing OrderCreated",
+                "publish order.paid kafka - OrderPaid",
+                "publish audit kafka - -",
+            ]
+        );
+
+        let kotlin = "@KafkaListener(topics = [\"order.created\"], groupId = \"billing\")\nfun on(event: OrderCreated) {}\nfun pay() { kafkaTemplate.send(\"order.paid\", OrderPaid(id)) }\n";
+        assert_eq!(
+            topics(&KotlinLanguage, "src/Events.kt", kotlin),
+            vec![
+                "subscribe order.created kafka billing OrderCreated",
+                "publish order.paid kafka - OrderPaid",
+            ]
+        );
+    }
+
+    #[test]
+    fn rust_and_js_clients() {
+        let rust = r#"
+const ORDERS: &str = "order.created";
+async fn run(producer: FutureProducer, consumer: StreamConsumer, nc: async_nats::Client) {
+    let config = ClientConfig::new().set("group.id", "billing");
+    consumer.subscribe(&[ORDERS, "order.paid"]).unwrap();
+    producer.send(FutureRecord::to("invoice.issued").payload(&p), timeout).await;
+    nc.queue_subscribe("payments.*".to_string(), "billing-workers".into()).await?;
+    nc.publish("payments.charged", payload.into()).await?;
+    self.cache.subscribe("not-a-topic");
+}
+"#;
+        assert_eq!(
+            topics(&RustLanguage, "src/events.rs", rust),
+            vec![
+                "subscribe order.created kafka billing -",
+                "subscribe order.paid kafka billing -",
+                "publish invoice.issued kafka - -",
+                "subscribe payments.* nats billing-workers -",
+                "publish payments.charged nats - -",
+            ]
+        );
+
+        let ts = r#"
+const consumer = kafka.consumer({ groupId: 'billing' });
+export async function start() {
+  await consumer.subscribe({ topics: ['order.created'] });
+  await producer.send({ topic: 'invoice.issued', messages });
+  nc.subscribe('payments.>', { queue: 'workers' });
+}
+"#;
+        assert_eq!(
+            topics(&ScriptLanguage::TYPESCRIPT, "src/events.ts", ts),
+            vec![
+                "subscribe order.created kafka billing -",
+                "publish invoice.issued kafka - -",
+                "subscribe payments.> nats workers -",
+            ]
+        );
+    }
+
+    #[test]
+    fn go_and_python_clients() {
+        let go = r#"package events
+func Run() {
+	r := kafka.NewReader(kafka.ReaderConfig{Topic: "order.created", GroupID: "billing"})
+	w.WriteMessages(ctx, kafka.Message{Topic: "invoice.issued", Value: b})
+	nc.QueueSubscribe("payments.*", "workers", handle)
+}
+"#;
+        assert_eq!(
+            topics(&GoLanguage, "events/run.go", go),
+            vec![
+                "subscribe order.created kafka billing -",
+                "publish invoice.issued kafka - -",
+                "subscribe payments.* nats workers -",
+            ]
+        );
+
+        let py = "async def run():\n    consumer = KafkaConsumer('order.created', group_id='billing')\n    producer.send('invoice.issued', value=b)\n    await nc.subscribe('payments.*', queue='workers', cb=handle)\n";
+        assert_eq!(
+            topics(&PythonLanguage, "events/run.py", py),
+            vec![
+                "subscribe order.created kafka billing -",
+                "publish invoice.issued kafka - -",
+                "subscribe payments.* nats workers -",
+            ]
+        );
+    }
+}
@@ -1,2 +1,22 @@
+pub(crate) mod chunker;
+pub(crate) mod compose_parser;
+pub(crate) mod dockerfile_parser;
+pub(crate) mod go_parser;
+pub(crate) mod http_calls;
+pub(crate) mod java_parser;
+pub(crate) mod k8s_parser;
+pub(crate) mod kotlin_parser;
+pub(crate) mod language;
+pub(crate) mod manifest;
+pub(crate) mod markdown_parser;
+pub(crate) mod messaging;
+pub(crate) mod openapi_parser;
+pub(crate) mod proto_parser;
+pub(crate) mod python_parser;
 pub(crate) mod repo_scanner;
+pub(crate) mod routes;
 pub(crate) mod rust_parser;
+pub(crate) mod spring_config_parser;
+pub(crate) mod sql;
+pub(crate) mod sql_parser;
+pub(crate) mod ts_parser;
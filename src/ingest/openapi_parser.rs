@@ -0,0 +1,507 @@
+//! openapi_parser.rs
+//!
+//! OpenAPI 3 / Swagger 2 spec support for the language registry. Claims
+//! `openapi*.{yaml,yml,json}` and `swagger*.{yaml,yml,json}` files and
+//! overrides `parse_file` (specs are data, not code).
+//!
+//! Documents include:
+//! - one `endpoint` per operation: symbol_name `GET /orders/{id}`, doc_comment
+//!   = summary + description, signature = `GET /orders/{id} -> OrderSummary`
+//! - one `schema` per `components.schemas` (v3) / `definitions` (v2) entry
+//!
+//! Endpoint metadata: `method`, `path`, `operation_id`, `parameters`
+//! (`{name, in, required}`), `request_schema`, `response_schemas`
+//! (`{status: schema}`), `tags`; both kinds carry the spec `title`/`version`.
+//! Schema names come from `$ref`s (last segment); arrays are `Name[]`.
+//!
+//! code is the operation/schema re-serialized as YAML under its symbol name
+//! (JSON specs included), so embeddings see one format. Line ranges are
+//! located by key and indentation in the original text (best-effort; 1..=1
+//! when not found).
+
+use crate::ingest::language::{LanguageSupport, SourceFile, file_name_lower};
+use crate::ingest::rust_parser::{Document, DocumentKind, RustParserError, with_file_summary};
+use serde_json::{Map, Value as JsonValue, json};
+use serde_norway::Value;
+use std::path::Path;
+
+const METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+pub struct OpenApiLanguage;
+
+impl LanguageSupport for OpenApiLanguage {
+    fn name(&self) -> &'static str {
+        "openapi"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["yaml", "yml", "json"]
+    }
+
+    /// Only files named like a spec; other YAML/JSON is left alone.
+    fn matches(&self, path: &Path) -> bool {
+        let name = file_name_lower(path);
+        self.has_extension(path) && (name.starts_with("openapi") || name.starts_with("swagger"))
+    }
+
+    fn parse_file(
+        &self,
+        file: &SourceFile,
+        include_filename_doc: bool,
+    ) -> Result<Vec<Document>, RustParserError> {
+        let spec: Value =
+            serde_norway::from_str(file.source).map_err(|e| RustParserError::InvalidFormat {
+                format: "OpenAPI spec",
+                message: e.to_string(),
+            })?;
+
+        let mut out = Vec::new();
+        let spec = Spec {
+            file,
+            root: &spec,
+            lines: file.source.lines().collect(),
+        };
+        spec.operations(&mut out);
+        spec.schemas(&mut out);
+        Ok(with_file_summary(file, include_filename_doc, out))
+    }
+}
+
+struct Spec<'a> {
+    file: &'a SourceFile<'a>,
+    root: &'a Value,
+    lines: Vec<&'a str>,
+}
+
+impl Spec<'_> {
+    fn operations(&self, out: &mut Vec<Document>) {
+        let Some(paths) = self.root.get("paths").and_then(Value::as_mapping) else {
+            return;
+        };
+        let paths_line = find_key(&self.lines, 0, self.lines.len(), "paths");
+
+        for (path, item) in paths {
+            let Some(path) = path.as_str() else { continue };
+            let path_block = paths_line
+                .and_then(|l| find_key(&self.lines, l + 1, self.lines.len(), path))
+                .map(|l| (l, block_end(&self.lines, l)));
+            let shared_params = item.get("parameters");
+
+            for &method in METHODS {
+                let Some(op) = item.get(method) else { continue };
+                let lines = path_block
+                    .and_then(|(start, end)| find_key(&self.lines, start + 1, end + 1, method))
+                    .map(|l| (l, block_end(&self.lines, l)));
+                out.push(self.operation(path, method, op, shared_params, lines));
+            }
+        }
+    }
+
+    fn operation(
+        &self,
+        path: &str,
+        method: &str,
+        op: &Value,
+        shared_params: Option<&Value>,
+        lines: Option<(usize, usize)>,
+    ) -> Document {
+        let method_upper = method.to_ascii_uppercase();
+        let symbol_name = format!("{method_upper} {path}");
+
+        let mut parameters = Vec::new();
+        let mut request_schema = None;
+        let params = shared_params
+            .and_then(Value::as_sequence)
+            .into_iter()
+            .chain(op.get("parameters").and_then(Value::as_sequence))
+            .flatten();
+        for p in params {
+            if let Some(r) = str_at(p, "$ref") {
+                parameters.push(json!({ "ref": ref_name(r) }));
+                continue;
+            }
+            // Swagger 2 request bodies are `in: body` parameters
+            if str_at(p, "in") == Some("body") {
+                request_schema = p.get("schema").and_then(schema_name);
+                continue;
+            }
+            parameters.push(json!({
+                "name": str_at(p, "name"),
+                "in": str_at(p, "in"),
+                "required": p.get("required").and_then(Value::as_bool).unwrap_or(false),
+            }));
+        }
+        if let Some(body) = op.get("requestBody") {
+            request_schema = content_schema(body).or(request_schema);
+        }
+
+        let mut response_schemas = Map::new();
+        if let Some(responses) = op.get("responses").and_then(Value::as_mapping) {
+            for (status, resp) in responses {
+                let status = scalar_string(status);
+                let schema =
+                    content_schema(resp).or_else(|| resp.get("schema").and_then(schema_name));
+                if let Some(schema) = schema {
+                    response_schemas.insert(status, json!(schema));
+                }
+            }
+        }
+
+        let operation_id = str_at(op, "operationId");
+        let mut metadata = self.base_metadata();
+        metadata.insert("method".into(), json!(method_upper));
+        metadata.insert("path".into(), json!(path));
+        metadata.insert("operation_id".into(), json!(operation_id));
+        metadata.insert("parameters".into(), JsonValue::Array(parameters));
+        metadata.insert("request_schema".into(), json!(request_schema));
+        metadata.insert(
+            "response_schemas".into(),
+            JsonValue::Object(response_schemas.clone()),
+        );
+        let tags: Vec<&str> = op
+            .get("tags")
+            .and_then(Value::as_sequence)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .collect();
+        metadata.insert("tags".into(), json!(tags));
+
+        // success response first: `GET /orders/{id} -> OrderSummary`
+        let success = response_schemas
+            .iter()
+            .find(|(status, _)| status.starts_with('2'))
+            .and_then(|(_, s)| s.as_str());
+        let mut signature = symbol_name.clone();
+        if let Some(op_id) = operation_id {
+            signature = format!("{signature} ({op_id})");
+        }
+        if let Some(success) = success {
+            signature = format!("{signature} -> {success}");
+        }
+
+        self.document(
+            symbol_name,
+            DocumentKind::Endpoint,
+            signature,
+            op,
+            metadata,
+            lines,
+        )
+    }
+
+    fn schemas(&self, out: &mut Vec<Document>) {
+        let (section, schemas) = match self.root.get("components").and_then(|c| c.get("schemas")) {
+            Some(s) => ("components", s),
+            None => match self.root.get("definitions") {
+                Some(s) => ("definitions", s),
+                None => return,
+            },
+        };
+        let Some(schemas) = schemas.as_mapping() else {
+            return;
+        };
+        let section_line = find_key(&self.lines, 0, self.lines.len(), section).and_then(|l| {
+            if section == "components" {
+                find_key(&self.lines, l + 1, block_end(&self.lines, l) + 1, "schemas")
+            } else {
+                Some(l)
+            }
+        });
+
+        for (name, schema) in schemas {
+            let name = scalar_string(name);
+            let lines = section_line
+                .and_then(|l| find_key(&self.lines, l + 1, block_end(&self.lines, l) + 1, &name))
+                .map(|l| (l, block_end(&self.lines, l)));
+            let kind = str_at(schema, "type").unwrap_or("object");
+            out.push(self.document(
+                name.clone(),
+                DocumentKind::Schema,
+                format!("schema {name}: {kind}"),
+                schema,
+                self.base_metadata(),
+                lines,
+            ));
+        }
+    }
+
+    /// doc_comment = `summary`/`title` + `description` of `node`; code = `node`
+    /// as YAML under its symbol name.
+    fn document(
+        &self,
+        symbol_name: String,
+        kind: DocumentKind,
+        signature: String,
+        node: &Value,
+        metadata: Map<String, JsonValue>,
+        lines: Option<(usize, usize)>,
+    ) -> Document {
+        let (line_start, line_end) = lines.map_or((1, 1), |(s, e)| (s as u32 + 1, e as u32 + 1));
+        let doc_comment = ["summary", "title", "description"]
+            .into_iter()
+            .filter_map(|key| str_at(node, key))
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let mut snippet = serde_norway::Mapping::new();
+        snippet.insert(Value::from(symbol_name.as_str()), node.clone());
+
+        Document {
+            repo: self.file.repo.to_string(),
+            file_path: self.file.file_path.to_string(),
+            qualified_name: Some(symbol_name.clone()),
+            symbol_name,
+            kind,
+            signature: Some(signature),
+            doc_comment: (!doc_comment.is_empty()).then_some(doc_comment),
+            code: serde_norway::to_string(&snippet).unwrap_or_default(),
+            parent_type: None,
+            module_path: None,
+            annotations: Vec::new(),
+            metadata,
+            line_start,
+            line_end,
+        }
+    }
+
+    fn base_metadata(&self) -> Map<String, JsonValue> {
+        let info = self.root.get("info");
+        let mut metadata = Map::new();
+        metadata.insert(
+            "spec_title".into(),
+            json!(info.and_then(|i| str_at(i, "title"))),
+        );
+        metadata.insert(
+            "spec_version".into(),
+            json!(info.and_then(|i| i.get("version")).map(scalar_string)),
+        );
+        metadata
+    }
+}
+
+fn str_at<'v>(v: &'v Value, key: &str) -> Option<&'v str> {
+    v.get(key).and_then(Value::as_str)
+}
+
+/// Mapping keys may be numbers (`200:`) in YAML.
+fn scalar_string(v: &Value) -> String {
+    match v {
+        Value::String(s) => s.clone(),
+        other => serde_norway::to_string(other)
+            .unwrap_or_default()
+            .trim()
+            .to_string(),
+    }
+}
+
+/// `#/components/schemas/Order` → `Order`.
+fn ref_name(r: &str) -> String {
+    r.rsplit('/').next().unwrap_or(r).to_string()
+}
+
+/// Name of a schema object: its `$ref`, `Item[]` for arrays, else its type.
+fn schema_name(schema: &Value) -> Option<String> {
+    if let Some(r) = str_at(schema, "$ref") {
+        return Some(ref_name(r));
+    }
+    match str_at(schema, "type") {
+        Some("array") => schema
+            .get("items")
+            .and_then(schema_name)
+            .map(|item| format!("{item}[]")),
+        other => other.map(str::to_string),
+    }
+}
+
+/// Schema of the first media type of an OpenAPI 3 request body / response.
+fn content_schema(v: &Value) -> Option<String> {
+    let content = v.get("content")?.as_mapping()?;
+    content
+        .values()
+        .find_map(|media| media.get("schema").and_then(schema_name))
+}
+
+/// First line in `lines[from..to]` that is the mapping key `key` (bare or
+/// quoted, YAML or JSON).
+pub(crate) fn find_key(lines: &[&str], from: usize, to: usize, key: &str) -> Option<usize> {
+    let candidates = [
+        format!("{key}:"),
+        format!("\"{key}\":"),
+        format!("'{key}':"),
+    ];
+    (from..to.min(lines.len())).find(|&i| {
+        let trimmed = lines[i].trim_start();
+        candidates.iter().any(|c| trimmed.starts_with(c.as_str()))
+    })
+}
+
+/// Last line of the block opened by the key at line `at`: everything more
+/// indented below it, plus a JSON closing bracket at the same indentation.
+pub(crate) fn block_end(lines: &[&str], at: usize) -> usize {
+    let indent = |l: &str| l.len() - l.trim_start().len();
+    let base = indent(lines[at]);
+    let mut end = at;
+    for (i, line) in lines.iter().enumerate().skip(at + 1) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if indent(line) <= base {
+            if indent(line) == base && line.trim_start().starts_with(['}', ']']) {
+                end = i;
+            }
+            break;
+        }
+        end = i;
+    }
+    end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::language::fixtures::{find, parse};
+
+    #[test]
+    fn matches_spec_file_names_only() {
+        let lang = OpenApiLanguage;
+        assert!(lang.matches(Path::new("api/openapi.yaml")));
+        assert!(lang.matches(Path::new("docs/swagger.json")));
+        assert!(lang.matches(Path::new("OpenAPI.v2.yml")));
+        assert!(!lang.matches(Path::new("docker-compose.yml")));
+        assert!(!lang.matches(Path::new("openapi.md")));
+    }
+
+    #[test]
+    fn openapi3_operations_and_schemas() {
+        let src = r#"openapi: 3.0.3
+info:
+  title: Orders API
+  version: 1.2.0
+paths:
+  /orders/{id}:
+    parameters:
+      - name: id
+        in: path
+        required: true
+    get:
+      operationId: getOrder
+      summary: Fetch one order
+      tags: [orders]
+      responses:
+        200:
+          description: ok
+          content:
+            application/json:
+              schema:
+                $ref: '#/components/schemas/OrderSummary'
+        404:
+          description: missing
+    delete:
+      operationId: deleteOrder
+      responses:
+        '204':
+          description: gone
+  /orders:
+    post:
+      operationId: createOrder
+      requestBody:
+        content:
+          application/json:
+            schema:
+              $ref: '#/components/schemas/NewOrder'
+      responses:
+        '201':
+          content:
+            application/json:
+              schema:
+                type: array
+                items:
+                  $ref: '#/components/schemas/OrderSummary'
+components:
+  schemas:
+    OrderSummary:
+      type: object
+      description: Summary of an order.
+      properties:
+        id: { type: string }
+    NewOrder:
+      type: object
+"#;
+        let docs = parse(&OpenApiLanguage, "api/openapi.yaml", src);
+
+        let get = find(&docs, "GET /orders/{id}");
+        assert_eq!(get.kind, DocumentKind::Endpoint);
+        assert_eq!(get.doc_comment.as_deref(), Some("Fetch one order"));
+        assert_eq!(
+            get.signature.as_deref(),
+            Some("GET /orders/{id} (getOrder) -> OrderSummary")
+        );
+        assert_eq!(get.metadata["operation_id"], "getOrder");
+        assert_eq!(get.metadata["parameters"][0]["name"], "id");
+        assert_eq!(get.metadata["parameters"][0]["in"], "path");
+        assert_eq!(get.metadata["response_schemas"]["200"], "OrderSummary");
+        assert_eq!(get.metadata["spec_title"], "Orders API");
+        assert_eq!(get.metadata["tags"][0], "orders");
+        assert_eq!((get.line_start, get.line_end), (11, 23));
+        assert!(get.code.contains("getOrder"));
+
+        let post = find(&docs, "POST /orders");
+        assert_eq!(post.metadata["request_schema"], "NewOrder");
+        assert_eq!(post.metadata["response_schemas"]["201"], "OrderSummary[]");
+        assert_eq!(
+            find(&docs, "DELETE /orders/{id}").metadata["method"],
+            "DELETE"
+        );
+
+        let summary = find(&docs, "OrderSummary");
+        assert_eq!(summary.kind, DocumentKind::Schema);
+        assert_eq!(summary.doc_comment.as_deref(), Some("Summary of an order."));
+        assert_eq!((summary.line_start, summary.line_end), (47, 51));
+        assert_eq!(find(&docs, "NewOrder").kind, DocumentKind::Schema);
+    }
+
+    #[test]
+    fn swagger2_json() {
+        let src = r##"{
+  "swagger": "2.0",
+  "info": { "title": "Billing", "version": "1" },
+  "paths": {
+    "/invoices": {
+      "post": {
+        "operationId": "createInvoice",
+        "parameters": [
+          { "in": "body", "name": "body", "schema": { "$ref": "#/definitions/Invoice" } },
+          { "in": "header", "name": "X-Tenant", "required": true }
+        ],
+        "responses": { "200": { "schema": { "$ref": "#/definitions/Invoice" } } }
+      }
+    }
+  },
+  "definitions": {
+    "Invoice": { "type": "object" }
+  }
+}
+"##;
+        let docs = parse(&OpenApiLanguage, "swagger.json", src);
+        let post = find(&docs, "POST /invoices");
+        assert_eq!(post.metadata["request_schema"], "Invoice");
+        assert_eq!(post.metadata["parameters"][0]["name"], "X-Tenant");
+        assert_eq!(post.metadata["response_schemas"]["200"], "Invoice");
+        assert_eq!((post.line_start, post.line_end), (6, 13));
+        assert_eq!(find(&docs, "Invoice").kind, DocumentKind::Schema);
+    }
+
+    #[test]
+    fn invalid_spec_is_an_error() {
+        let file = SourceFile {
+            repo: "orders",
+            file_path: "openapi.yaml",
+            source: "paths: [unclosed",
+        };
+        assert!(OpenApiLanguage.parse_file(&file, false).is_err());
+    }
+}
@@ -0,0 +1,453 @@
+//! proto_parser.rs
+//!
+//! Protobuf / gRPC contract support for the language registry. There is no
+//! tree-sitter grammar for `.proto` here, so `ProtoLanguage` overrides
+//! `parse_file` with a small tokenizer + brace-matching walk.
+//!
+//! Documents include:
+//! - `service` definitions
+//! - `rpc` definitions (parent_type = service)
+//! - `message` definitions, incl. nested ones (parent_type = enclosing messages)
+//! - `enum` definitions (top-level or nested in a message)
+//!
+//! For each document:
+//! - module_path is the `package`; qualified_name is package + parents + name,
+//!   `.`-joined (`orders.v1.OrderService.GetOrder`), i.e. the fully-qualified
+//!   protobuf name, so the same contract vendored into client and server repos
+//!   resolves to the same `lookup` key
+//! - doc_comment is the run of `//` comments directly above the definition
+//! - rpc metadata: `package`, `service`, `request_type`, `response_type`,
+//!   `client_streaming`, `server_streaming` and `grpc_path`
+//!   (`/orders.v1.OrderService/GetOrder`, what generated stubs call)
+//! - other definitions carry `package` in metadata
+//!
+//! Options, fields, `oneof`s and `extend` blocks are left inside their
+//! enclosing definition's code.
+
+use crate::ingest::language::{LanguageSupport, SourceFile};
+use crate::ingest::rust_parser::{
+    Document, DocumentKind, RustParserError, build_file_summary, byte_count,
+    line_comment_block_above, normalize_ws,
+};
+use serde_json::{Map, Value, json};
+
+pub struct ProtoLanguage;
+
+impl LanguageSupport for ProtoLanguage {
+    fn name(&self) -> &'static str {
+        "protobuf"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["proto"]
+    }
+
+    fn path_separator(&self) -> &'static str {
+        "."
+    }
+
+    fn parse_file(
+        &self,
+        file: &SourceFile,
+        include_filename_doc: bool,
+    ) -> Result<Vec<Document>, RustParserError> {
+        let tokens = tokenize(file.source);
+        let package = package_name(&tokens);
+
+        let mut out = Vec::new();
+        let walker = Walker {
+            file,
+            tokens: &tokens,
+            package: package.as_deref(),
+        };
+        walker.walk(0, tokens.len(), &[], &mut out);
+
+        if include_filename_doc {
+            let imports: Vec<String> = file
+                .source
+                .lines()
+                .map(str::trim)
+                .filter(|l| l.starts_with("import "))
+                .map(str::to_string)
+                .collect();
+            let mut doc = build_file_summary(file, None, &imports, &out);
+            doc.qualified_name = Some(package.unwrap_or_else(|| file.file_path.into()));
+            out.insert(0, doc);
+        }
+        Ok(out)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TokenKind {
+    /// Identifiers, keywords, (dotted) type names and numbers.
+    Word,
+    /// String literal, quotes included.
+    Str,
+    Punct,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    kind: TokenKind,
+    text: &'a str,
+    start: usize,
+    end: usize,
+}
+
+/// Splits proto source into tokens, dropping whitespace and comments.
+fn tokenize(source: &str) -> Vec<Token<'_>> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        if c.is_ascii_whitespace() {
+            i += 1;
+        } else if source[i..].starts_with("//") {
+            i = source[i..].find('\n').map_or(bytes.len(), |n| i + n);
+        } else if source[i..].starts_with("/*") {
+            i = source[i + 2..]
+                .find("*/")
+                .map_or(bytes.len(), |n| i + 2 + n + 2);
+        } else if c == b'"' || c == b'\'' {
+            let start = i;
+            i += 1;
+            while i < bytes.len() && bytes[i] != c {
+                i += if bytes[i] == b'\\' { 2 } else { 1 };
+            }
+            i = (i + 1).min(bytes.len());
+            tokens.push(Token {
+                kind: TokenKind::Str,
+                text: &source[start..i],
+                start,
+                end: i,
+            });
+        } else if c.is_ascii_alphanumeric() || c == b'_' || c == b'.' {
+            let start = i;
+            while i < bytes.len()
+                && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_' || bytes[i] == b'.')
+            {
+                i += 1;
+            }
+            tokens.push(Token {
+                kind: TokenKind::Word,
+                text: &source[start..i],
+                start,
+                end: i,
+            });
+        } else {
+            // multi-byte UTF-8 only occurs in strings/comments in valid proto
+            let len = source[i..].chars().next().map_or(1, char::len_utf8);
+            tokens.push(Token {
+                kind: TokenKind::Punct,
+                text: &source[i..i + len],
+                start: i,
+                end: i + len,
+            });
+            i += len;
+        }
+    }
+    tokens
+}
+
+/// Value of the top-level `package x.y;` statement.
+fn package_name(tokens: &[Token]) -> Option<String> {
+    let mut depth = 0usize;
+    for (i, t) in tokens.iter().enumerate() {
+        match t.text {
+            "{" => depth += 1,
+            "}" => depth = depth.saturating_sub(1),
+            "package" if depth == 0 && t.kind == TokenKind::Word => {
+                return tokens
+                    .get(i + 1)
+                    .filter(|n| n.kind == TokenKind::Word)
+                    .map(|n| n.text.to_string());
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+struct Walker<'a> {
+    file: &'a SourceFile<'a>,
+    tokens: &'a [Token<'a>],
+    package: Option<&'a str>,
+}
+
+impl Walker<'_> {
+    /// Emits the definitions in `tokens[from..to]`; `parents` are the names
+    /// of the enclosing message/service definitions.
+    fn walk(&self, from: usize, to: usize, parents: &[String], out: &mut Vec<Document>) {
+        let tokens = self.tokens;
+        let mut i = from;
+        while i < to {
+            let t = tokens[i];
+            let name = tokens
+                .get(i + 1)
+                .filter(|n| n.kind == TokenKind::Word && i + 1 < to);
+            match (t.kind, t.text, name) {
+                (TokenKind::Word, "message" | "enum" | "service", Some(name)) => {
+                    let Some(open) = self.find(i + 2, to, "{") else {
+                        return;
+                    };
+                    let close = self.matching_brace(open, to);
+                    let kind = match t.text {
+                        "message" => DocumentKind::Message,
+                        "enum" => DocumentKind::Enum,
+                        _ => DocumentKind::Service,
+                    };
+                    let nested = kind != DocumentKind::Enum;
+                    let metadata = self.base_metadata();
+                    out.push(self.document(i, close, name.text, kind, parents, metadata));
+
+                    if nested {
+                        let mut inner = parents.to_vec();
+                        inner.push(name.text.to_string());
+                        self.walk(open + 1, close, &inner, out);
+                    }
+                    i = close + 1;
+                }
+                (TokenKind::Word, "rpc", Some(name)) => {
+                    let end = self.rpc_end(i, to);
+                    out.extend(self.rpc(i, end, name.text, parents));
+                    i = end + 1;
+                }
+                (TokenKind::Punct, "{", _) => {
+                    // option blocks, `oneof`, `extend`, ...
+                    i = self.matching_brace(i, to) + 1;
+                }
+                _ => i += 1,
+            }
+        }
+    }
+
+    fn rpc(&self, start: usize, end: usize, name: &str, parents: &[String]) -> Option<Document> {
+        // rpc Name ( [stream] Request ) returns ( [stream] Response )
+        let tokens = &self.tokens[start..=end];
+        let mut groups: Vec<(bool, String)> = Vec::new();
+        let mut i = 0;
+        while i < tokens.len() && groups.len() < 2 {
+            if tokens[i].text == "(" {
+                let stream = tokens.get(i + 1).is_some_and(|t| t.text == "stream");
+                let ty = tokens.get(i + 1 + usize::from(stream))?;
+                groups.push((stream, ty.text.trim_start_matches('.').to_string()));
+            }
+            i += 1;
+        }
+        let [(client_streaming, request), (server_streaming, response)] =
+            <[(bool, String); 2]>::try_from(groups).ok()?;
+
+        let service = parents.last().cloned().unwrap_or_default();
+        let mut metadata = self.base_metadata();
+        metadata.insert("service".into(), json!(service));
+        metadata.insert("request_type".into(), json!(request));
+        metadata.insert("response_type".into(), json!(response));
+        metadata.insert("client_streaming".into(), json!(client_streaming));
+        metadata.insert("server_streaming".into(), json!(server_streaming));
+        let full_service = match self.package {
+            Some(p) => format!("{p}.{service}"),
+            None => service.clone(),
+        };
+        metadata.insert("grpc_path".into(), json!(format!("/{full_service}/{name}")));
+
+        Some(self.document(start, end, name, DocumentKind::Rpc, parents, metadata))
+    }
+
+    /// Index of the `;` or closing `}` ending the rpc starting at `start`.
+    fn rpc_end(&self, start: usize, to: usize) -> usize {
+        for i in start..to {
+            match self.tokens[i].text {
+                ";" => return i,
+                "{" => return self.matching_brace(i, to),
+                _ => {}
+            }
+        }
+        to.saturating_sub(1)
+    }
+
+    /// Document spanning tokens `first..=last`; the signature runs up to
+    /// (excluding) its first `{` or `;`.
+    fn document(
+        &self,
+        first: usize,
+        last: usize,
+        name: &str,
+        kind: DocumentKind,
+        parents: &[String],
+        metadata: Map<String, Value>,
+    ) -> Document {
+        let source = self.file.source;
+        let last = last.min(self.tokens.len() - 1);
+        let start = self.tokens[first].start;
+        let end = self.tokens[last].end;
+        let header_end = (first..=last)
+            .find(|&i| {
+                self.tokens[i].kind == TokenKind::Punct && matches!(self.tokens[i].text, "{" | ";")
+            })
+            .map_or(end, |i| self.tokens[i].start);
+        let signature = normalize_ws(&source[start..header_end]);
+
+        let parent_type = (!parents.is_empty()).then(|| parents.join("."));
+        let mut qualified: Vec<&str> = self.package.into_iter().collect();
+        qualified.extend(parents.iter().map(String::as_str));
+        qualified.push(name);
+
+        Document {
+            repo: self.file.repo.to_string(),
+            file_path: self.file.file_path.to_string(),
+            symbol_name: name.to_string(),
+            kind,
+            signature: Some(signature),
+            doc_comment: line_comment_block_above(source, start, "//"),
+            code: source[start..end].to_string(),
+            parent_type,
+            module_path: self.package.map(str::to_string),
+            qualified_name: Some(qualified.join(".")),
+            annotations: Vec::new(),
+            metadata,
+            line_start: line_of(source, start),
+            line_end: line_of(source, end.saturating_sub(1).max(start)),
+        }
+    }
+
+    fn base_metadata(&self) -> Map<String, Value> {
+        let mut metadata = Map::new();
+        if let Some(p) = self.package {
+            metadata.insert("package".into(), json!(p));
+        }
+        metadata
+    }
+
+    fn find(&self, from: usize, to: usize, text: &str) -> Option<usize> {
+        (from..to.min(self.tokens.len()))
+            .find(|&i| self.tokens[i].kind == TokenKind::Punct && self.tokens[i].text == text)
+    }
+
+    /// Index of the `}` closing the `{` at `open` (or the last token before
+    /// `to` if unbalanced).
+    fn matching_brace(&self, open: usize, to: usize) -> usize {
+        let mut depth = 0usize;
+        for i in open..to {
+            let t = self.tokens[i];
+            if t.kind != TokenKind::Punct {
+                continue;
+            }
+            match t.text {
+                "{" => depth += 1,
+                "}" => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return i;
+                    }
+                }
+                _ => {}
+            }
+        }
+        to.saturating_sub(1)
+    }
+}
+
+/// 1-based line of byte offset `pos`.
+fn line_of(source: &str, pos: usize) -> u32 {
+    1 + byte_count(&source.as_bytes()[..pos], b'\n') as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::language::fixtures::{find, parse};
+
+    #[test]
+    fn services_rpcs_messages_and_enums() {
+        let src = r#"syntax = "proto3";
+
+package orders.v1;
+
+option go_package = "example.com/orders/v1;ordersv1";
+
+// Order lifecycle operations.
+service OrderService {
+  // Fetches one order summary.
+  rpc GetOrder(GetOrderRequest) returns (OrderSummary);
+
+  rpc WatchOrders(stream WatchRequest) returns (stream .orders.v1.OrderEvent) {
+    option (google.api.http) = { get: "/v1/orders:watch" };
+  }
+}
+
+/* Summary of an order. */
+message OrderSummary {
+  string id = 1; // the order id
+  Status status = 2;
+
+  // Lifecycle state.
+  enum Status {
+    STATUS_UNSPECIFIED = 0;
+    STATUS_OPEN = 1;
+  }
+
+  message Line {
+    string sku = 1;
+  }
+  oneof payment {
+    string card = 3;
+  }
+}
+"#;
+        let docs = parse(&ProtoLanguage, "proto/orders/v1/orders.proto", src);
+
+        let service = find(&docs, "OrderService");
+        assert_eq!(service.kind, DocumentKind::Service);
+        assert_eq!(
+            service.doc_comment.as_deref(),
+            Some("Order lifecycle operations.")
+        );
+        assert_eq!(service.line_start, 8);
+        assert_eq!(service.line_end, 15);
+
+        let get = find(&docs, "GetOrder");
+        assert_eq!(get.kind, DocumentKind::Rpc);
+        assert_eq!(get.parent_type.as_deref(), Some("OrderService"));
+        assert_eq!(
+            get.doc_comment.as_deref(),
+            Some("Fetches one order summary.")
+        );
+        assert_eq!(
+            get.signature.as_deref(),
+            Some("rpc GetOrder(GetOrderRequest) returns (OrderSummary)")
+        );
+        assert_eq!(
+            get.qualified_name.as_deref(),
+            Some("orders.v1.OrderService.GetOrder")
+        );
+        assert_eq!(get.metadata["request_type"], "GetOrderRequest");
+        assert_eq!(get.metadata["response_type"], "OrderSummary");
+        assert_eq!(
+            get.metadata["grpc_path"],
+            "/orders.v1.OrderService/GetOrder"
+        );
+        assert_eq!(get.metadata["package"], "orders.v1");
+
+        let watch = find(&docs, "WatchOrders");
+        assert_eq!(watch.metadata["client_streaming"], true);
+        assert_eq!(watch.metadata["server_streaming"], true);
+        assert_eq!(watch.metadata["response_type"], "orders.v1.OrderEvent");
+        assert!(watch.code.ends_with('}'));
+
+        let summary = find(&docs, "OrderSummary");
+        assert_eq!(summary.kind, DocumentKind::Message);
+        assert!(summary.code.contains("oneof payment"));
+
+        let status = find(&docs, "Status");
+        assert_eq!(status.kind, DocumentKind::Enum);
+        assert_eq!(status.parent_type.as_deref(), Some("OrderSummary"));
+        assert_eq!(status.doc_comment.as_deref(), Some("Lifecycle state."));
+        assert_eq!(
+            find(&docs, "Line").qualified_name.as_deref(),
+            Some("orders.v1.OrderSummary.Line")
+        );
+        assert!(docs.iter().all(|d| d.symbol_name != "payment"));
+    }
+}
@@ -0,0 +1,381 @@
+//! python_parser.rs
+//!
+//! Python support for the language registry (tree-sitter-python).
+//! Documents include:
+//! - module-level functions (incl. `async def`)
+//! - classes, including nested ones (parent_type = enclosing class chain)
+//! - methods: functions defined in a class body (parent_type = `.`-joined
+//!   class chain, e.g. `Order.Meta`)
+//! - Kafka / NATS topic usages as `topic` documents (see `messaging.rs`)
+//! - SQL string literals as `sql_query` documents (see `sql.rs`)
+//!
+//! Notes:
+//! - Definitions local to a function body are skipped.
+//! - Decorators (`@app.get("/orders")`, `@router.post(...)`, `@dataclass`) are
+//!   part of the code, signature and line range, and are also recorded as
+//!   `annotations` (name + argument text) so route decorators can be filtered on.
+//! - Doc comments are the docstring (first statement of the body), cleaned up
+//!   like `inspect.cleandoc`.
+//! - Signatures are decorators + `def`/`class` header without the trailing `:`,
+//!   whitespace-normalized.
+//! - qualified_name is the dotted module path (from the file location, leading
+//!   `src/` and trailing `__init__` dropped) + class chain + name.
+
+use crate::ingest::language::{LanguageSupport, SourceFile};
+use crate::ingest::messaging;
+use crate::ingest::rust_parser::{
+    Annotation, Document, DocumentKind, ParseLanguage, field_text, lines_of, node_text,
+    normalize_ws,
+};
+use crate::ingest::sql;
+use std::path::Path;
+use tree_sitter::Node;
+
+pub struct PythonLanguage;
+
+impl LanguageSupport for PythonLanguage {
+    fn name(&self) -> &'static str {
+        "python"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["py", "pyi"]
+    }
+
+    fn grammar(&self) -> Option<ParseLanguage> {
+        Some(ParseLanguage::Python)
+    }
+
+    fn visit(&self, file: &SourceFile, node: Node, out: &mut Vec<Document>) {
+        match node.kind() {
+            "function_definition" | "class_definition" => {
+                out.extend(extract_definition(file, node));
+            }
+            "call" => messaging::python_topic_usage(file, node, out),
+            "string" => sql::sql_query(file, node, out),
+            _ => {}
+        }
+    }
+
+    fn file_module(&self, file: &SourceFile, _root: Node) -> Option<String> {
+        python_module(file.file_path)
+    }
+
+    fn path_separator(&self) -> &'static str {
+        "."
+    }
+
+    fn import_kinds(&self) -> &'static [&'static str] {
+        &[
+            "import_statement",
+            "import_from_statement",
+            "future_import_statement",
+        ]
+    }
+
+    fn module_doc(&self, file: &SourceFile, root: Node) -> Option<String> {
+        docstring(file.source, root)
+    }
+}
+
+/// Dotted module of a file: `src/orders/api/routes.py` → `orders.api.routes`,
+/// `orders/__init__.py` → `orders`.
+fn python_module(file_path: &str) -> Option<String> {
+    let stem = Path::new(file_path).with_extension("");
+    let mut segments: Vec<String> = stem
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    if segments.first().is_some_and(|s| s == "src") {
+        segments.remove(0);
+    }
+    if segments.last().is_some_and(|s| s == "__init__") {
+        segments.pop();
+    }
+    (!segments.is_empty()).then(|| segments.join("."))
+}
+
+fn extract_definition(file: &SourceFile, node: Node) -> Option<Document> {
+    let source = file.source;
+    let outer = node
+        .parent()
+        .filter(|p| p.kind() == "decorated_definition")
+        .unwrap_or(node);
+    // `None`: local to a function body
+    let classes = enclosing_classes(source, outer)?;
+
+    let kind = match node.kind() {
+        "class_definition" => DocumentKind::Class,
+        _ if classes.is_some() => DocumentKind::Method,
+        _ => DocumentKind::Function,
+    };
+    let name = node.child_by_field_name("name")?;
+    let body = node.child_by_field_name("body");
+    let header_end = body.map_or(node.end_byte(), |b| b.start_byte());
+    let signature = normalize_ws(&source[outer.start_byte()..header_end])
+        .trim_end_matches(':')
+        .to_string();
+    let (line_start, line_end) = lines_of(&outer);
+
+    Some(Document {
+        repo: file.repo.to_string(),
+        file_path: file.file_path.to_string(),
+        symbol_name: node_text(source, name),
+        kind,
+        signature: Some(signature),
+        doc_comment: body.and_then(|b| docstring(source, b)),
+        code: node_text(source, outer),
+        parent_type: classes,
+        module_path: None,
+        qualified_name: None,
+        annotations: decorators(source, outer),
+        metadata: Default::default(),
+        line_start,
+        line_end,
+    })
+}
+
+/// `.`-joined names of the classes whose body contains `node`, outermost
+/// first. Returns `None` when `node` is nested in a function.
+fn enclosing_classes(source: &str, node: Node) -> Option<Option<String>> {
+    let mut names = Vec::new();
+    let mut cur = node.parent();
+    while let Some(n) = cur {
+        match n.kind() {
+            "class_definition" => {
+                if let Some(name) = n.child_by_field_name("name") {
+                    names.push(node_text(source, name));
+                }
+            }
+            "function_definition" | "lambda" => return None,
+            _ => {}
+        }
+        cur = n.parent();
+    }
+    if names.is_empty() {
+        return Some(None);
+    }
+    names.reverse();
+    Some(Some(names.join(".")))
+}
+
+/// Decorators of a `decorated_definition`, in source order.
+fn decorators(source: &str, outer: Node) -> Vec<Annotation> {
+    if outer.kind() != "decorated_definition" {
+        return Vec::new();
+    }
+    (0..outer.named_child_count())
+        .filter_map(|i| outer.named_child(i))
+        .filter(|ch| ch.kind() == "decorator")
+        .filter_map(|dec| dec.named_child(0))
+        .map(|expr| match expr.kind() {
+            "call" => Annotation {
+                name: normalize_ws(&field_text(source, expr, "function")),
+                arguments: expr
+                    .child_by_field_name("arguments")
+                    .map(|args| strip_parens(&node_text(source, args))),
+            },
+            _ => Annotation {
+                name: normalize_ws(&node_text(source, expr)),
+                arguments: None,
+            },
+        })
+        .collect()
+}
+
+fn strip_parens(args: &str) -> String {
+    let inner = args
+        .strip_prefix('(')
+        .and_then(|a| a.strip_suffix(')'))
+        .unwrap_or(args);
+    normalize_ws(inner)
+}
+
+/// The string literal opening `body`, if it is a bare expression statement.
+fn docstring(source: &str, body: Node) -> Option<String> {
+    let first = body.named_child(0)?;
+    if first.kind() != "expression_statement" || first.named_child_count() != 1 {
+        return None;
+    }
+    let string = first.named_child(0).filter(|s| s.kind() == "string")?;
+    let raw = node_text(source, string);
+    let cleaned = clean_docstring(strip_quotes(&raw));
+    (!cleaned.is_empty()).then_some(cleaned)
+}
+
+/// Drops string prefixes (`r`, `u`, ...) and the surrounding quotes.
+fn strip_quotes(raw: &str) -> &str {
+    let s = raw.trim_start_matches(|c: char| c.is_ascii_alphabetic());
+    for quote in ["\"\"\"", "'''", "\"", "'"] {
+        if let Some(inner) = s.strip_prefix(quote).and_then(|s| s.strip_suffix(quote)) {
+            return inner;
+        }
+    }
+    s
+}
+
+/// First line trimmed, common indentation of the rest removed, surrounding
+/// blank lines dropped (PEP 257).
+fn clean_docstring(doc: &str) -> String {
+    let mut lines = doc.lines();
+    let first = lines.next().unwrap_or_default().trim();
+    let rest: Vec<&str> = lines.collect();
+    let indent = rest
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    let mut out = vec![first.to_string()];
+    out.extend(
+        rest.iter()
+            .map(|l| l.get(indent..).unwrap_or("").trim_end().to_string()),
+    );
+    out.join("\n").trim_matches('\n').trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::language::fixtures::{find, parse};
+
+    #[test]
+    fn functions_classes_methods_and_decorators() {
+        let src = r#"
+"""Pricing routes."""
+from fastapi import APIRouter
+
+router = APIRouter()
+
+
+@router.get("/prices/{sku}", response_model=Price)
+async def get_price(sku: str) -> Price:
+    """Look up the current price.
+
+    Falls back to the list price.
+    """
+    def local():
+        pass
+    return await repo.get(sku)
+
+
+@dataclass
+class Price(BaseModel):
+    """A price point."""
+
+    class Meta:
+        def describe(self):
+            return "meta"
+
+    @property
+    def total(self) -> int:
+        return self.amount
+
+    def _helper(self):
+        pass
+"#;
+        let docs = parse(&PythonLanguage, "src/pricing/api/routes.py", src);
+        let names: Vec<&str> = docs.iter().map(|d| d.symbol_name.as_str()).collect();
+        assert!(!names.contains(&"local"));
+
+        let get = find(&docs, "get_price");
+        assert_eq!(get.kind, DocumentKind::Function);
+        assert_eq!(
+            get.doc_comment.as_deref(),
+            Some("Look up the current price.\n\nFalls back to the list price.")
+        );
+        assert_eq!(
+            get.signature.as_deref(),
+            Some(
+                r#"@router.get("/prices/{sku}", response_model=Price) async def get_price(sku: str) -> Price"#
+            )
+        );
+        assert_eq!(
+            get.annotations,
+            vec![Annotation {
+                name: "router.get".into(),
+                arguments: Some(r#""/prices/{sku}", response_model=Price"#.into()),
+            }]
+        );
+        assert!(get.code.starts_with("@router.get"));
+        assert_eq!(
+            get.qualified_name.as_deref(),
+            Some("pricing.api.routes.get_price")
+        );
+
+        let price = find(&docs, "Price");
+        assert_eq!(price.kind, DocumentKind::Class);
+        assert_eq!(price.doc_comment.as_deref(), Some("A price point."));
+        assert_eq!(price.annotations[0].name, "dataclass");
+        assert_eq!(price.annotations[0].arguments, None);
+
+        let total = find(&docs, "total");
+        assert_eq!(total.kind, DocumentKind::Method);
+        assert_eq!(total.parent_type.as_deref(), Some("Price"));
+        assert_eq!(total.annotations[0].name, "property");
+        assert_eq!(
+            find(&docs, "describe").parent_type.as_deref(),
+            Some("Price.Meta")
+        );
+        assert_eq!(find(&docs, "Meta").parent_type.as_deref(), Some("Price"));
+    }
+
+    #[test]
+    fn module_paths() {
+        assert_eq!(
+            python_module("src/orders/api.py").as_deref(),
+            Some("orders.api")
+        );
+        assert_eq!(
+            python_module("orders/__init__.py").as_deref(),
+            Some("orders")
+        );
+        assert_eq!(python_module("manage.py").as_deref(), Some("manage"));
+    }
+
+    #[test]
+    fn file_summary_has_docstring_imports_and_signatures() {
+        let src = r#""""Pricing routes.
+
+Prices are cached per SKU.
+"""
+from __future__ import annotations
+import logging
+from fastapi import APIRouter
+
+def get_price(sku: str) -> Price:
+    return repo.get(sku)
+
+class Price(BaseModel):
+    def total(self) -> int:
+        return self.amount
+"#;
+        let file = SourceFile {
+            repo: "pricing",
+            file_path: "src/pricing/api/routes.py",
+            source: src,
+        };
+        let docs = PythonLanguage.parse_file(&file, true).expect("parse");
+        let summary = &docs[0];
+        assert_eq!(summary.kind, DocumentKind::Filename);
+        assert_eq!(
+            summary.qualified_name.as_deref(),
+            Some("pricing.api.routes")
+        );
+        assert_eq!(
+            summary.doc_comment.as_deref(),
+            Some("Pricing routes.\n\nPrices are cached per SKU.")
+        );
+        assert_eq!(
+            summary.code,
+            "from __future__ import annotations\n\
+             import logging\n\
+             from fastapi import APIRouter\n\
+             \n\
+             def get_price(sku: str) -> Price\n\
+             class Price(BaseModel)\n    \
+             def total(self) -> int"
+        );
+    }
+}
@@ -1,7 +1,8 @@
 //! repo_scanner.rs
 //!
-//! Recursively walks a repository and yields `.rs` source files,
-//! respecting `.gitignore`, `.ignore`, and standard ignore patterns.
+//! Recursively walks a repository and yields the source files of every
+//! language in the `LanguageRegistry`, respecting `.gitignore`, `.ignore`,
+//! and standard ignore patterns.
 //!
 //! Each repo corresponds to one Qdrant collection.
 //!
@@ -10,6 +11,7 @@
 //! - Use `ignore::WalkBuilder` to honor .gitignore/.ignore
 //! - Yield `(repo_name, relative_path, source_code)` for each valid file
 
+use crate::ingest::language::LanguageRegistry;
 use ignore::{DirEntry, WalkBuilder};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -31,14 +33,16 @@ pub struct FileEntry {
     pub source: String,
 }
 
-pub struct ProjectScanner;
+pub struct ProjectScanner<'a> {
+    registry: &'a LanguageRegistry,
+}
 
-impl ProjectScanner {
-    pub fn new() -> Self {
-        Self
+impl<'a> ProjectScanner<'a> {
+    pub fn new(registry: &'a LanguageRegistry) -> Self {
+        Self { registry }
     }
 
-    /// Scans the project and returns all valid .rs files in nested vectors.
+    /// Returns the repo roots (direct subdirectories) of the project.
     pub fn scan_project(&self, root: &Path) -> Result<Vec<PathBuf>, ProjectScannerError> {
         let mut directories = Vec::new();
 
@@ -56,14 +60,10 @@ impl ProjectScanner {
         Ok(directories)
     }
 
-    /// Scans the repository and returns all Rust source files (.rs),
+    /// Scans the repository and returns every file a registered language owns,
     /// honoring `.gitignore` and `.ignore` rules automatically.
     pub fn scan_repo(&self, root: &Path) -> Result<Vec<FileEntry>, ProjectScannerError> {
-        let repo_name = root
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("unknown")
-            .to_string();
+        let repo_name = repo_dir_name(root);
 
         let mut out = Vec::new();
 
@@ -73,6 +73,7 @@ impl ProjectScanner {
             .git_ignore(true)
             .git_exclude(true)
             .git_global(true)
+            .require_git(false) // honor .gitignore even outside a git checkout
             .ignore(true)
             .build();
 
@@ -82,11 +83,11 @@ impl ProjectScanner {
                 Err(_) => continue,
             };
 
-            if should_include(&entry) {
+            if self.should_include(&entry) {
                 let rel_path = entry.path().strip_prefix(root).unwrap_or(entry.path());
                 let rel_str = rel_path.to_string_lossy().to_string();
                 let content = fs::read_to_string(entry.path())
-                    .map_err(|e| ProjectScannerError::Io(e))
+                    .map_err(ProjectScannerError::Io)
                     .and_then(|s| {
                         if !s.is_char_boundary(s.len()) {
                             Err(ProjectScannerError::InvalidUtf8(rel_str.clone()))
@@ -105,20 +106,22 @@ impl ProjectScanner {
 
         Ok(out)
     }
-}
 
-/// Check whether a file should be included in scanning.
-fn should_include(entry: &DirEntry) -> bool {
-    let path = entry.path();
-    if !entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
-        return false;
-    }
-    match path.extension().and_then(|x| x.to_str()) {
-        Some("rs") | Some("kt") | Some("js") | Some("ts") => true,
-        _ => false,
+    /// Check whether a file should be included in scanning.
+    fn should_include(&self, entry: &DirEntry) -> bool {
+        entry.file_type().is_some_and(|t| t.is_file())
+            && self.registry.for_path(entry.path()).is_some()
     }
 }
 
+/// Repo name as recorded in `FileEntry::repo` (basename of the repo root).
+pub fn repo_dir_name(root: &Path) -> String {
+    root.file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("unknown")
+        .to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -134,11 +137,15 @@ mod tests {
         fs::write(repo.join("main.rs"), "fn main() {}").unwrap();
         fs::write(repo.join("ignored.rs"), "fn ignored() {}").unwrap();
 
-        let scanner = ProjectScanner::new();
+        fs::write(repo.join("notes.txt"), "not source").unwrap();
+
+        let registry = LanguageRegistry::default();
+        let scanner = ProjectScanner::new(&registry);
         let files = scanner.scan_repo(repo).unwrap();
 
         let filenames: Vec<_> = files.iter().map(|f| &f.file_path).collect();
         assert!(filenames.contains(&&"main.rs".to_string()));
         assert!(!filenames.contains(&&"ignored.rs".to_string()));
+        assert!(!filenames.contains(&&"notes.txt".to_string()));
     }
 }
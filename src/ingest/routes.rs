@@ -0,0 +1,900 @@
+//! routes.rs
+//!
+//! HTTP routes declared in framework code, emitted as `endpoint` documents by
+//! the language that parses the file (called from its `visit`):
+//! - axum: `.route("/orders/{id}", get(show).post(create))`, prefixed by
+//!   enclosing `.nest("/v1", ...)` calls
+//! - actix-web: `#[get("/orders")]` / `#[route("/x", method = "POST")]` on
+//!   functions, `.route("/x", web::get().to(show))` inside `web::scope("/v1")`
+//! - Express: `app.get("/orders", handler)`, `router.route("/orders").post(...)`
+//! - Ktor: `get("/orders") { ... }` inside `routing { }`, nested
+//!   `route("/prefix") { }` blocks, and `Route` extension functions
+//! - Spring: `@GetMapping`, `@PostMapping`, ..., `@RequestMapping` on methods,
+//!   prefixed by the class-level `@RequestMapping`
+//!
+//! One document per (method, path): symbol_name and qualified_name are
+//! `GET /orders/{id}`, the same shape as OpenAPI endpoints, so `lookup` finds
+//! both. Metadata: `method` (`ANY` when not restricted), `path` (prefixes
+//! joined, placeholders as declared), `handler` (the handler as written, e.g.
+//! `handlers::show` or `OrderController.get`; `null` for inline closures) and
+//! `framework`. The handler's point id is stored as `handler_point_id` after
+//! each indexing pass (`transform::route_linker`).
+
+use crate::ingest::java_parser;
+use crate::ingest::kotlin_parser::extension_receiver;
+use crate::ingest::language::SourceFile;
+use crate::ingest::rust_parser::{
+    Annotation, Document, DocumentKind, byte_count, field_text, node_text, normalize_ws,
+    slice_source,
+};
+use serde_json::json;
+use std::ops::Range;
+use tree_sitter::Node;
+
+/// A route found in code.
+struct Route {
+    method: String,
+    path: String,
+    handler: Option<String>,
+    framework: &'static str,
+}
+
+/// `get` → `GET`, for the HTTP verbs frameworks name their route builders after.
+pub(crate) fn http_method(name: &str) -> Option<String> {
+    let lower = name.to_ascii_lowercase();
+    matches!(
+        lower.as_str(),
+        "get" | "post" | "put" | "delete" | "patch" | "head" | "options" | "trace"
+    )
+    .then(|| lower.to_ascii_uppercase())
+}
+
+/// Joins path segments: `("/orders", "{id}")` → `/orders/{id}`, `("", "")` → `/`.
+fn join_path(prefix: &str, path: &str) -> String {
+    let segments: Vec<&str> = prefix
+        .split('/')
+        .chain(path.split('/'))
+        .filter(|s| !s.is_empty())
+        .collect();
+    format!("/{}", segments.join("/"))
+}
+
+/// Contents of a string literal node; `None` for interpolated strings.
+fn string_value(source: &str, node: Node) -> Option<String> {
+    if !matches!(node.kind(), "string_literal" | "string" | "template_string") {
+        return None;
+    }
+    let text = node_text(source, node);
+    if text.contains("${") {
+        return None;
+    }
+    Some(text.trim_matches(['"', '\'', '`']).to_string())
+}
+
+pub(crate) fn named_children(node: Node) -> impl Iterator<Item = Node> {
+    (0..node.named_child_count())
+        .filter_map(move |i| node.named_child(i))
+        .filter(|ch| !ch.kind().contains("comment"))
+}
+
+fn route_document(
+    file: &SourceFile,
+    range: Range<usize>,
+    route: Route,
+    doc_comment: Option<String>,
+) -> Document {
+    let source = file.source;
+    let symbol_name = format!("{} {}", route.method, route.path);
+    let signature = match &route.handler {
+        Some(handler) => format!("{symbol_name} -> {handler}"),
+        None => symbol_name.clone(),
+    };
+    let line_start = 1 + byte_count(&source.as_bytes()[..range.start], b'\n') as u32;
+    let line_end = 1 + byte_count(&source.as_bytes()[..range.end], b'\n') as u32;
+
+    let mut metadata = serde_json::Map::new();
+    metadata.insert("method".into(), json!(route.method));
+    metadata.insert("path".into(), json!(route.path));
+    metadata.insert("handler".into(), json!(route.handler));
+    metadata.insert("framework".into(), json!(route.framework));
+
+    Document {
+        repo: file.repo.to_string(),
+        file_path: file.file_path.to_string(),
+        qualified_name: Some(symbol_name.clone()),
+        symbol_name,
+        kind: DocumentKind::Endpoint,
+        signature: Some(signature),
+        doc_comment,
+        code: slice_source(source, range),
+        parent_type: None,
+        module_path: None,
+        annotations: Vec::new(),
+        metadata,
+        line_start,
+        line_end,
+    }
+}
+
+// ---- Rust: axum / actix-web ----
+
+/// `.route(path, method_router)` calls (axum `Router`, actix-web `App`/`Scope`).
+pub(crate) fn rust_route_call(file: &SourceFile, node: Node, out: &mut Vec<Document>) {
+    let source = file.source;
+    if node.kind() != "call_expression" {
+        return;
+    }
+    let Some(function) = node
+        .child_by_field_name("function")
+        .filter(|f| f.kind() == "field_expression")
+    else {
+        return;
+    };
+    let Some(field) = function.child_by_field_name("field") else {
+        return;
+    };
+    if node_text(source, field) != "route" {
+        return;
+    }
+    let Some(args) = node.child_by_field_name("arguments") else {
+        return;
+    };
+    let mut args = named_children(args);
+    let (Some(path), Some(router)) = (
+        args.next().and_then(|p| string_value(source, p)),
+        args.next(),
+    ) else {
+        return;
+    };
+
+    let mut prefix = nest_prefix(source, node);
+    if let Some(receiver) = function.child_by_field_name("value") {
+        prefix.extend(scope_prefix(source, receiver));
+    }
+    let path = join_path(&prefix.join("/"), &path);
+    let framework = if node_text(source, router).contains(".to(") {
+        "actix-web"
+    } else {
+        "axum"
+    };
+
+    for (method, handler) in method_router(source, router) {
+        let route = Route {
+            method,
+            path: path.clone(),
+            handler,
+            framework,
+        };
+        out.push(route_document(
+            file,
+            field.start_byte()..node.end_byte(),
+            route,
+            None,
+        ));
+    }
+}
+
+/// Methods and handlers of an axum `MethodRouter` (`get(a).post(b)`) or an
+/// actix-web `Route` (`web::get().to(a)`).
+fn method_router(source: &str, node: Node) -> Vec<(String, Option<String>)> {
+    if node.kind() != "call_expression" {
+        return Vec::new();
+    }
+    let (Some(function), Some(args)) = (
+        node.child_by_field_name("function"),
+        node.child_by_field_name("arguments"),
+    ) else {
+        return Vec::new();
+    };
+    let handler = named_children(args)
+        .next()
+        .and_then(|h| rust_handler(source, h));
+
+    match function.kind() {
+        "identifier" | "scoped_identifier" => {
+            let name = node_text(source, function);
+            let name = name.rsplit("::").next().unwrap_or_default();
+            http_method(name)
+                .map(|m| vec![(m, handler)])
+                .unwrap_or_default()
+        }
+        "field_expression" => {
+            let mut routes = function
+                .child_by_field_name("value")
+                .map(|v| method_router(source, v))
+                .unwrap_or_default();
+            let field = field_text(source, function, "field");
+            if let Some(method) = http_method(&field) {
+                routes.push((method, handler));
+            } else if field == "to" {
+                for route in routes.iter_mut().filter(|r| r.1.is_none()) {
+                    route.1 = handler.clone();
+                }
+            }
+            routes
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Handler path as written; `None` for closures and other inline handlers.
+fn rust_handler(source: &str, node: Node) -> Option<String> {
+    matches!(
+        node.kind(),
+        "identifier" | "scoped_identifier" | "field_expression" | "generic_function"
+    )
+    .then(|| normalize_ws(&node_text(source, node)))
+}
+
+/// Paths of the `.nest("/prefix", router)` calls whose router contains `node`,
+/// outermost first.
+fn nest_prefix(source: &str, node: Node) -> Vec<String> {
+    let mut prefixes = Vec::new();
+    let mut child = node;
+    while let Some(parent) = child.parent() {
+        if parent.kind() == "arguments"
+            && let Some(call) = parent.parent()
+            && let Some(function) = call.child_by_field_name("function")
+            && function.kind() == "field_expression"
+            && function
+                .child_by_field_name("field")
+                .is_some_and(|f| &source[f.byte_range()] == "nest")
+            && let Some(path) = named_children(parent).next()
+            && path.id() != child.id()
+            && let Some(path) = string_value(source, path)
+        {
+            prefixes.push(path);
+        }
+        child = parent;
+    }
+    prefixes.reverse();
+    prefixes
+}
+
+/// Path of a `web::scope("/prefix")` at the root of a receiver chain.
+fn scope_prefix(source: &str, receiver: Node) -> Option<String> {
+    let mut cur = receiver;
+    loop {
+        if cur.kind() != "call_expression" {
+            return None;
+        }
+        let function = cur.child_by_field_name("function")?;
+        match function.kind() {
+            "field_expression" => cur = function.child_by_field_name("value")?,
+            "identifier" | "scoped_identifier" => {
+                let name = node_text(source, function);
+                if name.rsplit("::").next() != Some("scope") {
+                    return None;
+                }
+                let args = cur.child_by_field_name("arguments")?;
+                return named_children(args)
+                    .next()
+                    .and_then(|p| string_value(source, p));
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// `#[get("/path")]` / `#[route("/path", method = "GET")]` attributes on a
+/// function (actix-web, and Rocket's identical syntax). `handler` is the
+/// function's own document.
+pub(crate) fn rust_route_attributes(
+    file: &SourceFile,
+    function: Node,
+    handler: &Document,
+    out: &mut Vec<Document>,
+) {
+    let source = file.source;
+    let mut first_attribute = None;
+    let mut routes = Vec::new();
+    let mut sibling = function.prev_named_sibling();
+    while let Some(s) = sibling {
+        match s.kind() {
+            "attribute_item" => {
+                first_attribute = Some(s);
+                routes.extend(attribute_routes(source, s));
+            }
+            "line_comment" | "block_comment" => {}
+            _ => break,
+        }
+        sibling = s.prev_named_sibling();
+    }
+    let Some(first_attribute) = first_attribute else {
+        return;
+    };
+
+    let handler_name = match &handler.parent_type {
+        Some(parent) => format!("{parent}::{}", handler.symbol_name),
+        None => handler.symbol_name.clone(),
+    };
+    for (method, path) in routes {
+        let route = Route {
+            method,
+            path: join_path("", &path),
+            handler: Some(handler_name.clone()),
+            framework: "actix-web",
+        };
+        out.push(route_document(
+            file,
+            first_attribute.start_byte()..function.end_byte(),
+            route,
+            handler.doc_comment.clone(),
+        ));
+    }
+}
+
+fn attribute_routes(source: &str, attribute_item: Node) -> Vec<(String, String)> {
+    let Some(attribute) = named_children(attribute_item).next() else {
+        return Vec::new();
+    };
+    let Some(name) = named_children(attribute).next() else {
+        return Vec::new();
+    };
+    let name = node_text(source, name);
+    let name = name.rsplit("::").next().unwrap_or_default();
+    let Some(args) = attribute.child_by_field_name("arguments") else {
+        return Vec::new();
+    };
+    let tokens: Vec<Node> = named_children(args).collect();
+    let Some(path) = tokens.first().and_then(|t| string_value(source, *t)) else {
+        return Vec::new();
+    };
+
+    if let Some(method) = http_method(name) {
+        return vec![(method, path)];
+    }
+    if name != "route" {
+        return Vec::new();
+    }
+    // `method = "GET"` pairs
+    let methods: Vec<String> = tokens
+        .windows(2)
+        .filter(|w| &source[w[0].byte_range()] == "method")
+        .filter_map(|w| string_value(source, w[1]))
+        .map(|m| m.to_ascii_uppercase())
+        .collect();
+    if methods.is_empty() {
+        return vec![("ANY".to_string(), path)];
+    }
+    methods.into_iter().map(|m| (m, path.clone())).collect()
+}
+
+// ---- Express ----
+
+/// `app.get("/orders", ...handlers)`, `router.route("/orders").get(handler)`.
+/// The receiver must look like an app/router (`app`, `router`, `ordersRouter`,
+/// `server`, ...) so HTTP clients (`axios.get(url)`) are not mistaken for routes.
+pub(crate) fn express_route(file: &SourceFile, node: Node, out: &mut Vec<Document>) {
+    let source = file.source;
+    if node.kind() != "call_expression" {
+        return;
+    }
+    let Some(function) = node
+        .child_by_field_name("function")
+        .filter(|f| f.kind() == "member_expression")
+    else {
+        return;
+    };
+    let (Some(object), Some(property), Some(args)) = (
+        function.child_by_field_name("object"),
+        function.child_by_field_name("property"),
+        node.child_by_field_name("arguments"),
+    ) else {
+        return;
+    };
+    let property = node_text(source, property);
+    let Some(method) = http_method(&property).or_else(|| (property == "all").then(|| "ANY".into()))
+    else {
+        return;
+    };
+
+    let args: Vec<Node> = named_children(args).collect();
+    let (path, handlers) = match object.kind() {
+        "identifier" if is_router_name(&source[object.byte_range()]) => {
+            let Some(path) = args.first().and_then(|p| string_value(source, *p)) else {
+                return;
+            };
+            (path, &args[1..])
+        }
+        // router.route("/orders").get(...)
+        "call_expression" => {
+            let Some(path) = express_route_path(source, object) else {
+                return;
+            };
+            (path, &args[..])
+        }
+        _ => return,
+    };
+    let Some(last) = handlers.last() else {
+        return;
+    };
+    let handler = matches!(last.kind(), "identifier" | "member_expression")
+        .then(|| normalize_ws(&node_text(source, *last)));
+
+    let route = Route {
+        method,
+        path: join_path("", &path),
+        handler,
+        framework: "express",
+    };
+    out.push(route_document(file, node.byte_range(), route, None));
+}
+
+fn is_router_name(name: &str) -> bool {
+    let lower = name.to_ascii_lowercase();
+    matches!(lower.as_str(), "app" | "server")
+        || lower.ends_with("router")
+        || lower.ends_with("routes")
+        || lower.ends_with("app")
+}
+
+/// Path of a `router.route("/path")` receiver (possibly chained:
+/// `router.route("/x").get(a).post(b)`).
+fn express_route_path(source: &str, call: Node) -> Option<String> {
+    let function = call
+        .child_by_field_name("function")
+        .filter(|f| f.kind() == "member_expression")?;
+    let property = function.child_by_field_name("property")?;
+    let object = function.child_by_field_name("object")?;
+    match &source[property.byte_range()] {
+        "route" => {
+            let args = call.child_by_field_name("arguments")?;
+            named_children(args)
+                .next()
+                .and_then(|p| string_value(source, p))
+        }
+        verb if http_method(verb).is_some() || verb == "all" => express_route_path(source, object),
+        _ => None,
+    }
+}
+
+// ---- Ktor ----
+
+/// `get("/path") { ... }`, `post { ... }`, `get("/path", ::handler)` inside a
+/// Ktor routing block (`routing`/`route`/`authenticate` lambda, or an
+/// extension function on `Route`/`Routing`).
+pub(crate) fn ktor_route(file: &SourceFile, node: Node, out: &mut Vec<Document>) {
+    let source = file.source;
+    if node.kind() != "call_expression" {
+        return;
+    }
+    let Some(callee) = node
+        .named_child(0)
+        .filter(|c| c.kind() == "simple_identifier")
+    else {
+        return;
+    };
+    let Some(method) = http_method(&source[callee.byte_range()]) else {
+        return;
+    };
+    if !in_ktor_routing(source, node) {
+        return;
+    }
+
+    let args: Vec<Node> = named_children(node)
+        .filter(|c| c.kind() == "call_suffix")
+        .flat_map(named_children)
+        .filter(|c| c.kind() == "value_arguments")
+        .flat_map(named_children)
+        .filter_map(|arg| arg.named_child(0))
+        .collect();
+    let path = match args.first() {
+        Some(first) => match string_value(source, *first) {
+            Some(path) => path,
+            None => return,
+        },
+        None => String::new(),
+    };
+    let handler = args.get(1).map(|h| {
+        normalize_ws(&node_text(source, *h))
+            .trim_start_matches("::")
+            .to_string()
+    });
+    // `get("/x") { ... }`: the trailing lambda wraps the call in another one
+    let outer = node
+        .parent()
+        .filter(|p| p.kind() == "call_expression" && p.named_child(0) == Some(node))
+        .unwrap_or(node);
+
+    let prefix = ktor_prefix(source, outer);
+    let route = Route {
+        method,
+        path: join_path(&prefix.join("/"), &path),
+        handler,
+        framework: "ktor",
+    };
+    out.push(route_document(file, outer.byte_range(), route, None));
+}
+
+/// Name and first string argument of a Ktor builder call, looking through the
+/// trailing-lambda wrapper (`route("/x") { }` → `("route", Some("/x"))`).
+fn ktor_call(source: &str, node: Node) -> Option<(String, Option<String>)> {
+    if node.kind() != "call_expression" {
+        return None;
+    }
+    let first = node.named_child(0)?;
+    match first.kind() {
+        "simple_identifier" => {
+            let path = named_children(node)
+                .filter(|c| c.kind() == "call_suffix")
+                .flat_map(named_children)
+                .filter(|c| c.kind() == "value_arguments")
+                .flat_map(named_children)
+                .find_map(|arg| arg.named_child(0))
+                .and_then(|s| string_value(source, s));
+            Some((node_text(source, first), path))
+        }
+        "call_expression" => ktor_call(source, first),
+        _ => None,
+    }
+}
+
+fn in_ktor_routing(source: &str, node: Node) -> bool {
+    let mut cur = node.parent();
+    while let Some(n) = cur {
+        match n.kind() {
+            "call_expression" => {
+                if let Some((name, _)) = ktor_call(source, n)
+                    && matches!(name.as_str(), "routing" | "route" | "authenticate")
+                {
+                    return true;
+                }
+            }
+            "function_declaration" => {
+                return extension_receiver(source, n)
+                    .is_some_and(|r| matches!(r.as_str(), "Route" | "Routing"));
+            }
+            _ => {}
+        }
+        cur = n.parent();
+    }
+    false
+}
+
+/// Paths of the enclosing `route("/prefix") { }` blocks, outermost first.
+fn ktor_prefix(source: &str, node: Node) -> Vec<String> {
+    let mut prefixes = Vec::new();
+    let mut cur = node.parent();
+    while let Some(n) = cur {
+        if let Some((name, Some(path))) = ktor_call(source, n)
+            && name == "route"
+        {
+            prefixes.push(path);
+        }
+        cur = n.parent();
+    }
+    prefixes.reverse();
+    prefixes
+}
+
+// ---- Spring ----
+
+const SPRING_MAPPINGS: &[(&str, &str)] = &[
+    ("GetMapping", "GET"),
+    ("PostMapping", "POST"),
+    ("PutMapping", "PUT"),
+    ("DeleteMapping", "DELETE"),
+    ("PatchMapping", "PATCH"),
+];
+
+/// Routes of a Spring controller method; `handler` is the method's document
+/// (its annotations are already parsed).
+pub(crate) fn spring_routes(
+    file: &SourceFile,
+    method: Node,
+    handler: &Document,
+    out: &mut Vec<Document>,
+) {
+    let class_prefixes: Vec<String> = method
+        .parent()
+        .and_then(|body| body.parent())
+        .map(|class| java_parser::annotations(file.source, class))
+        .unwrap_or_default()
+        .iter()
+        .find(|a| short_name(&a.name) == "RequestMapping")
+        .map(|a| mapping_paths(a.arguments.as_deref()))
+        .unwrap_or_else(|| vec![String::new()]);
+
+    let handler_name = match &handler.parent_type {
+        Some(parent) => format!("{parent}.{}", handler.symbol_name),
+        None => handler.symbol_name.clone(),
+    };
+    for annotation in &handler.annotations {
+        for method_name in mapping_methods(annotation) {
+            for prefix in &class_prefixes {
+                for path in mapping_paths(annotation.arguments.as_deref()) {
+                    let route = Route {
+                        method: method_name.clone(),
+                        path: join_path(prefix, &path),
+                        handler: Some(handler_name.clone()),
+                        framework: "spring",
+                    };
+                    out.push(route_document(
+                        file,
+                        method.byte_range(),
+                        route,
+                        handler.doc_comment.clone(),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+fn short_name(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+/// HTTP methods of a mapping annotation; empty for other annotations.
+fn mapping_methods(annotation: &Annotation) -> Vec<String> {
+    let name = short_name(&annotation.name);
+    if let Some((_, method)) = SPRING_MAPPINGS.iter().find(|(n, _)| *n == name) {
+        return vec![method.to_string()];
+    }
+    if name != "RequestMapping" {
+        return Vec::new();
+    }
+    let methods: Vec<String> = annotation_elements(annotation.arguments.as_deref())
+        .into_iter()
+        .filter(|(key, _)| key == "method")
+        .flat_map(|(_, value)| {
+            value
+                .split([',', '{', '}'])
+                .filter_map(|m| http_method(short_name(m.trim())))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    if methods.is_empty() {
+        vec!["ANY".to_string()]
+    } else {
+        methods
+    }
+}
+
+/// Paths of a mapping annotation (`"/x"`, `value = {"/a", "/b"}`,
+/// `path = "/x"`); `[""]` when it has none.
+fn mapping_paths(arguments: Option<&str>) -> Vec<String> {
+    let paths: Vec<String> = annotation_elements(arguments)
+        .into_iter()
+        .filter(|(key, _)| key == "value" || key == "path")
+        .flat_map(|(_, value)| string_literals(&value))
+        .collect();
+    if paths.is_empty() {
+        vec![String::new()]
+    } else {
+        paths
+    }
+}
+
+/// `key = value` pairs of an annotation argument list; a bare value is `value`.
+fn annotation_elements(arguments: Option<&str>) -> Vec<(String, String)> {
+    let Some(arguments) = arguments else {
+        return Vec::new();
+    };
+    let mut parts = Vec::new();
+    let (mut depth, mut in_string, mut start) = (0i32, false, 0);
+    for (i, c) in arguments.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '{' | '(' if !in_string => depth += 1,
+            '}' | ')' if !in_string => depth -= 1,
+            ',' if !in_string && depth == 0 => {
+                parts.push(&arguments[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&arguments[start..]);
+
+    parts
+        .into_iter()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|part| match part.split_once('=') {
+            Some((key, value)) if !key.contains('"') => {
+                (key.trim().to_string(), value.trim().to_string())
+            }
+            _ => ("value".to_string(), part.to_string()),
+        })
+        .collect()
+}
+
+fn string_literals(text: &str) -> Vec<String> {
+    text.split('"')
+        .skip(1)
+        .step_by(2)
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::java_parser::JavaLanguage;
+    use crate::ingest::kotlin_parser::KotlinLanguage;
+    use crate::ingest::language::LanguageSupport;
+    use crate::ingest::rust_parser::RustLanguage;
+    use crate::ingest::ts_parser::ScriptLanguage;
+
+    fn routes(language: &dyn LanguageSupport, file_path: &str, source: &str) -> Vec<Document> {
+        let file = SourceFile {
+            repo: "orders",
+            file_path,
+            source,
+        };
+        language
+            .parse_file(&file, false)
+            .expect("parse")
+            .into_iter()
+            .filter(|d| d.kind == DocumentKind::Endpoint)
+            .collect()
+    }
+
+    fn summary(docs: &[Document]) -> Vec<(String, Option<String>)> {
+        docs.iter()
+            .map(|d| {
+                let handler = d.metadata["handler"].as_str().map(str::to_string);
+                (d.symbol_name.clone(), handler)
+            })
+            .collect()
+    }
+
+    fn pair(route: &str, handler: Option<&str>) -> (String, Option<String>) {
+        (route.to_string(), handler.map(str::to_string))
+    }
+
+    #[test]
+    fn axum_and_actix_routes() {
+        let src = r#"
+pub fn router() -> Router {
+    Router::new()
+        .route("/orders", get(handlers::list).post(create))
+        .nest("/v1", Router::new().route("/orders/{id}", get(show).delete(|| async {})))
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::scope("/admin").route("/stats", web::get().to(stats)));
+}
+
+/// Health probe.
+#[get("/health")]
+async fn health() -> impl Responder { "ok" }
+
+#[route("/items", method = "GET", method = "HEAD")]
+async fn items() -> impl Responder { "[]" }
+"#;
+        let docs = routes(&RustLanguage, "src/api.rs", src);
+        assert_eq!(
+            summary(&docs),
+            vec![
+                pair("GET /orders", Some("handlers::list")),
+                pair("POST /orders", Some("create")),
+                pair("GET /v1/orders/{id}", Some("show")),
+                pair("DELETE /v1/orders/{id}", None),
+                pair("GET /admin/stats", Some("stats")),
+                pair("GET /health", Some("health")),
+                pair("GET /items", Some("items")),
+                pair("HEAD /items", Some("items")),
+            ]
+        );
+        assert_eq!(docs[0].metadata["framework"], "axum");
+        assert_eq!(docs[0].qualified_name.as_deref(), Some("GET /orders"));
+        assert!(docs[0].code.starts_with("route(\"/orders\""));
+        assert_eq!(docs[0].line_start, 4);
+        assert_eq!(docs[4].metadata["framework"], "actix-web");
+        let health = &docs[5];
+        assert_eq!(health.doc_comment.as_deref(), Some("Health probe."));
+        assert!(health.code.starts_with("#[get(\"/health\")]"));
+    }
+
+    #[test]
+    fn express_routes() {
+        let src = r#"
+const router = express.Router();
+app.get('/orders/:id', auth, getOrder);
+router.post(`/orders`, async (req, res) => res.send(201));
+ordersRouter.route('/orders/:id').put(controller.update).delete(remove);
+axios.get('/orders', config);
+cache.get('key');
+"#;
+        let docs = routes(&ScriptLanguage::TYPESCRIPT, "src/routes.ts", src);
+        assert_eq!(
+            summary(&docs),
+            vec![
+                pair("GET /orders/:id", Some("getOrder")),
+                pair("POST /orders", None),
+                pair("DELETE /orders/:id", Some("remove")),
+                pair("PUT /orders/:id", Some("controller.update")),
+            ]
+        );
+        assert_eq!(docs[0].metadata["framework"], "express");
+    }
+
+    #[test]
+    fn ktor_routes() {
+        let src = r#"
+fun Application.module() {
+    routing {
+        route("/orders") {
+            get("{id}") {
+                call.respond(repo.get(call.parameters["id"]))
+            }
+            post {
+                call.respond(HttpStatusCode.Created)
+            }
+        }
+        get("/health", ::health)
+    }
+}
+
+fun Route.adminRoutes() {
+    delete("/admin/cache") { cache.clear() }
+}
+
+fun unrelated(map: Map<String, String>) = get("nope")
+"#;
+        let docs = routes(&KotlinLanguage, "src/main/kotlin/Routes.kt", src);
+        let mut found = summary(&docs);
+        found.sort();
+        assert_eq!(
+            found,
+            vec![
+                pair("DELETE /admin/cache", None),
+                pair("GET /health", Some("health")),
+                pair("GET /orders/{id}", None),
+                pair("POST /orders", None),
+            ]
+        );
+        let get = docs
+            .iter()
+            .find(|d| d.symbol_name == "GET /orders/{id}")
+            .unwrap();
+        assert!(get.code.starts_with("get(\"{id}\") {"));
+        assert_eq!(get.metadata["framework"], "ktor");
+    }
+
+    #[test]
+    fn spring_routes_with_class_prefix() {
+        let src = r#"package com.shop.orders;
+
+@RestController
+@RequestMapping("/orders")
+public class OrderController {
+    /** Fetches one order. */
+    @GetMapping("/{id}")
+    public Order get(@PathVariable String id) { return null; }
+
+    @RequestMapping(value = {"", "/bulk"}, method = {RequestMethod.POST, RequestMethod.PUT})
+    public void save(@RequestBody Order order) {}
+
+    @PostMapping
+    public void create() {}
+
+    public void helper() {}
+}
+"#;
+        let docs = routes(&JavaLanguage, "src/main/java/OrderController.java", src);
+        assert_eq!(
+            summary(&docs),
+            vec![
+                pair("GET /orders/{id}", Some("OrderController.get")),
+                pair("POST /orders", Some("OrderController.save")),
+                pair("POST /orders/bulk", Some("OrderController.save")),
+                pair("PUT /orders", Some("OrderController.save")),
+                pair("PUT /orders/bulk", Some("OrderController.save")),
+                pair("POST /orders", Some("OrderController.create")),
+            ]
+        );
+        assert_eq!(docs[0].doc_comment.as_deref(), Some("Fetches one order."));
+        assert_eq!(docs[0].metadata["framework"], "spring");
+    }
+
+    #[test]
+    fn path_joining() {
+        assert_eq!(join_path("/orders/", "/{id}"), "/orders/{id}");
+        assert_eq!(join_path("/orders", ""), "/orders");
+        assert_eq!(join_path("", ""), "/");
+    }
+}
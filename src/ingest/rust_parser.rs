@@ -2,51 +2,104 @@
 //!
 //! Parses a Rust source file into "Document" units using tree-sitter-rust.
 //! Documents include:
-//! - filename (the entire file as one document)
-//! - structs, enums, traits
-//! - free functions
-//! - methods (functions inside `impl` that have a `self` receiver)
+//! - filename (a summary of the file: `//!` docs, `use` declarations and
+//!   symbol signatures)
+//! - structs, enums, unions, traits, type aliases
+//! - `impl` blocks (symbol_name = the impl header, e.g. `Display for Order`)
+//! - free functions, and associated functions (functions inside `impl`/`trait`
+//!   without `self`; parent_type set)
+//! - methods (functions inside `impl`/`trait` that have a `self` receiver;
+//!   trait default methods are attributed to the trait)
+//! - trait method declarations without a body
+//! - `const`/`static` items, `macro_rules!` definitions, `mod` items
 //!
 //! For each document we record:
 //! - repo, file_path
-//! - kind: function | method | struct | enum | trait | filename
+//! - kind: see `DocumentKind::as_str`
 //! - symbol_name (e.g., function/struct name, or file basename for filename docs)
 //! - signature (best-effort; header without body for items with bodies)
 //! - doc_comment (leading `///` or `//!` lines grouped)
-//! - code (full code snippet for that node; for filename, full file text)
-//! - parent_type (for methods, the impl target type string)
+//! - code (full code snippet for that node; for filename, the summary)
+//! - parent_type (impl target type string for impl members, trait name for
+//!   trait members)
+//! - module_path (enclosing inline `mod` blocks, `::`-joined)
+//! - qualified_name (crate + file modules + module_path + parent + name, e.g.
+//!   `auth_service::client::qdrant_client::QdrantClient::new`)
 //! - line_start, line_end (1-based inclusive)
 //!
 //! Notes:
 //! - Doc comments are collected heuristically by scanning contiguous `///` / `//!`
-//!   lines directly above the item (stopping at blank/non-comment code).
-//! - Method detection: a function inside an `impl_item` with a `self_parameter`
-//!   in its parameter list.
+//!   lines directly above the item and its `#[...]` attributes (stopping at
+//!   blank/non-comment code).
+//! - `.route(...)` calls and `#[get("/path")]`-style handlers also yield
+//!   `endpoint` documents (see `routes.rs`); reqwest calls yield `http_call`
+//!   documents (see `http_calls.rs`), rdkafka / async-nats usages `topic`
+//!   documents (see `messaging.rs`).
+//! - diesel `table!` macros yield `table` documents, and string literals that
+//!   parse as SQL `sql_query` documents (see `sql.rs`).
+//! - Method detection: a function inside an `impl_item`/`trait_item` with a
+//!   `self_parameter` in its parameter list.
 //! - Parent type: we extract the full `impl <...> <Target> for <Trait>? {` header
 //!   slice between `impl` and the `{`, then normalize whitespace.
 //!
 //! This file only depends on tree-sitter and serde/thiserror; it does not perform I/O.
 
-use tree_sitter_javascript::language;
+use crate::ingest::http_calls;
+use crate::ingest::language::{LanguageSupport, SourceFile, qualified_name};
+use crate::ingest::messaging;
+use crate::ingest::routes;
+use crate::ingest::sql;
 use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::path::Path;
 use thiserror::Error;
-use tree_sitter::{Language, Node, Parser, Point, Range};
+use tree_sitter::{Language, Node, Parser, Point, Range, Tree};
 
 #[derive(Debug, Error)]
 pub enum RustParserError {
     #[error("tree-sitter parse failed")]
     ParseFailed,
+
+    /// A structured file (spec, manifest, ...) that could not be read.
+    #[error("invalid {format}: {message}")]
+    InvalidFormat {
+        format: &'static str,
+        message: String,
+    },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum DocumentKind {
     Function,
     Method,
     Struct,
     Enum,
     Trait,
+    Class,
+    Interface,
+    Object,
+    TypeAlias,
+    Impl,
+    TraitMethod,
+    Const,
+    Static,
+    Macro,
+    Module,
+    Union,
+    Record,
+    Service,
+    Rpc,
+    Message,
+    Endpoint,
+    Schema,
+    HttpCall,
+    Topic,
+    Deployment,
+    DocSection,
+    Table,
+    SqlQuery,
     Filename,
 }
 
@@ -58,6 +111,29 @@ impl DocumentKind {
             DocumentKind::Struct => "struct",
             DocumentKind::Enum => "enum",
             DocumentKind::Trait => "trait",
+            DocumentKind::Class => "class",
+            DocumentKind::Interface => "interface",
+            DocumentKind::Object => "object",
+            DocumentKind::TypeAlias => "type_alias",
+            DocumentKind::Impl => "impl",
+            DocumentKind::TraitMethod => "trait_method",
+            DocumentKind::Const => "const",
+            DocumentKind::Static => "static",
+            DocumentKind::Macro => "macro",
+            DocumentKind::Module => "module",
+            DocumentKind::Union => "union",
+            DocumentKind::Record => "record",
+            DocumentKind::Service => "service",
+            DocumentKind::Rpc => "rpc",
+            DocumentKind::Message => "message",
+            DocumentKind::Endpoint => "endpoint",
+            DocumentKind::Schema => "schema",
+            DocumentKind::HttpCall => "http_call",
+            DocumentKind::Topic => "topic",
+            DocumentKind::Deployment => "deployment",
+            DocumentKind::DocSection => "doc_section",
+            DocumentKind::Table => "table",
+            DocumentKind::SqlQuery => "sql_query",
             DocumentKind::Filename => "filename",
         }
     }
@@ -73,79 +149,91 @@ pub struct Document {
     pub doc_comment: Option<String>,
     pub code: String,
     pub parent_type: Option<String>,
+    /// Enclosing inline module path (e.g. `api::v1`), relative to the file.
+    pub module_path: Option<String>,
+    /// Fully qualified path: the file's module/package (see
+    /// `LanguageSupport::file_module`), inline modules, parent type and
+    /// name, e.g. `auth_service::client::QdrantClient::new`. Set by
+    /// `CodeParser::parse_file`.
+    pub qualified_name: Option<String>,
+    /// Decorators/annotations on the declaration, outermost first.
+    #[serde(default)]
+    pub annotations: Vec<Annotation>,
+    /// Kind-specific structured fields (e.g. an rpc's `request_type`), stored
+    /// under the `metadata` payload key.
+    #[serde(default)]
+    pub metadata: serde_json::Map<String, serde_json::Value>,
     pub line_start: u32,
     pub line_end: u32,
 }
 
-/// Primary parser type
-pub struct CodeParser {
-    parser: Parser,
+/// A decorator or annotation attached to a declaration, e.g. Python's
+/// `@app.get("/orders")` → name `app.get`, arguments `"/orders"`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Annotation {
+    /// Dotted name without the `@`.
+    pub name: String,
+    /// Argument list text without the parentheses; `None` when there is none.
+    pub arguments: Option<String>,
 }
 
+/// Tree-sitter grammars available to `CodeParser`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ParseLanguage {
     Rust,
     Kotlin,
     JavaScript,
     TypeScript,
+    Tsx,
+    Go,
+    Python,
+    Java,
 }
 
+/// Primary parser type: a tree-sitter parser for one grammar. Extraction rules
+/// come from the `LanguageSupport` passed to `parse_file`.
+pub struct CodeParser {
+    parser: Parser,
+}
 
 impl CodeParser {
     pub fn new(language: ParseLanguage) -> Result<Self, RustParserError> {
         let mut parser = Parser::new();
 
-        match language {
-            ParseLanguage::Rust => {
-                parser
-                    .set_language(&tree_sitter_rust::language().into())
-                    .map_err(|_| RustParserError::ParseFailed)?;
-            }
-            ParseLanguage::JavaScript => {
-                parser
-                    .set_language(&tree_sitter_javascript::language().into())
-                    .map_err(|_| RustParserError::ParseFailed)?;
-            }
-            ParseLanguage::TypeScript => {
-                parser
-                    .set_language(&tree_sitter_typescript::language_typescript().into())
-                    .map_err(|_| RustParserError::ParseFailed)?;
-            }
-            ParseLanguage::Kotlin => {
-                parser
-                    .set_language(&tree_sitter_kotlin::language())
-                    .map_err(|_| RustParserError::ParseFailed)?;
-            },
+        let grammar = match language {
+            ParseLanguage::Rust => tree_sitter_rust::language(),
+            ParseLanguage::JavaScript => language_from_fn(tree_sitter_javascript::LANGUAGE),
+            ParseLanguage::TypeScript => tree_sitter_typescript::language_typescript(),
+            ParseLanguage::Tsx => tree_sitter_typescript::language_tsx(),
+            ParseLanguage::Kotlin => tree_sitter_kotlin::language(),
+            ParseLanguage::Go => language_from_fn(tree_sitter_go::LANGUAGE),
+            ParseLanguage::Python => language_from_fn(tree_sitter_python::LANGUAGE),
+            ParseLanguage::Java => language_from_fn(tree_sitter_java::LANGUAGE),
         };
+        parser
+            .set_language(&grammar)
+            .map_err(|_| RustParserError::ParseFailed)?;
         Ok(Self { parser })
     }
 
-    /// Parse a single Rust file into Documents.
+    /// Parse a single file into Documents.
     ///
-    /// - `repo`: repo/collection name
-    /// - `file_path`: relative path from repo root
-    /// - `source`: full file contents
-    /// - `include_filename_doc`: whether to include a top-level "filename" document
+    /// - `language`: extraction rules applied to every node (DFS pre-order)
+    /// - `file`: repo name, path relative to the repo root, full contents
+    /// - `include_filename_doc`: whether to include a top-level "filename"
+    ///   document (a summary of the file, see `build_file_summary`)
     pub fn parse_file(
         &mut self,
-        repo: &str,
-        file_path: &str,
-        source: &str,
+        language: &(impl LanguageSupport + ?Sized),
+        file: &SourceFile,
         include_filename_doc: bool,
     ) -> Result<Vec<Document>, RustParserError> {
-        let tree = self
-            .parser
-            .parse(source, None)
-            .ok_or(RustParserError::ParseFailed)?;
+        let tree = self.parse_tree(file.source)?;
         let root = tree.root_node();
 
         let mut out = Vec::new();
 
-        if include_filename_doc {
-            out.push(self.build_filename_document(repo, file_path, source));
-        }
-
         // Walk the tree and collect items of interest.
-        let _ = root.walk();
         let mut stack: Vec<Node> = vec![root];
 
         while let Some(node) = stack.pop() {
@@ -156,162 +244,393 @@ impl CodeParser {
                 }
             }
 
-            match node.kind() {
-                // Structs, Enums, Traits
-                "struct_item" | "enum_item" | "trait_item" => {
-                    if let Some(doc) = self.extract_named_item(repo, file_path, source, node) {
-                        out.push(doc);
-                    }
-                }
+            language.visit(file, node, &mut out);
+        }
 
-                // Function items
-                "function_item" => {
-                    let parent = node.parent();
-                    let is_method = parent
-                        .as_ref()
-                        .map(|p| p.kind() == "impl_item")
-                        .unwrap_or(false)
-                        && has_self_parameter(node);
-
-                    if is_method {
-                        if let Some(doc) =
-                            self.extract_method_item(repo, file_path, source, node, parent.unwrap())
-                        {
-                            out.push(doc);
-                        }
-                    } else {
-                        if let Some(doc) = self.extract_function_item(repo, file_path, source, node)
-                        {
-                            out.push(doc);
-                        }
-                    }
-                }
+        if include_filename_doc {
+            let imports: Vec<String> = (0..root.named_child_count())
+                .filter_map(|i| root.named_child(i))
+                .filter(|ch| language.import_kinds().contains(&ch.kind()))
+                .map(|ch| node_text(file.source, ch).trim().to_string())
+                .collect();
+            let module_doc = language.module_doc(file, root);
+            out.insert(0, build_file_summary(file, module_doc, &imports, &out));
+        }
 
-                _ => {}
-            }
+        // documents named by their extractor (e.g. routes) keep their name
+        let file_module = language.file_module(file, root);
+        for doc in out.iter_mut().filter(|d| d.qualified_name.is_none()) {
+            doc.qualified_name = Some(qualified_name(
+                file_module.as_deref(),
+                doc,
+                language.path_separator(),
+            ));
         }
 
         Ok(out)
     }
 
-    fn build_filename_document(&self, repo: &str, file_path: &str, source: &str) -> Document {
-        let symbol_name = Path::new(file_path)
-            .file_name()
-            .and_then(|s| s.to_str())
-            .unwrap_or(file_path)
-            .to_string();
-        let total_lines = 1 + byte_count(source.as_bytes(), b'\n') as u32;
-
-        Document {
-            repo: repo.to_string(),
-            file_path: file_path.to_string(),
-            symbol_name,
-            kind: DocumentKind::Filename,
-            signature: None,
-            doc_comment: None,
-            code: source.to_string(),
-            parent_type: None,
-            line_start: 1,
-            line_end: total_lines,
-        }
+    /// The syntax tree of `source`, for passes that need nodes rather than
+    /// Documents (e.g. `chunker`).
+    pub(crate) fn parse_tree(&mut self, source: &str) -> Result<Tree, RustParserError> {
+        self.parser
+            .parse(source, None)
+            .ok_or(RustParserError::ParseFailed)
     }
+}
 
-    fn extract_named_item(
-        &self,
-        repo: &str,
-        file_path: &str,
-        source: &str,
-        node: Node,
-    ) -> Option<Document> {
-        // Determine kind and name child
-        let (kind, name_child_kind) = match node.kind() {
-            "struct_item" => (DocumentKind::Struct, "type_identifier"),
-            "enum_item" => (DocumentKind::Enum, "type_identifier"),
-            "trait_item" => (DocumentKind::Trait, "type_identifier"),
-            _ => return None,
+thread_local! {
+    /// Parsers already set up for a grammar, reused by `with_parser`.
+    static PARSERS: RefCell<HashMap<ParseLanguage, CodeParser>> = RefCell::new(HashMap::new());
+}
+
+/// Runs `f` with this thread's parser for `language`, created on first use,
+/// so loading a grammar is paid once per thread rather than once per file.
+/// The parser is taken out of the cache while `f` runs; a nested call for
+/// the same grammar builds a parser of its own.
+pub fn with_parser<T>(
+    language: ParseLanguage,
+    f: impl FnOnce(&mut CodeParser) -> Result<T, RustParserError>,
+) -> Result<T, RustParserError> {
+    let cached = PARSERS.with(|p| p.borrow_mut().remove(&language));
+    let mut parser = match cached {
+        Some(parser) => parser,
+        None => CodeParser::new(language)?,
+    };
+    let result = f(&mut parser);
+    PARSERS.with(|p| p.borrow_mut().insert(language, parser));
+    result
+}
+
+/// Loads a grammar shipped as a `tree-sitter-language` `LanguageFn` (grammar
+/// crates >= 0.23) into our tree-sitter 0.21 runtime. Those grammars are
+/// generated with ABI 14, which the runtime supports; `set_language` still
+/// rejects incompatible ones.
+///
+/// The runtime cannot move to 0.23+ (which takes `LanguageFn` natively):
+/// tree-sitter-kotlin 0.3.8, the latest Kotlin grammar, pins it below 0.23.
+/// Nor can Go/Python/Java stay on 0.21-era grammars: tree-sitter-javascript
+/// 0.21 pins `cc ~1.0.90`, which the 0.23 grammars' `cc 1.1` cannot share.
+/// `every_grammar_loads` checks each grammar still loads and parses.
+fn language_from_fn(language: tree_sitter_language::LanguageFn) -> Language {
+    // SAFETY: the function comes from a tree-sitter generated grammar and
+    // returns a pointer to its static `TSLanguage`.
+    unsafe { Language::from_raw(language.into_raw()() as *const tree_sitter::ffi::TSLanguage) }
+}
+
+/// Rust extraction rules.
+pub struct RustLanguage;
+
+impl LanguageSupport for RustLanguage {
+    fn name(&self) -> &'static str {
+        "rust"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["rs"]
+    }
+
+    fn grammar(&self) -> Option<ParseLanguage> {
+        Some(ParseLanguage::Rust)
+    }
+
+    fn visit(&self, file: &SourceFile, node: Node, out: &mut Vec<Document>) {
+        let doc = match node.kind() {
+            // Named items: types, consts/statics, macros, modules
+            "struct_item" | "enum_item" | "trait_item" | "union_item" | "type_item"
+            | "const_item" | "static_item" | "macro_definition" | "mod_item" => {
+                extract_named_item(file, node)
+            }
+
+            "impl_item" => extract_impl_item(file, node),
+
+            // Function items (free, associated, methods, trait default methods)
+            "function_item" => extract_function_item(file, node),
+
+            // Trait method declarations without a body
+            "function_signature_item" => extract_trait_method_decl(file, node),
+
+            // `.route(...)` calls (axum, actix-web), reqwest calls, broker clients
+            "call_expression" => {
+                routes::rust_route_call(file, node, out);
+                http_calls::rust_http_call(file, node, out);
+                messaging::rust_topic_usage(file, node, out);
+                None
+            }
+
+            // diesel `table!` schemas, SQL in string literals (`sqlx::query!`)
+            "macro_invocation" => {
+                sql::diesel_tables(file, node, out);
+                None
+            }
+            "string_literal" | "raw_string_literal" => {
+                sql::sql_query(file, node, out);
+                None
+            }
+
+            _ => None,
+        };
+        let Some(doc) = doc else {
+            return;
         };
+        // `#[get("/path")]` handlers also yield their routes
+        let mut routes = Vec::new();
+        if node.kind() == "function_item" {
+            routes::rust_route_attributes(file, node, &doc, &mut routes);
+        }
+        out.push(doc);
+        out.extend(routes);
+    }
 
-        let name = child_text_by_kind(source, node, name_child_kind)?;
-        let (line_start, line_end) = lines_of(&node);
-        let signature = extract_item_signature(source, node);
-        let doc_comment = leading_doc_comment_block_above(source, node);
-        let code = slice_source(source, node.byte_range());
-
-        Some(Document {
-            repo: repo.to_string(),
-            file_path: file_path.to_string(),
-            symbol_name: name,
-            kind,
-            signature,
-            doc_comment,
-            code,
-            parent_type: None,
-            line_start,
-            line_end,
-        })
+    fn file_module(&self, file: &SourceFile, _root: Node) -> Option<String> {
+        Some(rust_file_module(file.repo, file.file_path))
     }
 
-    fn extract_function_item(
-        &self,
-        repo: &str,
-        file_path: &str,
-        source: &str,
-        node: Node,
-    ) -> Option<Document> {
-        // Free function
-        let name = child_text_by_kind(source, node, "identifier")?;
-        let (line_start, line_end) = lines_of(&node);
-        let signature = extract_item_signature(source, node);
-        let doc_comment = leading_doc_comment_block_above(source, node);
-        let code = slice_source(source, node.byte_range());
-
-        Some(Document {
-            repo: repo.to_string(),
-            file_path: file_path.to_string(),
-            symbol_name: name,
-            kind: DocumentKind::Function,
-            signature,
-            doc_comment,
-            code,
-            parent_type: None,
-            line_start,
-            line_end,
-        })
+    fn import_kinds(&self) -> &'static [&'static str] {
+        &["use_declaration", "extern_crate_declaration"]
     }
 
-    fn extract_method_item(
-        &self,
-        repo: &str,
-        file_path: &str,
-        source: &str,
-        func_node: Node,
-        impl_node: Node,
-    ) -> Option<Document> {
-        let name = child_text_by_kind(source, func_node, "identifier")?;
-        let (line_start, line_end) = lines_of(&func_node);
-        let signature = extract_item_signature(source, func_node);
-        let doc_comment = leading_doc_comment_block_above(source, func_node);
-        let code = slice_source(source, func_node.byte_range());
-
-        let parent_type = extract_impl_target_type(source, impl_node);
-
-        Some(Document {
-            repo: repo.to_string(),
-            file_path: file_path.to_string(),
-            symbol_name: name,
-            kind: DocumentKind::Method,
-            signature,
-            doc_comment,
-            code,
-            parent_type,
-            line_start,
-            line_end,
+    /// The `//!` lines opening the file.
+    fn module_doc(&self, file: &SourceFile, _root: Node) -> Option<String> {
+        let lines: Vec<&str> = file
+            .source
+            .lines()
+            .map(str::trim)
+            .skip_while(|l| l.is_empty())
+            .map_while(|l| l.strip_prefix("//!"))
+            .map(|l| l.strip_prefix(' ').unwrap_or(l))
+            .collect();
+        let joined = lines.join("\n").trim_matches('\n').to_string();
+        (!joined.is_empty()).then_some(joined)
+    }
+}
+
+/// Crate + module path of a Rust file from its location:
+/// - `src/client/qdrant_client.rs` in repo `auth-service` →
+///   `auth_service::client::qdrant_client`
+/// - `lib.rs`/`main.rs`/`mod.rs` name their directory's module
+/// - in workspaces the crate is the directory holding `src`
+///   (`crates/billing/src/api.rs` → `billing::api`)
+/// - files outside `src` keep their directories (`tests/api.rs` → `<repo>::tests::api`)
+fn rust_file_module(repo: &str, file_path: &str) -> String {
+    let mut segments: Vec<&str> = file_path.split(['/', '\\']).collect();
+    if let Some(last) = segments.last_mut() {
+        *last = last.strip_suffix(".rs").unwrap_or(last);
+    }
+    let (krate, modules) = match segments.iter().rposition(|s| *s == "src") {
+        Some(0) => (repo, &segments[1..]),
+        Some(i) => (segments[i - 1], &segments[i + 1..]),
+        None => (repo, &segments[..]),
+    };
+
+    let mut parts = vec![krate.replace('-', "_")];
+    let n = modules.len();
+    for (i, m) in modules.iter().enumerate() {
+        if i + 1 == n && matches!(*m, "lib" | "main" | "mod") {
+            continue;
+        }
+        parts.push(m.to_string());
+    }
+    parts.join("::")
+}
+
+/// File-level summary standing in for the whole file: the module doc comment
+/// (`doc_comment`), then the imports and one line per symbol of `docs` (its
+/// signature, members indented under their parent) as code. Usage records
+/// (calls, topics, queries) are left out; lines span the whole file.
+pub fn build_file_summary(
+    file: &SourceFile,
+    module_doc: Option<String>,
+    imports: &[String],
+    docs: &[Document],
+) -> Document {
+    let symbol_name = Path::new(file.file_path)
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or(file.file_path)
+        .to_string();
+    let total_lines = 1 + byte_count(file.source.as_bytes(), b'\n') as u32;
+
+    let symbols: Vec<String> = docs
+        .iter()
+        .filter(|d| {
+            !matches!(
+                d.kind,
+                DocumentKind::Filename
+                    | DocumentKind::HttpCall
+                    | DocumentKind::Topic
+                    | DocumentKind::SqlQuery
+            )
+        })
+        .map(|d| {
+            let indent = if d.parent_type.is_some() { "    " } else { "" };
+            let line = match &d.signature {
+                Some(sig) => normalize_ws(sig),
+                None => format!("{} {}", d.kind.as_str(), d.symbol_name),
+            };
+            format!("{indent}{line}")
         })
+        .collect();
+    let code = [imports.join("\n"), symbols.join("\n")]
+        .into_iter()
+        .filter(|block| !block.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    Document {
+        repo: file.repo.to_string(),
+        file_path: file.file_path.to_string(),
+        symbol_name,
+        kind: DocumentKind::Filename,
+        signature: None,
+        doc_comment: module_doc,
+        code,
+        parent_type: None,
+        module_path: None,
+        qualified_name: None,
+        annotations: Vec::new(),
+        metadata: Default::default(),
+        line_start: 1,
+        line_end: total_lines,
+    }
+}
+
+/// `docs` led by their file summary when `include_filename_doc` is set, for
+/// formats without imports or module docs.
+pub fn with_file_summary(
+    file: &SourceFile,
+    include_filename_doc: bool,
+    mut docs: Vec<Document>,
+) -> Vec<Document> {
+    if include_filename_doc {
+        let summary = build_file_summary(file, None, &[], &docs);
+        docs.insert(0, summary);
+    }
+    docs
+}
+
+fn extract_named_item(file: &SourceFile, node: Node) -> Option<Document> {
+    let source = file.source;
+    // Determine kind and name child
+    let (kind, name_child_kind) = match node.kind() {
+        "struct_item" => (DocumentKind::Struct, "type_identifier"),
+        "enum_item" => (DocumentKind::Enum, "type_identifier"),
+        "trait_item" => (DocumentKind::Trait, "type_identifier"),
+        "union_item" => (DocumentKind::Union, "type_identifier"),
+        "type_item" => (DocumentKind::TypeAlias, "type_identifier"),
+        "const_item" => (DocumentKind::Const, "identifier"),
+        "static_item" => (DocumentKind::Static, "identifier"),
+        "macro_definition" => (DocumentKind::Macro, "identifier"),
+        "mod_item" => (DocumentKind::Module, "identifier"),
+        _ => return None,
+    };
+
+    let name = child_text_by_kind(source, node, name_child_kind)?;
+    // associated consts/types belong to their impl/trait
+    let parent_type = enclosing_container(node).and_then(|c| container_name(source, c));
+    // a macro body is token soup; its "signature" is just the header
+    let signature = if kind == DocumentKind::Macro {
+        Some(format!("macro_rules! {name}"))
+    } else {
+        extract_item_signature(source, node)
+    };
+
+    Some(build_item_document(
+        file,
+        node,
+        name,
+        kind,
+        signature,
+        parent_type,
+    ))
+}
+
+fn extract_impl_item(file: &SourceFile, node: Node) -> Option<Document> {
+    let target = extract_impl_target_type(file.source, node)?;
+    let signature = extract_item_signature(file.source, node);
+    Some(build_item_document(
+        file,
+        node,
+        target,
+        DocumentKind::Impl,
+        signature,
+        None,
+    ))
+}
+
+/// Free functions, associated functions (attributed to the impl target),
+/// methods and trait default methods (attributed to the trait).
+fn extract_function_item(file: &SourceFile, node: Node) -> Option<Document> {
+    let source = file.source;
+    let name = child_text_by_kind(source, node, "identifier")?;
+    let container = enclosing_container(node);
+    let kind = if container.is_some() && has_self_parameter(node) {
+        DocumentKind::Method
+    } else {
+        DocumentKind::Function
+    };
+    let parent_type = container.and_then(|c| container_name(source, c));
+    let signature = extract_item_signature(source, node);
+
+    Some(build_item_document(
+        file,
+        node,
+        name,
+        kind,
+        signature,
+        parent_type,
+    ))
+}
+
+fn extract_trait_method_decl(file: &SourceFile, node: Node) -> Option<Document> {
+    let source = file.source;
+    // `function_signature_item` also appears in `extern` blocks; only trait
+    // declarations are of interest
+    let container = enclosing_container(node).filter(|c| c.kind() == "trait_item")?;
+    let name = child_text_by_kind(source, node, "identifier")?;
+    let signature = extract_item_signature(source, node);
+
+    Some(build_item_document(
+        file,
+        node,
+        name,
+        DocumentKind::TraitMethod,
+        signature,
+        container_name(source, container),
+    ))
+}
+
+fn build_item_document(
+    file: &SourceFile,
+    node: Node,
+    symbol_name: String,
+    kind: DocumentKind,
+    signature: Option<String>,
+    parent_type: Option<String>,
+) -> Document {
+    let source = file.source;
+    let (line_start, line_end) = lines_of(&node);
+
+    Document {
+        repo: file.repo.to_string(),
+        file_path: file.file_path.to_string(),
+        symbol_name,
+        kind,
+        signature,
+        doc_comment: leading_doc_comment_block_above(source, first_outer_attribute(node)),
+        code: node_text(source, node),
+        parent_type,
+        module_path: enclosing_module_path(source, node),
+        qualified_name: None,
+        annotations: Vec::new(),
+        metadata: Default::default(),
+        line_start,
+        line_end,
     }
 }
 
 /// ---- helpers ----
-fn lines_of(node: &Node) -> (u32, u32) {
+pub(crate) fn lines_of(node: &Node) -> (u32, u32) {
     let Range {
         start_point: Point { row: sr, .. },
         end_point: Point { row: er, .. },
@@ -320,38 +639,88 @@ fn lines_of(node: &Node) -> (u32, u32) {
     (sr as u32 + 1, er as u32 + 1)
 }
 
-fn slice_source(source: &str, range: std::ops::Range<usize>) -> String {
+pub(crate) fn slice_source(source: &str, range: std::ops::Range<usize>) -> String {
     source.get(range).map(|s| s.to_string()).unwrap_or_default()
 }
 
+/// Returns the source text of `node`.
+pub(crate) fn node_text(source: &str, node: Node) -> String {
+    slice_source(source, node.byte_range())
+}
+
+/// Returns the source text of the `field` child of `node`, empty without one.
+pub(crate) fn field_text(source: &str, node: Node, field: &str) -> String {
+    node.child_by_field_name(field)
+        .map(|n| node_text(source, n))
+        .unwrap_or_default()
+}
+
 /// Returns the text of a child node with the given kind, if it exists.
-fn child_text_by_kind(source: &str, node: Node, kind: &str) -> Option<String> {
+pub(crate) fn child_text_by_kind(source: &str, node: Node, kind: &str) -> Option<String> {
     for i in 0..node.child_count() {
-        if let Some(ch) = node.child(i) {
-            if ch.kind() == kind {
-                return Some(slice_source(source, ch.byte_range()));
-            }
+        if let Some(ch) = node.child(i)
+            && ch.kind() == kind
+        {
+            return Some(node_text(source, ch));
         }
     }
     None
 }
 
+/// `::`-joined names of the inline `mod` blocks enclosing `node`, outermost first.
+fn enclosing_module_path(source: &str, node: Node) -> Option<String> {
+    let mut mods = Vec::new();
+    let mut cur = node.parent();
+    while let Some(n) = cur {
+        if n.kind() == "mod_item"
+            && let Some(name) = child_text_by_kind(source, n, "identifier")
+        {
+            mods.push(name);
+        }
+        cur = n.parent();
+    }
+    if mods.is_empty() {
+        return None;
+    }
+    mods.reverse();
+    Some(mods.join("::"))
+}
+
+/// Returns the `impl_item` / `trait_item` an item belongs to. Items inside
+/// them hang off a `declaration_list` body, not the impl/trait node itself.
+fn enclosing_container(node: Node) -> Option<Node> {
+    let body = node.parent()?;
+    if body.kind() != "declaration_list" {
+        return None;
+    }
+    body.parent()
+        .filter(|p| matches!(p.kind(), "impl_item" | "trait_item"))
+}
+
+/// parent_type for members of an impl (its target) or trait (its name).
+fn container_name(source: &str, container: Node) -> Option<String> {
+    match container.kind() {
+        "impl_item" => extract_impl_target_type(source, container),
+        _ => child_text_by_kind(source, container, "type_identifier"),
+    }
+}
+
 /// Detects if a function_item has a self receiver parameter.
 fn has_self_parameter(func_node: Node) -> bool {
     // Look for a "parameters" child that contains a "self_parameter" descendant.
     for i in 0..func_node.child_count() {
-        if let Some(ch) = func_node.child(i) {
-            if ch.kind() == "parameters" {
-                // descend to find "self_parameter"
-                let mut stack = vec![ch];
-                while let Some(n) = stack.pop() {
-                    if n.kind() == "self_parameter" {
-                        return true;
-                    }
-                    for j in 0..n.child_count() {
-                        if let Some(c) = n.child(j) {
-                            stack.push(c);
-                        }
+        if let Some(ch) = func_node.child(i)
+            && ch.kind() == "parameters"
+        {
+            // descend to find "self_parameter"
+            let mut stack = vec![ch];
+            while let Some(n) = stack.pop() {
+                if n.kind() == "self_parameter" {
+                    return true;
+                }
+                for j in 0..n.child_count() {
+                    if let Some(c) = n.child(j) {
+                        stack.push(c);
                     }
                 }
             }
@@ -387,7 +756,7 @@ fn extract_item_signature(source: &str, node: Node) -> Option<String> {
     // Also handle a trailing semicolon items (e.g., trait fns w/o body)
     // If the node has a ';' token as last child, include up to it.
     // Otherwise, use cutoff as determined above.
-    let text_full = slice_source(source, node.byte_range());
+    let text_full = node_text(source, node);
     if cutoff < node.end_byte() {
         let head = &source[node.start_byte()..cutoff];
         return Some(head.trim().to_string());
@@ -409,7 +778,7 @@ fn extract_item_signature(source: &str, node: Node) -> Option<String> {
 /// - `impl<T> Foo<T> { ... }`
 /// - `impl Trait for Type { ... }` (we still return the full header piece)
 fn extract_impl_target_type(source: &str, impl_node: Node) -> Option<String> {
-    let text = slice_source(source, impl_node.byte_range());
+    let text = node_text(source, impl_node);
     // Find "impl" and the first "{" after it.
     let impl_pos = text.find("impl")?;
     let brace_pos = text.find('{')?;
@@ -421,6 +790,18 @@ fn extract_impl_target_type(source: &str, impl_node: Node) -> Option<String> {
     Some(norm)
 }
 
+/// The first of the `#[...]` attributes directly above `node` (they are
+/// siblings, not children), or `node` itself. Doc comments sit above them.
+fn first_outer_attribute(node: Node) -> Node {
+    let mut first = node;
+    while let Some(prev) = first.prev_named_sibling()
+        && prev.kind() == "attribute_item"
+    {
+        first = prev;
+    }
+    first
+}
+
 /// Collects leading doc comment lines directly above the node:
 /// consecutive lines starting with "///" or "//!", stopping at the first
 /// non-comment, non-blank line.
@@ -433,10 +814,8 @@ fn leading_doc_comment_block_above(source: &str, node: Node) -> Option<String> {
     }
     // Drop the trailing partial line if not ending with '\n'
     if !prefix.ends_with('\n') {
-        if let Some(last) = lines.pop() {
-            // last is a partial prefix of the line where node begins; ignore
-            let _ = last;
-        }
+        // last is a partial prefix of the line where node begins; ignore
+        lines.pop();
     }
 
     // Walk upward accumulating contiguous `///` or `//!` lines (trim trailing '\n')
@@ -487,30 +866,134 @@ fn leading_doc_comment_block_above(source: &str, node: Node) -> Option<String> {
         None
     } else {
         collected_rev.reverse();
-        let joined = collected_rev.join("\n").trim_end().to_string();
+        let joined = collected_rev.join("\n").trim_matches('\n').to_string();
         Some(joined)
     }
 }
 
-fn normalize_ws(s: &str) -> String {
+/// Returns the `/** ... */` block comment ending right above `node` (only
+/// whitespace in between), with the comment markers and leading `*` stripped.
+/// Used for KDoc/JSDoc/Javadoc-style languages.
+pub(crate) fn block_doc_comment_above(source: &str, node: Node) -> Option<String> {
+    let prefix = source[..node.start_byte()].trim_end();
+    let body_end = prefix.strip_suffix("*/")?;
+    let start = body_end.rfind("/**")?;
+    let body = &body_end[start + 3..];
+    // the closing `*/` belongs to a plain `/* */` comment, not this doc block
+    if body.contains("*/") {
+        return None;
+    }
+
+    let lines: Vec<&str> = body
+        .lines()
+        .map(|l| {
+            let l = l.trim();
+            l.strip_prefix('*').map(str::trim_start).unwrap_or(l)
+        })
+        .collect();
+    let joined = lines.join("\n").trim_matches('\n').to_string();
+    if joined.is_empty() {
+        None
+    } else {
+        Some(joined)
+    }
+}
+
+/// Returns the run of `marker` line comments (e.g. `//`, `#`) ending on the
+/// line right above byte offset `start`, markers and one following space
+/// stripped. A blank line ends the run. Used for languages documented with
+/// plain line comments.
+pub(crate) fn line_comment_block_above(source: &str, start: usize, marker: &str) -> Option<String> {
+    let prefix = &source[..start];
+    // text before the node on its own line must be indentation only
+    let line_start = prefix.rfind('\n').map_or(0, |i| i + 1);
+    if !prefix[line_start..].trim().is_empty() {
+        return None;
+    }
+
+    let mut collected_rev: Vec<&str> = Vec::new();
+    for line in prefix[..line_start].lines().rev() {
+        let Some(content) = line.trim_start().strip_prefix(marker) else {
+            break;
+        };
+        collected_rev.push(content.strip_prefix(' ').unwrap_or(content).trim_end());
+    }
+    if collected_rev.is_empty() {
+        return None;
+    }
+    collected_rev.reverse();
+    let joined = collected_rev.join("\n").trim_matches('\n').to_string();
+    (!joined.is_empty()).then_some(joined)
+}
+
+pub(crate) fn normalize_ws(s: &str) -> String {
     s.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
-fn byte_count(haystack: &[u8], needle: u8) -> usize {
+pub(crate) fn byte_count(haystack: &[u8], needle: u8) -> usize {
     haystack.iter().filter(|&&b| b == needle).count()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::cmp::PartialEq;
 
-    impl PartialEq for DocumentKind {
-        fn eq(&self, other: &Self) -> bool {
-            todo!()
+    #[test]
+    fn every_grammar_loads() {
+        let samples = [
+            (ParseLanguage::Rust, "fn f() {}"),
+            (ParseLanguage::Kotlin, "fun f() {}"),
+            (ParseLanguage::JavaScript, "function f() {}"),
+            (ParseLanguage::TypeScript, "function f(): void {}"),
+            (ParseLanguage::Tsx, "const a = <div />;"),
+            (ParseLanguage::Go, "package main\n\nfunc f() {}\n"),
+            (ParseLanguage::Python, "def f():\n    pass\n"),
+            (ParseLanguage::Java, "class A { void f() {} }"),
+        ];
+        for (language, source) in samples {
+            let tree = CodeParser::new(language)
+                .and_then(|mut p| p.parse_tree(source))
+                .unwrap_or_else(|e| panic!("{language:?}: {e}"));
+            let root = tree.root_node();
+            assert!(!root.has_error(), "{language:?}: {}", root.to_sexp());
+            assert!(root.named_child_count() > 0, "{language:?}");
         }
     }
 
+    #[test]
+    fn with_parser_reuses_one_parser_per_grammar() {
+        let parse = |source: &str| {
+            with_parser(ParseLanguage::Rust, |p| {
+                // A nested call must not clash with the borrowed parser.
+                with_parser(ParseLanguage::Rust, |inner| inner.parse_tree("fn g() {}"))?;
+                p.parse_tree(source)
+            })
+        };
+        assert!(!parse("fn f() {}").unwrap().root_node().has_error());
+        assert!(!parse("struct S;").unwrap().root_node().has_error());
+        PARSERS.with(|p| {
+            let cached = p.borrow();
+            assert_eq!(cached.len(), 1);
+            assert!(cached.contains_key(&ParseLanguage::Rust));
+        });
+    }
+
+    fn parse(
+        repo: &str,
+        file_path: &str,
+        source: &str,
+        include_filename_doc: bool,
+    ) -> Vec<Document> {
+        let file = SourceFile {
+            repo,
+            file_path,
+            source,
+        };
+        RustLanguage
+            .parse_file(&file, include_filename_doc)
+            .expect("parse")
+    }
+
     #[test]
     fn test_leading_doc_comment() {
         let src = r#"
@@ -518,10 +1001,7 @@ mod tests {
 /// more
 fn foo() {}
 "#;
-        let mut p = CodeParser::new(ParseLanguage::Rust).unwrap();
-        let docs = p
-            .parse_file("repo", "src/lib.rs", src, false)
-            .expect("parse");
+        let docs = parse("repo", "src/lib.rs", src, false);
         let f = docs
             .iter()
             .find(|d| d.kind == DocumentKind::Function)
@@ -543,8 +1023,7 @@ impl A {
 
 fn baz() {}
 "#;
-        let mut p = CodeParser::new(ParseLanguage::Rust).unwrap();
-        let docs = p.parse_file("repo", "a.rs", src, false).expect("parse");
+        let docs = parse("repo", "a.rs", src, false);
 
         let meth = docs
             .iter()
@@ -558,16 +1037,186 @@ fn baz() {}
 
     #[test]
     fn test_filename_document() {
-        let src = "fn x() {}\n";
-        let mut p = CodeParser::new(ParseLanguage::Rust).unwrap();
-        let docs = p.parse_file("r", "src/main.rs", src, true).expect("ok");
-        let file = docs
-            .iter()
-            .find(|d| matches!(d.kind, DocumentKind::Filename))
-            .unwrap();
+        let src = r#"//! Order storage.
+//!
+//! Backed by Postgres.
+
+use std::fmt;
+use crate::db::{Pool, Row};
+
+pub struct Order { id: u64 }
+
+impl fmt::Display for Order {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.id)
+    }
+}
+
+pub fn load(pool: &Pool, id: u64) -> Option<Order> {
+    let row: Row = pool.get(id)?;
+    Some(Order { id: row.id })
+}
+"#;
+        let docs = parse("r", "src/main.rs", src, true);
+        let file = &docs[0];
+        assert_eq!(file.kind, DocumentKind::Filename);
         assert_eq!(file.symbol_name, "main.rs");
         assert_eq!(file.line_start, 1);
-        assert_eq!(file.line_end, 2);
-        assert_eq!(file.code, src);
+        assert_eq!(file.line_end, 20);
+        assert_eq!(
+            file.doc_comment.as_deref(),
+            Some("Order storage.\n\nBacked by Postgres.")
+        );
+        assert_eq!(
+            file.code,
+            "use std::fmt;\n\
+             use crate::db::{Pool, Row};\n\
+             \n\
+             pub struct Order\n\
+             impl fmt::Display for Order\n    \
+             fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result\n\
+             pub fn load(pool: &Pool, id: u64) -> Option<Order>"
+        );
+    }
+
+    #[test]
+    fn test_inline_module_path() {
+        let src = r#"
+mod api {
+    pub mod v1 {
+        pub fn list() {}
+    }
+}
+fn list() {}
+"#;
+        let docs = parse("r", "src/lib.rs", src, false);
+        let paths: Vec<Option<&str>> = docs
+            .iter()
+            .filter(|d| d.symbol_name == "list")
+            .map(|d| d.module_path.as_deref())
+            .collect();
+        assert_eq!(paths, vec![Some("api::v1"), None]);
+    }
+
+    #[test]
+    fn test_associated_functions_and_trait_members() {
+        let src = r#"
+pub struct QdrantClient;
+
+impl QdrantClient {
+    pub fn new() -> Self { QdrantClient }
+}
+
+pub trait Store {
+    fn get(&self, id: u64) -> Option<String>;
+    fn exists(&self, id: u64) -> bool { self.get(id).is_some() }
+}
+
+impl Store for QdrantClient {
+    fn get(&self, _id: u64) -> Option<String> { None }
+}
+"#;
+        let docs = parse("r", "src/lib.rs", src, false);
+
+        let new = docs.iter().find(|d| d.symbol_name == "new").unwrap();
+        assert_eq!(new.kind, DocumentKind::Function);
+        assert_eq!(new.parent_type.as_deref(), Some("QdrantClient"));
+
+        let decl = docs
+            .iter()
+            .find(|d| d.kind == DocumentKind::TraitMethod)
+            .unwrap();
+        assert_eq!(decl.symbol_name, "get");
+        assert_eq!(decl.parent_type.as_deref(), Some("Store"));
+
+        let default = docs.iter().find(|d| d.symbol_name == "exists").unwrap();
+        assert_eq!(default.kind, DocumentKind::Method);
+        assert_eq!(default.parent_type.as_deref(), Some("Store"));
+
+        let impls: Vec<&str> = docs
+            .iter()
+            .filter(|d| d.kind == DocumentKind::Impl)
+            .map(|d| d.symbol_name.as_str())
+            .collect();
+        assert_eq!(impls, vec!["QdrantClient", "Store for QdrantClient"]);
+    }
+
+    #[test]
+    fn test_other_item_kinds() {
+        let src = r#"
+/// Batch size.
+pub const BATCH: usize = 64;
+static NAME: &str = "svc";
+pub type Result<T> = std::result::Result<T, Error>;
+macro_rules! point { ($x:expr) => { $x }; }
+mod inner {}
+union Bits { i: u32, f: f32 }
+"#;
+        let docs = parse("r", "src/lib.rs", src, false);
+        let kind = |name: &str| {
+            docs.iter()
+                .find(|d| d.symbol_name == name)
+                .unwrap()
+                .kind
+                .clone()
+        };
+        assert_eq!(kind("BATCH"), DocumentKind::Const);
+        assert_eq!(kind("NAME"), DocumentKind::Static);
+        assert_eq!(kind("Result"), DocumentKind::TypeAlias);
+        assert_eq!(kind("point"), DocumentKind::Macro);
+        assert_eq!(kind("inner"), DocumentKind::Module);
+        assert_eq!(kind("Bits"), DocumentKind::Union);
+
+        let batch = docs.iter().find(|d| d.symbol_name == "BATCH").unwrap();
+        assert_eq!(batch.doc_comment.as_deref(), Some("Batch size."));
+        let mac = docs.iter().find(|d| d.symbol_name == "point").unwrap();
+        assert_eq!(mac.signature.as_deref(), Some("macro_rules! point"));
+    }
+
+    #[test]
+    fn test_qualified_names() {
+        let src = r#"
+pub struct QdrantClient;
+impl QdrantClient {
+    pub fn new() -> Self { QdrantClient }
+}
+mod retry {
+    pub fn backoff() {}
+}
+"#;
+        let docs = parse("auth-service", "src/client/qdrant_client.rs", src, true);
+        let qualified = |name: &str| {
+            docs.iter()
+                .find(|d| d.symbol_name == name)
+                .and_then(|d| d.qualified_name.clone())
+                .unwrap()
+        };
+        assert_eq!(
+            qualified("new"),
+            "auth_service::client::qdrant_client::QdrantClient::new"
+        );
+        assert_eq!(
+            qualified("backoff"),
+            "auth_service::client::qdrant_client::retry::backoff"
+        );
+        assert_eq!(
+            qualified("QdrantClient"),
+            "auth_service::client::qdrant_client::QdrantClient"
+        );
+        assert_eq!(
+            qualified("qdrant_client.rs"),
+            "auth_service::client::qdrant_client"
+        );
+    }
+
+    #[test]
+    fn test_rust_file_module() {
+        assert_eq!(rust_file_module("svc", "src/lib.rs"), "svc");
+        assert_eq!(rust_file_module("svc", "src/api/mod.rs"), "svc::api");
+        assert_eq!(
+            rust_file_module("svc", "crates/billing-core/src/ledger.rs"),
+            "billing_core::ledger"
+        );
+        assert_eq!(rust_file_module("svc", "tests/api.rs"), "svc::tests::api");
     }
 }
@@ -0,0 +1,363 @@
+//! spring_config_parser.rs
+//!
+//! Spring Boot configuration support for the language registry. Claims
+//! `application*.{yml,yaml,properties}` and `bootstrap*` files and overrides
+//! `parse_file` (configuration is data, not code).
+//!
+//! Documents include one `topic` per destination of a Spring Cloud Stream
+//! binding (`spring.cloud.stream.bindings.<binding>.destination`, comma
+//! separated destinations allowed) and for `spring.kafka.template.default-topic`.
+//! The role comes from the binding name (`orderCreated-in-0` / `input`
+//! subscribe, `-out-0` / `output` publish), the group from `.group`, the broker
+//! from `.binder` or the binder configured under `spring.cloud.stream.<binder>`;
+//! the metadata is that of code usages (see `messaging.rs`), `caller` being
+//! the binding name.
+//!
+//! YAML keys may be nested or dotted (`spring.cloud.stream: ...`); multi-document
+//! files (`---`) are read document by document. code is the binding's block;
+//! line ranges are best-effort (1..=1 when not found).
+
+use crate::ingest::language::{LanguageSupport, SourceFile, file_name_lower};
+use crate::ingest::messaging::{Role, Usage, topic_document};
+use crate::ingest::rust_parser::{Document, RustParserError, with_file_summary};
+use serde::Deserialize;
+use serde_norway::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const BINDINGS: &str = "spring.cloud.stream.bindings.";
+
+pub struct SpringConfigLanguage;
+
+impl LanguageSupport for SpringConfigLanguage {
+    fn name(&self) -> &'static str {
+        "spring_config"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["yml", "yaml", "properties"]
+    }
+
+    /// Only Spring Boot config files; other YAML is left alone.
+    fn matches(&self, path: &Path) -> bool {
+        let name = file_name_lower(path);
+        self.has_extension(path)
+            && (name.starts_with("application") || name.starts_with("bootstrap"))
+    }
+
+    fn parse_file(
+        &self,
+        file: &SourceFile,
+        include_filename_doc: bool,
+    ) -> Result<Vec<Document>, RustParserError> {
+        let properties = if file.file_path.ends_with(".properties") {
+            parse_properties(file.source)
+        } else {
+            flatten_yaml(file.source)?
+        };
+
+        let mut out = Vec::new();
+        let lines: Vec<&str> = file.source.lines().collect();
+        for (binding, usage) in stream_usages(&properties) {
+            let (start, end) = binding_lines(&lines, &binding);
+            let code = lines
+                .get(start - 1..end)
+                .map(|block| block.join("\n"))
+                .unwrap_or_default();
+            out.push(topic_document(
+                file,
+                code,
+                (start as u32, end as u32),
+                usage,
+                Some(binding),
+            ));
+        }
+        Ok(with_file_summary(file, include_filename_doc, out))
+    }
+}
+
+/// `key=value` / `key: value` lines; comments (`#`, `!`) skipped.
+fn parse_properties(source: &str) -> BTreeMap<String, String> {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with(['#', '!']))
+        .filter_map(|l| {
+            let at = l.find(['=', ':'])?;
+            Some((l[..at].trim().to_string(), l[at + 1..].trim().to_string()))
+        })
+        .collect()
+}
+
+/// Every scalar of every YAML document, under its dotted key.
+fn flatten_yaml(source: &str) -> Result<BTreeMap<String, String>, RustParserError> {
+    let mut out = BTreeMap::new();
+    for document in serde_norway::Deserializer::from_str(source) {
+        let value = Value::deserialize(document).map_err(|e| RustParserError::InvalidFormat {
+            format: "Spring config",
+            message: e.to_string(),
+        })?;
+        flatten("", &value, &mut out);
+    }
+    Ok(out)
+}
+
+fn flatten(prefix: &str, value: &Value, out: &mut BTreeMap<String, String>) {
+    match value {
+        Value::Mapping(map) => {
+            for (key, child) in map {
+                let key = match key {
+                    Value::String(s) => s.clone(),
+                    other => serde_norway::to_string(other)
+                        .unwrap_or_default()
+                        .trim()
+                        .to_string(),
+                };
+                let path = if prefix.is_empty() {
+                    key
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten(&path, child, out);
+            }
+        }
+        Value::String(s) => {
+            out.insert(prefix.to_string(), s.clone());
+        }
+        Value::Number(n) => {
+            out.insert(prefix.to_string(), n.to_string());
+        }
+        Value::Bool(b) => {
+            out.insert(prefix.to_string(), b.to_string());
+        }
+        _ => {}
+    }
+}
+
+/// Topic usages declared by the configuration, with their binding name.
+fn stream_usages(properties: &BTreeMap<String, String>) -> Vec<(String, Usage)> {
+    let default_broker = ["kafka", "nats", "rabbit"]
+        .into_iter()
+        .find(|b| {
+            let prefix = format!("spring.cloud.stream.{b}.");
+            properties.keys().any(|k| k.starts_with(&prefix))
+        })
+        .or_else(|| {
+            properties
+                .keys()
+                .any(|k| k.starts_with("spring.kafka."))
+                .then_some("kafka")
+        });
+
+    let mut out = Vec::new();
+    for (key, destinations) in properties {
+        let Some(binding) = key
+            .strip_prefix(BINDINGS)
+            .and_then(|rest| rest.strip_suffix(".destination"))
+        else {
+            continue;
+        };
+        let Some(role) = binding_role(binding) else {
+            continue;
+        };
+        let setting = |name: &str| properties.get(&format!("{BINDINGS}{binding}.{name}"));
+        let broker = setting("binder")
+            .and_then(|b| {
+                ["kafka", "nats", "rabbit"]
+                    .into_iter()
+                    .find(|known| b.contains(known))
+            })
+            .or(default_broker)
+            .unwrap_or("unknown");
+        for topic in destinations
+            .split(',')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+        {
+            out.push((
+                binding.to_string(),
+                Usage {
+                    topic: topic.to_string(),
+                    role,
+                    broker,
+                    group: setting("group").cloned(),
+                    message_type: None,
+                },
+            ));
+        }
+    }
+    if let Some(topic) = properties.get("spring.kafka.template.default-topic") {
+        out.push((
+            "default-topic".to_string(),
+            Usage {
+                topic: topic.clone(),
+                role: Role::Publish,
+                broker: "kafka",
+                group: None,
+                message_type: None,
+            },
+        ));
+    }
+    out
+}
+
+/// `orderCreated-in-0` / `input` subscribe, `orderCreated-out-0` / `output`
+/// publish; other names are not directional.
+fn binding_role(binding: &str) -> Option<Role> {
+    let lower = binding.to_ascii_lowercase();
+    let base = lower.trim_end_matches(|c: char| c.is_ascii_digit());
+    if base.ends_with("-in-") || lower == "input" {
+        Some(Role::Subscribe)
+    } else if base.ends_with("-out-") || lower == "output" {
+        Some(Role::Publish)
+    } else {
+        None
+    }
+}
+
+/// 1-based line range of a binding: its YAML block, or the first and last
+/// properties line naming it.
+fn binding_lines(lines: &[&str], binding: &str) -> (usize, usize) {
+    let indent = |l: &str| l.len() - l.trim_start().len();
+    let yaml_key = format!("{binding}:");
+    if let Some(at) = lines
+        .iter()
+        .position(|l| l.trim_start().starts_with(&yaml_key))
+    {
+        let end = lines
+            .iter()
+            .enumerate()
+            .skip(at + 1)
+            .take_while(|(_, l)| l.trim().is_empty() || indent(l) > indent(lines[at]))
+            .filter(|(_, l)| !l.trim().is_empty())
+            .map(|(i, _)| i)
+            .last()
+            .unwrap_or(at);
+        return (at + 1, end + 1);
+    }
+    let keys = ['.', '=', ':'].map(|end| format!(".{binding}{end}"));
+    let matching: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| keys.iter().any(|k| l.contains(k.as_str())))
+        .map(|(i, _)| i)
+        .collect();
+    match (matching.first(), matching.last()) {
+        (Some(first), Some(last)) => (first + 1, last + 1),
+        _ => (1, 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::rust_parser::DocumentKind;
+
+    fn parse(file_path: &str, source: &str) -> Vec<Document> {
+        let file = SourceFile {
+            repo: "billing",
+            file_path,
+            source,
+        };
+        SpringConfigLanguage
+            .parse_file(&file, false)
+            .expect("parse")
+    }
+
+    #[test]
+    fn matches_spring_config_files_only() {
+        let lang = SpringConfigLanguage;
+        assert!(lang.matches(Path::new("src/main/resources/application.yml")));
+        assert!(lang.matches(Path::new("application-prod.properties")));
+        assert!(!lang.matches(Path::new("docker-compose.yml")));
+        assert!(!lang.matches(Path::new("application.json")));
+    }
+
+    #[test]
+    fn stream_bindings_become_topics() {
+        let yaml = "\
+server:
+  port: 8080
+spring.cloud.stream:
+  kafka:
+    binder:
+      brokers: kafka:9092
+  bindings:
+    orderCreated-in-0:
+      destination: order.created,order.updated
+      group: billing
+    invoiceIssued-out-0:
+      destination: invoice.issued
+    audit:
+      destination: audit
+";
+        let docs = parse("src/main/resources/application.yml", yaml);
+        let summary: Vec<(String, String, String, u32, u32)> = docs
+            .iter()
+            .map(|d| {
+                assert_eq!(d.kind, DocumentKind::Topic);
+                (
+                    d.symbol_name.clone(),
+                    d.metadata["role"].as_str().unwrap().to_string(),
+                    d.metadata["group"].as_str().unwrap_or("-").to_string(),
+                    d.line_start,
+                    d.line_end,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "invoice.issued".into(),
+                    "publish".into(),
+                    "-".into(),
+                    11,
+                    12
+                ),
+                (
+                    "order.created".into(),
+                    "subscribe".into(),
+                    "billing".into(),
+                    8,
+                    10
+                ),
+                (
+                    "order.updated".into(),
+                    "subscribe".into(),
+                    "billing".into(),
+                    8,
+                    10
+                ),
+            ]
+        );
+        assert_eq!(docs[1].metadata["broker"], "kafka");
+        assert_eq!(docs[1].metadata["caller"], "orderCreated-in-0");
+
+        let props = "\
+# bindings
+spring.cloud.stream.bindings.payments-in-0.destination=payments.charged
+spring.cloud.stream.bindings.payments-in-0.binder=nats
+spring.kafka.template.default-topic=billing.events
+";
+        let docs = parse("application.properties", props);
+        let summary: Vec<(&str, &serde_json::Value, u32, u32)> = docs
+            .iter()
+            .map(|d| {
+                (
+                    d.symbol_name.as_str(),
+                    &d.metadata["broker"],
+                    d.line_start,
+                    d.line_end,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("payments.charged", &serde_json::json!("nats"), 2, 3),
+                ("billing.events", &serde_json::json!("kafka"), 4, 4),
+            ]
+        );
+    }
+}
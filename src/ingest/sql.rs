@@ -0,0 +1,732 @@
+//! sql.rs
+//!
+//! Database schema and query extraction shared by the parsers.
+//!
+//! Tables come from SQL migrations and Liquibase changelogs (see
+//! `sql_parser.rs`) and from diesel `table!` macros in Rust (`diesel_tables`,
+//! with the file's `joinable!` lines as foreign keys). Each yields a `table`
+//! document: symbol_name / qualified_name is the table as named in the schema
+//! (`payments.ledger_entries`); the repo declaring it is its owner. metadata:
+//! - `schema`, `table`, `source` (`migration` / `liquibase` / `diesel`),
+//!   `operation` (`create`, or `alter` for a file only changing the table)
+//! - `columns` (`{name, type, nullable, default}`), `primary_key`, `indexes`
+//!   (`{name, columns, unique}`), `foreign_keys` (`{columns, references_table,
+//!   references_columns}`), `dropped_columns`
+//!
+//! Queries are string literals of code that parse as SQL (`sqlx::query!`,
+//! JDBC, `@Query`, `db.Query`, `cursor.execute`, ...; see `sql_query`). Each
+//! yields a `sql_query` document named after its first written table, else its
+//! first read table, with metadata `operation`, `tables`, `reads`, `writes`,
+//! `caller` and the `sql` text; code is the enclosing call.
+
+use crate::ingest::http_calls::caller;
+use crate::ingest::language::SourceFile;
+use crate::ingest::rust_parser::{Document, DocumentKind, lines_of, node_text, normalize_ws};
+use serde::Serialize;
+use serde_json::json;
+use sqlparser::dialect::{Dialect, GenericDialect, MySqlDialect, PostgreSqlDialect};
+use sqlparser::parser::Parser;
+use std::collections::HashSet;
+use tree_sitter::Node;
+
+/// Keywords never taken for a table name or alias.
+const KEYWORDS: &[&str] = &[
+    "select",
+    "from",
+    "where",
+    "join",
+    "inner",
+    "left",
+    "right",
+    "full",
+    "outer",
+    "cross",
+    "on",
+    "using",
+    "group",
+    "order",
+    "by",
+    "having",
+    "limit",
+    "offset",
+    "union",
+    "all",
+    "as",
+    "set",
+    "values",
+    "lateral",
+    "only",
+    "natural",
+    "returning",
+    "for",
+    "with",
+    "into",
+    "update",
+    "delete",
+    "insert",
+    "and",
+    "or",
+    "not",
+    "in",
+    "is",
+    "null",
+    "window",
+    "fetch",
+    "default",
+];
+
+/// One table as one file declares or changes it.
+#[derive(Debug, Default)]
+pub(crate) struct Table {
+    pub name: String,
+    pub source: &'static str,
+    pub operation: &'static str,
+    pub columns: Vec<Column>,
+    pub primary_key: Vec<String>,
+    pub indexes: Vec<Index>,
+    pub foreign_keys: Vec<ForeignKey>,
+    pub dropped_columns: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct Column {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub data_type: String,
+    pub nullable: bool,
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct Index {
+    pub name: Option<String>,
+    pub columns: Vec<String>,
+    pub unique: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ForeignKey {
+    pub columns: Vec<String>,
+    pub references_table: String,
+    pub references_columns: Vec<String>,
+}
+
+/// Tables a query reads and writes.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct QueryTables {
+    pub operation: &'static str,
+    pub reads: Vec<String>,
+    pub writes: Vec<String>,
+}
+
+/// `table` document; `lines` is the 1-based range `code` was taken from.
+pub(crate) fn table_document(
+    file: &SourceFile,
+    table: Table,
+    code: String,
+    (line_start, line_end): (u32, u32),
+) -> Document {
+    let (schema, short) = match table.name.rsplit_once('.') {
+        Some((schema, short)) => (Some(schema.to_string()), short.to_string()),
+        None => (None, table.name.clone()),
+    };
+    let columns: Vec<String> = table
+        .columns
+        .iter()
+        .map(|c| {
+            let mut column = format!("{} {}", c.name, c.data_type);
+            if table.primary_key.contains(&c.name) {
+                column.push_str(" PK");
+            }
+            if let Some(fk) = table
+                .foreign_keys
+                .iter()
+                .find(|fk| fk.columns == [c.name.clone()])
+            {
+                column.push_str(&format!(" -> {}", fk.references_table));
+            }
+            column
+        })
+        .chain(table.dropped_columns.iter().map(|c| format!("-{c}")))
+        .collect();
+    let signature = format!(
+        "{} TABLE {} ({})",
+        table.operation.to_ascii_uppercase(),
+        table.name,
+        columns.join(", ")
+    );
+
+    let mut metadata = serde_json::Map::new();
+    metadata.insert("schema".into(), json!(schema));
+    metadata.insert("table".into(), json!(short));
+    metadata.insert("source".into(), json!(table.source));
+    metadata.insert("operation".into(), json!(table.operation));
+    metadata.insert("columns".into(), json!(table.columns));
+    metadata.insert("primary_key".into(), json!(table.primary_key));
+    metadata.insert("indexes".into(), json!(table.indexes));
+    metadata.insert("foreign_keys".into(), json!(table.foreign_keys));
+    metadata.insert("dropped_columns".into(), json!(table.dropped_columns));
+
+    Document {
+        repo: file.repo.to_string(),
+        file_path: file.file_path.to_string(),
+        qualified_name: Some(table.name.clone()),
+        symbol_name: table.name,
+        kind: DocumentKind::Table,
+        signature: Some(signature),
+        doc_comment: None,
+        code,
+        parent_type: None,
+        module_path: None,
+        annotations: Vec::new(),
+        metadata,
+        line_start,
+        line_end,
+    }
+}
+
+/// A string literal node (any language) holding a SQL query becomes a
+/// `sql_query` document.
+pub(crate) fn sql_query(file: &SourceFile, node: Node, out: &mut Vec<Document>) {
+    let source = file.source;
+    let sql = normalize_ws(&literal_text(&node_text(source, node)));
+    if !parses_as_sql(&sql) {
+        return;
+    }
+    let Some(tables) = query_tables(&sql) else {
+        return;
+    };
+    let span = enclosing_call(node).unwrap_or(node);
+    let caller = caller(source, node);
+    let main = tables
+        .writes
+        .first()
+        .or(tables.reads.first())
+        .cloned()
+        .unwrap_or_default();
+    let mut all = tables.writes.clone();
+    all.extend(
+        tables
+            .reads
+            .iter()
+            .filter(|t| !tables.writes.contains(t))
+            .cloned(),
+    );
+    let signature = format!(
+        "{} {}{}",
+        tables.operation.to_ascii_uppercase(),
+        all.join(", "),
+        caller
+            .as_deref()
+            .map(|c| format!(" (in {c})"))
+            .unwrap_or_default()
+    );
+
+    let mut metadata = serde_json::Map::new();
+    metadata.insert("operation".into(), json!(tables.operation));
+    metadata.insert("tables".into(), json!(all));
+    metadata.insert("reads".into(), json!(tables.reads));
+    metadata.insert("writes".into(), json!(tables.writes));
+    metadata.insert("caller".into(), json!(caller));
+    metadata.insert("sql".into(), json!(sql));
+
+    let (line_start, line_end) = lines_of(&span);
+    out.push(Document {
+        repo: file.repo.to_string(),
+        file_path: file.file_path.to_string(),
+        qualified_name: Some(main.clone()),
+        symbol_name: main,
+        kind: DocumentKind::SqlQuery,
+        signature: Some(signature),
+        doc_comment: None,
+        code: node_text(source, span),
+        parent_type: None,
+        module_path: None,
+        annotations: Vec::new(),
+        metadata,
+        line_start,
+        line_end,
+    });
+}
+
+/// Contents of a string literal: prefixes (`r#`, `b`, `f`), triple quotes and
+/// interpolations (`${id}`, `$id`) replaced by `?`.
+fn literal_text(raw: &str) -> String {
+    let body = raw.trim_start_matches(|c: char| c.is_ascii_alphabetic());
+    let hashes = body.len() - body.trim_start_matches('#').len();
+    let body = body
+        .get(hashes..body.len().saturating_sub(hashes))
+        .unwrap_or(body);
+    let body = ["\"\"\"", "'''", "\"", "'", "`"]
+        .into_iter()
+        .find_map(|q| body.strip_prefix(q)?.strip_suffix(q))
+        .unwrap_or(body);
+
+    let mut out = String::with_capacity(body.len());
+    let mut chars = body.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('$', Some('{')) => {
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                }
+                out.push('?');
+            }
+            ('$', Some(n)) if n.is_ascii_alphabetic() => {
+                while chars
+                    .peek()
+                    .is_some_and(|c| c.is_ascii_alphanumeric() || *c == '_')
+                {
+                    chars.next();
+                }
+                out.push('?');
+            }
+            ('\\', Some('n' | 't' | '\n')) => {
+                chars.next();
+                out.push(' ');
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Statements starting with a DML keyword that some dialect accepts.
+fn parses_as_sql(sql: &str) -> bool {
+    let first = sql
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    if !matches!(
+        first.as_str(),
+        "select" | "insert" | "update" | "delete" | "with" | "merge"
+    ) {
+        return false;
+    }
+    // psycopg placeholders
+    let sql = sql.replace("%s", "?");
+    let dialects: [&dyn Dialect; 3] = [&PostgreSqlDialect {}, &MySqlDialect {}, &GenericDialect {}];
+    dialects
+        .into_iter()
+        .any(|d| Parser::parse_sql(d, &sql).is_ok())
+}
+
+/// Tables read (`FROM`, `JOIN`, `USING`) and written (`INSERT INTO`,
+/// `UPDATE`, `DELETE FROM`, `MERGE INTO`) by a query; CTE names excluded.
+pub(crate) fn query_tables(sql: &str) -> Option<QueryTables> {
+    let toks = tokens(sql);
+    let lower: Vec<String> = toks.iter().map(|t| t.to_ascii_lowercase()).collect();
+    let at = |i: usize| lower.get(i).map(String::as_str).unwrap_or_default();
+
+    let ctes: HashSet<&str> = (1..lower.len())
+        .filter(|&i| {
+            at(i + 1) == "as" && at(i + 2) == "(" && matches!(at(i - 1), "with" | "," | "recursive")
+        })
+        .map(&at)
+        .collect();
+    // a name followed by `(` in FROM is a function (`generate_series(...)`),
+    // after INTO a column list
+    let table = |i: usize, function_ok: bool| -> Option<String> {
+        let t = lower.get(i)?;
+        let starts_ident = t.starts_with(|c: char| c.is_alphabetic() || "_\"`[".contains(c));
+        (starts_ident
+            && !KEYWORDS.contains(&t.as_str())
+            && (function_ok || at(i + 1) != "(")
+            && !ctes.contains(t.as_str()))
+        .then(|| unquote_ident(&toks[i]))
+    };
+
+    let mut tables = QueryTables::default();
+    let push = |list: &mut Vec<String>, name: Option<String>| {
+        if let Some(name) = name
+            && !list.contains(&name)
+        {
+            list.push(name);
+        }
+    };
+    for i in 0..lower.len() {
+        match at(i) {
+            "from" if i > 0 && at(i - 1) == "delete" => {
+                tables.operation = "delete";
+                push(&mut tables.writes, table(i + 1, true));
+            }
+            "from" => {
+                // `FROM a x, b AS y`
+                let mut j = i + 1;
+                while let Some(name) = table(j, false) {
+                    push(&mut tables.reads, Some(name));
+                    j += 1;
+                    if at(j) == "as" {
+                        j += 2;
+                    } else if table(j, false).is_some() {
+                        j += 1;
+                    }
+                    if at(j) != "," {
+                        break;
+                    }
+                    j += 1;
+                }
+            }
+            "join" | "using" => push(&mut tables.reads, table(i + 1, false)),
+            "into" => {
+                if tables.operation.is_empty() {
+                    tables.operation = match lower.iter().find(|t| *t == "merge") {
+                        Some(_) => "merge",
+                        None => "insert",
+                    };
+                }
+                push(&mut tables.writes, table(i + 1, true));
+            }
+            "update" if i == 0 || !matches!(at(i - 1), "for" | "do" | "key") => {
+                tables.operation = "update";
+                push(&mut tables.writes, table(i + 1, true));
+            }
+            _ => {}
+        }
+    }
+    if tables.operation.is_empty() {
+        tables.operation = "select";
+    }
+    (!tables.reads.is_empty() || !tables.writes.is_empty()).then_some(tables)
+}
+
+/// Identifiers (`"schema"."table"` kept whole), parentheses and commas.
+fn tokens(sql: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut chars = sql.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '-' && chars.clone().nth(1) == Some('-') {
+            for c in chars.by_ref() {
+                if c == '\n' {
+                    break;
+                }
+            }
+        } else if c == '\'' {
+            chars.next();
+            for c in chars.by_ref() {
+                if c == '\'' {
+                    break;
+                }
+            }
+            out.push("'".to_string());
+        } else if c.is_alphanumeric() || "_.$\"`[]".contains(c) {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek() {
+                if !(c.is_alphanumeric() || "_.$\"`[]".contains(c)) {
+                    break;
+                }
+                word.push(c);
+                chars.next();
+            }
+            out.push(word);
+        } else {
+            out.push(c.to_string());
+            chars.next();
+        }
+    }
+    out
+}
+
+/// `"Payments"."Ledger"` → `Payments.Ledger`.
+pub(crate) fn unquote_ident(ident: &str) -> String {
+    ident.replace(['"', '`', '[', ']'], "")
+}
+
+/// Whether two table names denote the same table: case-insensitive, and a
+/// name without schema matches the qualified one (`ledger_entries` /
+/// `payments.ledger_entries`).
+pub fn same_table(a: &str, b: &str) -> bool {
+    let (a, b) = (a.to_ascii_lowercase(), b.to_ascii_lowercase());
+    let short = |s: &str| s.rsplit('.').next().unwrap_or_default().to_string();
+    a == b || ((!a.contains('.') || !b.contains('.')) && short(&a) == short(&b))
+}
+
+/// The call (or macro / annotation) a query literal is passed to.
+fn enclosing_call(node: Node) -> Option<Node> {
+    let mut cur = node.parent();
+    for _ in 0..4 {
+        let n = cur?;
+        if matches!(
+            n.kind(),
+            "call_expression"
+                | "macro_invocation"
+                | "method_invocation"
+                | "call"
+                | "annotation"
+                | "object_creation_expression"
+        ) {
+            return Some(n);
+        }
+        cur = n.parent();
+    }
+    None
+}
+
+/// `table!` / `diesel::table!` macro invocations: one `table` document per
+/// declared table.
+pub(crate) fn diesel_tables(file: &SourceFile, node: Node, out: &mut Vec<Document>) {
+    let source = file.source;
+    let Some(name) = node.child_by_field_name("macro") else {
+        return;
+    };
+    if !matches!(node_text(source, name).as_str(), "table" | "diesel::table") {
+        return;
+    }
+    let Some(body) = (0..node.named_child_count())
+        .filter_map(|i| node.named_child(i))
+        .find(|c| c.kind() == "token_tree")
+    else {
+        return;
+    };
+    let text = node_text(source, body);
+    let joinable = joinables(source);
+    let code = node_text(source, node);
+    for mut table in parse_table_macro(&text) {
+        table.foreign_keys.extend(
+            joinable
+                .iter()
+                .filter(|(child, _, _)| same_table(child, &table.name))
+                .map(|(_, parent, column)| ForeignKey {
+                    columns: vec![column.clone()],
+                    references_table: parent.clone(),
+                    references_columns: Vec::new(),
+                }),
+        );
+        out.push(table_document(file, table, code.clone(), lines_of(&node)));
+    }
+}
+
+/// Tables of a `table!` body: `name (pk) { col -> Type, ... }` entries, `use`
+/// lines and attributes skipped.
+fn parse_table_macro(body: &str) -> Vec<Table> {
+    let inner = body
+        .trim()
+        .trim_start_matches(['{', '(', '['])
+        .trim_end_matches(['}', ')', ']']);
+    let mut cleaned = String::new();
+    for line in inner.lines() {
+        let line = line.trim();
+        if line.starts_with("use ") || line.starts_with("//") {
+            continue;
+        }
+        let line = match line.starts_with("#[") {
+            true => line.split_once(']').map_or("", |(_, rest)| rest),
+            false => line,
+        };
+        cleaned.push_str(line);
+        cleaned.push('\n');
+    }
+
+    let mut tables = Vec::new();
+    let mut rest = cleaned.as_str();
+    while let Some(open) = rest.find('{') {
+        let header = rest[..open].trim();
+        let Some(close) = rest[open..].find('}') else {
+            break;
+        };
+        let columns_text = &rest[open + 1..open + close];
+        rest = &rest[open + close + 1..];
+
+        let (name, pk) = match header.split_once('(') {
+            Some((name, pk)) => (name.trim(), pk.trim_end_matches(')')),
+            None => (header, "id"),
+        };
+        let mut table = Table {
+            name: name.replace(' ', ""),
+            source: "diesel",
+            operation: "create",
+            primary_key: pk
+                .split(',')
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty())
+                .collect(),
+            ..Table::default()
+        };
+        for entry in split_top_level(columns_text) {
+            let Some((column, ty)) = entry.split_once("->") else {
+                continue;
+            };
+            let ty = ty.trim();
+            let (nullable, ty) = match ty.strip_prefix("Nullable<") {
+                Some(inner) => (true, inner.strip_suffix('>').unwrap_or(inner)),
+                None => (false, ty),
+            };
+            table.columns.push(Column {
+                name: column.trim().to_string(),
+                data_type: ty.to_string(),
+                nullable,
+                default: None,
+            });
+        }
+        tables.push(table);
+    }
+    tables
+}
+
+/// Commas outside `<...>`.
+fn split_top_level(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut depth, mut start) = (0i32, 0);
+    for (i, c) in text.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' if !text[..i].ends_with('-') => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(text[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(text[start..].trim());
+    parts.retain(|p| !p.is_empty());
+    parts
+}
+
+/// `joinable!(posts -> users (user_id));` as `(posts, users, user_id)`.
+fn joinables(source: &str) -> Vec<(String, String, String)> {
+    source
+        .split("joinable!")
+        .skip(1)
+        .filter_map(|rest| {
+            let args = rest.trim_start().strip_prefix('(')?;
+            let args = &args[..args.find(';')?];
+            let (child, rest) = args.split_once("->")?;
+            let (parent, column) = rest.split_once('(')?;
+            let column = column.split(')').next()?;
+            Some((
+                child.trim().to_string(),
+                parent.trim().to_string(),
+                column.trim().to_string(),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::go_parser::GoLanguage;
+    use crate::ingest::language::LanguageSupport;
+    use crate::ingest::rust_parser::RustLanguage;
+
+    fn parse(language: &dyn LanguageSupport, file_path: &str, source: &str) -> Vec<Document> {
+        let file = SourceFile {
+            repo: "payments",
+            file_path,
+            source,
+        };
+        language
+            .parse_file(&file, false)
+            .unwrap()
+            .into_iter()
+            .filter(|d| matches!(d.kind, DocumentKind::Table | DocumentKind::SqlQuery))
+            .collect()
+    }
+
+    #[test]
+    fn classifies_reads_and_writes() {
+        let tables = |sql: &str| {
+            let t = query_tables(sql).unwrap();
+            (t.operation, t.reads, t.writes)
+        };
+        assert_eq!(
+            tables(
+                "SELECT e.id FROM payments.ledger_entries e JOIN accounts a ON a.id = e.account_id FOR UPDATE"
+            ),
+            (
+                "select",
+                vec![
+                    "payments.ledger_entries".to_string(),
+                    "accounts".to_string()
+                ],
+                vec![]
+            )
+        );
+        assert_eq!(
+            tables(
+                "WITH due AS (SELECT id FROM invoices) UPDATE invoices SET paid = true FROM due WHERE invoices.id = due.id"
+            ),
+            (
+                "update",
+                vec!["invoices".to_string()],
+                vec!["invoices".to_string()]
+            )
+        );
+        assert_eq!(
+            tables(
+                "INSERT INTO ledger_entries (id) VALUES ($1) ON CONFLICT (id) DO UPDATE SET id = $1"
+            ),
+            ("insert", vec![], vec!["ledger_entries".to_string()])
+        );
+        assert!(same_table("ledger_entries", "payments.Ledger_Entries"));
+        assert!(!same_table(
+            "billing.ledger_entries",
+            "payments.ledger_entries"
+        ));
+    }
+
+    #[test]
+    fn queries_in_code() {
+        let src = r##"
+async fn record(pool: &PgPool, id: i64) -> Result<()> {
+    sqlx::query!(r#"INSERT INTO ledger_entries (id, account_id) VALUES ($1, $2)"#, id, 1)
+        .execute(pool)
+        .await?;
+    let msg = "select a ledger entry from the list";
+    Ok(())
+}
+"##;
+        let docs = parse(&RustLanguage, "src/ledger.rs", src);
+        assert_eq!(docs.len(), 1);
+        assert_eq!(docs[0].symbol_name, "ledger_entries");
+        assert_eq!(docs[0].metadata["writes"], json!(["ledger_entries"]));
+        assert_eq!(docs[0].metadata["caller"], "record");
+        assert_eq!(docs[0].line_start, 3);
+
+        let src = "package store\n\nfunc (s *Store) Balance(id string) {\n\ts.db.QueryRow(`SELECT sum(amount) FROM ledger_entries WHERE account_id = $1`, id)\n}\n";
+        let docs = parse(&GoLanguage, "store/ledger.go", src);
+        assert_eq!(
+            docs[0].signature.as_deref(),
+            Some("SELECT ledger_entries (in Balance)")
+        );
+    }
+
+    #[test]
+    fn diesel_schema() {
+        let src = "\
+// @generated automatically by Diesel CLI.
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    payments.ledger_entries (id) {
+        id -> Int8,
+        account_id -> Int8,
+        #[sql_name = \"type\"]
+        kind -> Varchar,
+        memo -> Nullable<Text>,
+    }
+}
+
+diesel::joinable!(ledger_entries -> accounts (account_id));
+";
+        let docs = parse(&RustLanguage, "src/schema.rs", src);
+        assert_eq!(docs.len(), 1);
+        let meta = &docs[0].metadata;
+        assert_eq!(docs[0].symbol_name, "payments.ledger_entries");
+        assert_eq!(meta["schema"], "payments");
+        assert_eq!(meta["primary_key"], json!(["id"]));
+        assert_eq!(
+            meta["columns"][3],
+            json!({ "name": "memo", "type": "Text", "nullable": true, "default": null })
+        );
+        assert_eq!(meta["columns"][2]["name"], "kind");
+        assert_eq!(meta["foreign_keys"][0]["references_table"], "accounts");
+    }
+}
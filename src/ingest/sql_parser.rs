@@ -0,0 +1,699 @@
+//! sql_parser.rs
+//!
+//! Schema migration support for the language registry: `.sql` files
+//! (plain migrations, Flyway `V3__add_ledger.sql`, Liquibase formatted SQL)
+//! and Liquibase changelogs (`*changelog*.{xml,yaml,yml,json}`). Both override
+//! `parse_file` and yield one `table` document (see `sql.rs`) per table the
+//! file creates or changes.
+//!
+//! SQL files are split into statements at line-final `;` (`$$` bodies kept
+//! together) and each is read with sqlparser (PostgreSQL, then MySQL, then
+//! generic dialect); statements no dialect accepts are skipped. Understood:
+//! `CREATE TABLE` (columns, inline and table constraints), `ALTER TABLE`
+//! (`ADD`/`DROP`/`RENAME COLUMN`, `ADD CONSTRAINT`) and `CREATE INDEX`. A table
+//! only altered by the file gets `operation: alter`. code is the file's
+//! statements about the table, the line range spans them.
+//!
+//! Changelogs: `createTable`, `addColumn`, `dropColumn`, `renameColumn`,
+//! `createIndex`, `addPrimaryKey`, `addUniqueConstraint` and
+//! `addForeignKeyConstraint` changes (column `constraints` included), wherever
+//! they appear; XML attributes and YAML/JSON keys are read alike. code is the
+//! change blocks naming the table (located by indentation, best-effort).
+
+use crate::ingest::language::{LanguageSupport, SourceFile, file_name_lower};
+use crate::ingest::openapi_parser::block_end;
+use crate::ingest::rust_parser::{Document, RustParserError, with_file_summary};
+use crate::ingest::sql::{Column, ForeignKey, Index, Table, table_document, unquote_ident};
+use serde_norway::{Mapping, Value};
+use sqlparser::ast::{
+    AlterTableOperation, ColumnDef, ColumnOption, ObjectName, Statement, TableConstraint,
+};
+use sqlparser::dialect::{Dialect, GenericDialect, MySqlDialect, PostgreSqlDialect};
+use sqlparser::parser::Parser;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+/// Tables of a file in order of appearance, with the 0-based line ranges of
+/// the statements / changes about them.
+#[derive(Default)]
+struct Schema {
+    tables: Vec<(Table, Vec<RangeInclusive<usize>>)>,
+}
+
+impl Schema {
+    /// The table named `name`, added as an `alter` when first seen.
+    fn table(
+        &mut self,
+        name: &str,
+        source: &'static str,
+    ) -> &mut (Table, Vec<RangeInclusive<usize>>) {
+        let at = match self
+            .tables
+            .iter()
+            .position(|(t, _)| t.name.eq_ignore_ascii_case(name))
+        {
+            Some(at) => at,
+            None => {
+                let table = Table {
+                    name: name.to_string(),
+                    source,
+                    operation: "alter",
+                    ..Table::default()
+                };
+                self.tables.push((table, Vec::new()));
+                self.tables.len() - 1
+            }
+        };
+        &mut self.tables[at]
+    }
+
+    fn documents(self, file: &SourceFile, include_filename_doc: bool) -> Vec<Document> {
+        let lines: Vec<&str> = file.source.lines().collect();
+        let mut out = Vec::new();
+        for (table, mut ranges) in self.tables {
+            ranges.sort_by_key(|r| *r.start());
+            ranges.dedup();
+            let code = ranges
+                .iter()
+                .filter_map(|r| lines.get(r.clone()))
+                .map(|block| block.join("\n"))
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            let start = ranges.first().map_or(0, |r| *r.start());
+            let end = ranges.iter().map(|r| *r.end()).max().unwrap_or(start);
+            out.push(table_document(
+                file,
+                table,
+                code,
+                (start as u32 + 1, end as u32 + 1),
+            ));
+        }
+        with_file_summary(file, include_filename_doc, out)
+    }
+}
+
+pub struct SqlLanguage;
+
+impl LanguageSupport for SqlLanguage {
+    fn name(&self) -> &'static str {
+        "sql"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["sql"]
+    }
+
+    fn parse_file(
+        &self,
+        file: &SourceFile,
+        include_filename_doc: bool,
+    ) -> Result<Vec<Document>, RustParserError> {
+        let mut schema = Schema::default();
+        for (statement, lines) in statements(file.source) {
+            apply_statement(&mut schema, statement, lines);
+        }
+        Ok(schema.documents(file, include_filename_doc))
+    }
+}
+
+/// Statements of a SQL file with their 0-based line ranges.
+fn statements(source: &str) -> Vec<(Statement, RangeInclusive<usize>)> {
+    let dialects: [&dyn Dialect; 3] = [&PostgreSqlDialect {}, &MySqlDialect {}, &GenericDialect {}];
+    let lines: Vec<&str> = source.lines().collect();
+    let mut out = Vec::new();
+    let mut start = None;
+    let mut in_dollar_body = false;
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        if start.is_none() && (trimmed.is_empty() || trimmed.starts_with("--")) {
+            continue;
+        }
+        let first = *start.get_or_insert(i);
+        if line.matches("$$").count() % 2 == 1 {
+            in_dollar_body = !in_dollar_body;
+        }
+        if in_dollar_body || !(trimmed.ends_with(';') || i + 1 == lines.len()) {
+            continue;
+        }
+        start = None;
+        let text = lines[first..=i].join("\n");
+        if let Some(statements) = dialects
+            .iter()
+            .find_map(|d| Parser::parse_sql(*d, &text).ok())
+        {
+            out.extend(statements.into_iter().map(|s| (s, first..=i)));
+        }
+    }
+    out
+}
+
+fn apply_statement(schema: &mut Schema, statement: Statement, lines: RangeInclusive<usize>) {
+    match statement {
+        Statement::CreateTable(create) => {
+            let (table, ranges) = schema.table(&object_name(&create.name), "migration");
+            table.operation = "create";
+            ranges.push(lines);
+            for column in &create.columns {
+                add_column(table, column);
+            }
+            for constraint in &create.constraints {
+                add_constraint(table, constraint);
+            }
+        }
+        Statement::AlterTable {
+            name, operations, ..
+        } => {
+            let (table, ranges) = schema.table(&object_name(&name), "migration");
+            ranges.push(lines);
+            for operation in operations {
+                match operation {
+                    AlterTableOperation::AddColumn { column_def, .. } => {
+                        add_column(table, &column_def)
+                    }
+                    AlterTableOperation::DropColumn { column_name, .. } => {
+                        table.columns.retain(|c| c.name != column_name.value);
+                        table.dropped_columns.push(column_name.value);
+                    }
+                    AlterTableOperation::RenameColumn {
+                        old_column_name,
+                        new_column_name,
+                    } => {
+                        if let Some(c) = table
+                            .columns
+                            .iter_mut()
+                            .find(|c| c.name == old_column_name.value)
+                        {
+                            c.name = new_column_name.value;
+                        }
+                    }
+                    AlterTableOperation::AddConstraint(constraint) => {
+                        add_constraint(table, &constraint)
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Statement::CreateIndex(index) => {
+            let (table, ranges) = schema.table(&object_name(&index.table_name), "migration");
+            ranges.push(lines);
+            table.indexes.push(Index {
+                name: index.name.as_ref().map(object_name),
+                columns: index.columns.iter().map(|c| c.expr.to_string()).collect(),
+                unique: index.unique,
+            });
+        }
+        _ => {}
+    }
+}
+
+fn add_column(table: &mut Table, column: &ColumnDef) {
+    let name = column.name.value.clone();
+    let mut nullable = true;
+    let mut default = None;
+    for option in &column.options {
+        match &option.option {
+            ColumnOption::NotNull => nullable = false,
+            ColumnOption::Default(expr) => default = Some(expr.to_string()),
+            ColumnOption::Unique { is_primary, .. } => {
+                if *is_primary {
+                    nullable = false;
+                    table.primary_key.push(name.clone());
+                } else {
+                    table.indexes.push(Index {
+                        name: None,
+                        columns: vec![name.clone()],
+                        unique: true,
+                    });
+                }
+            }
+            ColumnOption::ForeignKey {
+                foreign_table,
+                referred_columns,
+                ..
+            } => table.foreign_keys.push(ForeignKey {
+                columns: vec![name.clone()],
+                references_table: object_name(foreign_table),
+                references_columns: referred_columns.iter().map(|c| c.value.clone()).collect(),
+            }),
+            _ => {}
+        }
+    }
+    table.columns.push(Column {
+        name,
+        data_type: column.data_type.to_string(),
+        nullable,
+        default,
+    });
+}
+
+fn add_constraint(table: &mut Table, constraint: &TableConstraint) {
+    let names = |idents: &[sqlparser::ast::Ident]| -> Vec<String> {
+        idents.iter().map(|i| i.value.clone()).collect()
+    };
+    match constraint {
+        TableConstraint::PrimaryKey { columns, .. } => {
+            table.primary_key = names(columns);
+            for c in table.columns.iter_mut() {
+                if table.primary_key.contains(&c.name) {
+                    c.nullable = false;
+                }
+            }
+        }
+        TableConstraint::Unique {
+            name,
+            index_name,
+            columns,
+            ..
+        } => table.indexes.push(Index {
+            name: index_name
+                .as_ref()
+                .or(name.as_ref())
+                .map(|n| n.value.clone()),
+            columns: names(columns),
+            unique: true,
+        }),
+        TableConstraint::Index { name, columns, .. } => table.indexes.push(Index {
+            name: name.as_ref().map(|n| n.value.clone()),
+            columns: names(columns),
+            unique: false,
+        }),
+        TableConstraint::ForeignKey {
+            columns,
+            foreign_table,
+            referred_columns,
+            ..
+        } => table.foreign_keys.push(ForeignKey {
+            columns: names(columns),
+            references_table: object_name(foreign_table),
+            references_columns: names(referred_columns),
+        }),
+        _ => {}
+    }
+}
+
+/// `"payments"."ledger_entries"` → `payments.ledger_entries`.
+fn object_name(name: &ObjectName) -> String {
+    unquote_ident(&name.to_string())
+}
+
+pub struct LiquibaseLanguage;
+
+impl LanguageSupport for LiquibaseLanguage {
+    fn name(&self) -> &'static str {
+        "liquibase"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["xml", "yaml", "yml", "json"]
+    }
+
+    /// Only changelog files; other XML/YAML is left alone.
+    fn matches(&self, path: &Path) -> bool {
+        self.has_extension(path) && file_name_lower(path).contains("changelog")
+    }
+
+    fn parse_file(
+        &self,
+        file: &SourceFile,
+        include_filename_doc: bool,
+    ) -> Result<Vec<Document>, RustParserError> {
+        let changelog = if file.file_path.ends_with(".xml") {
+            let xml = roxmltree::Document::parse(file.source).map_err(|e| {
+                RustParserError::InvalidFormat {
+                    format: "Liquibase changelog",
+                    message: e.to_string(),
+                }
+            })?;
+            xml_value(xml.root_element())
+        } else {
+            serde_norway::from_str(file.source).map_err(|e| RustParserError::InvalidFormat {
+                format: "Liquibase changelog",
+                message: e.to_string(),
+            })?
+        };
+
+        let lines: Vec<&str> = file.source.lines().collect();
+        let mut schema = Schema::default();
+        apply_changes(&mut schema, &changelog, &lines);
+        Ok(schema.documents(file, include_filename_doc))
+    }
+}
+
+/// An element as a mapping: attributes, and child elements grouped by tag.
+fn xml_value(node: roxmltree::Node) -> Value {
+    let mut map = Mapping::new();
+    for attr in node.attributes() {
+        map.insert(attr.name().into(), attr.value().into());
+    }
+    for child in node.children().filter(|c| c.is_element()) {
+        let key = Value::from(child.tag_name().name());
+        let value = xml_value(child);
+        match map.get_mut(&key) {
+            Some(Value::Sequence(items)) => items.push(value),
+            _ => {
+                map.insert(key, Value::Sequence(vec![value]));
+            }
+        }
+    }
+    Value::Mapping(map)
+}
+
+/// Every change anywhere below `value`.
+fn apply_changes(schema: &mut Schema, value: &Value, lines: &[&str]) {
+    match value {
+        Value::Mapping(map) => {
+            for (key, child) in map {
+                let change = key.as_str().unwrap_or_default();
+                for change_value in items(Some(child)) {
+                    apply_change(schema, change, change_value, lines);
+                }
+                apply_changes(schema, child, lines);
+            }
+        }
+        Value::Sequence(items) => {
+            for item in items {
+                apply_changes(schema, item, lines);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn apply_change(schema: &mut Schema, change: &str, value: &Value, lines: &[&str]) {
+    let text = |v: &Value, key: &str| v.get(key).and_then(scalar);
+    let list = |v: &Value, key: &str| -> Vec<String> {
+        text(v, key)
+            .map(|s| s.split(',').map(|c| c.trim().to_string()).collect())
+            .unwrap_or_default()
+    };
+    let table_key = match change {
+        "addForeignKeyConstraint" => "baseTableName",
+        "createTable"
+        | "addColumn"
+        | "dropColumn"
+        | "renameColumn"
+        | "createIndex"
+        | "addPrimaryKey"
+        | "addUniqueConstraint" => "tableName",
+        _ => return,
+    };
+    let Some(table_name) = text(value, table_key) else {
+        return;
+    };
+    let name = match text(value, "schemaName").or_else(|| text(value, "baseTableSchemaName")) {
+        Some(schema) => format!("{schema}.{table_name}"),
+        None => table_name.clone(),
+    };
+    let (table, ranges) = schema.table(&name, "liquibase");
+    let range = change_lines(lines, table_key, &table_name, ranges);
+    ranges.extend(range);
+
+    match change {
+        "createTable" | "addColumn" => {
+            if change == "createTable" {
+                table.operation = "create";
+            }
+            for column in columns(value) {
+                add_liquibase_column(table, column);
+            }
+        }
+        "dropColumn" => {
+            let mut dropped = list(value, "columnName");
+            dropped.extend(columns(value).filter_map(|c| text(c, "name")));
+            table.columns.retain(|c| !dropped.contains(&c.name));
+            table.dropped_columns.extend(dropped);
+        }
+        "renameColumn" => {
+            let (Some(old), Some(new)) =
+                (text(value, "oldColumnName"), text(value, "newColumnName"))
+            else {
+                return;
+            };
+            if let Some(c) = table.columns.iter_mut().find(|c| c.name == old) {
+                c.name = new;
+            }
+        }
+        "createIndex" => table.indexes.push(Index {
+            name: text(value, "indexName"),
+            columns: columns(value).filter_map(|c| text(c, "name")).collect(),
+            unique: value.get("unique").is_some_and(truthy),
+        }),
+        "addPrimaryKey" => table.primary_key = list(value, "columnNames"),
+        "addUniqueConstraint" => table.indexes.push(Index {
+            name: text(value, "constraintName"),
+            columns: list(value, "columnNames"),
+            unique: true,
+        }),
+        "addForeignKeyConstraint" => table.foreign_keys.push(ForeignKey {
+            columns: list(value, "baseColumnNames"),
+            references_table: text(value, "referencedTableName").unwrap_or_default(),
+            references_columns: list(value, "referencedColumnNames"),
+        }),
+        _ => {}
+    }
+}
+
+fn add_liquibase_column(table: &mut Table, column: &Value) {
+    let Some(name) = column.get("name").and_then(scalar) else {
+        return;
+    };
+    let constraints = items(column.get("constraints")).next();
+    let flag = |key: &str| constraints.and_then(|c| c.get(key)).is_some_and(truthy);
+    let nullable = !flag("primaryKey")
+        && constraints
+            .and_then(|c| c.get("nullable"))
+            .is_none_or(truthy);
+    if flag("primaryKey") {
+        table.primary_key.push(name.clone());
+    }
+    if flag("unique") {
+        table.indexes.push(Index {
+            name: None,
+            columns: vec![name.clone()],
+            unique: true,
+        });
+    }
+    // `references: accounts(id)` or `referencedTableName` + `referencedColumnNames`
+    let references = constraints.and_then(|c| {
+        if let Some(r) = c.get("references").and_then(scalar) {
+            let (table, columns) = r.split_once('(').unwrap_or((&r, ""));
+            return Some((
+                table.trim().to_string(),
+                columns.trim_end_matches(')').to_string(),
+            ));
+        }
+        let table = c.get("referencedTableName").and_then(scalar)?;
+        Some((
+            table,
+            c.get("referencedColumnNames")
+                .and_then(scalar)
+                .unwrap_or_default(),
+        ))
+    });
+    if let Some((references_table, columns)) = references {
+        table.foreign_keys.push(ForeignKey {
+            columns: vec![name.clone()],
+            references_table,
+            references_columns: columns
+                .split(',')
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty())
+                .collect(),
+        });
+    }
+    table.columns.push(Column {
+        name,
+        data_type: column.get("type").and_then(scalar).unwrap_or_default(),
+        nullable,
+        default: [
+            "defaultValue",
+            "defaultValueNumeric",
+            "defaultValueComputed",
+            "defaultValueBoolean",
+        ]
+        .into_iter()
+        .find_map(|k| column.get(k).and_then(scalar)),
+    });
+}
+
+/// Columns of a change: `columns: [{column: {...}}]` (YAML/JSON) or
+/// `<column .../>` children (XML).
+fn columns(change: &Value) -> impl Iterator<Item = &Value> {
+    items(change.get("columns"))
+        .filter_map(|c| c.get("column"))
+        .chain(items(change.get("column")))
+}
+
+/// A mapping, or the mappings of a sequence.
+fn items(value: Option<&Value>) -> Box<dyn Iterator<Item = &Value> + '_> {
+    match value {
+        Some(Value::Sequence(items)) => Box::new(items.iter()),
+        Some(v @ Value::Mapping(_)) => Box::new(std::iter::once(v)),
+        _ => Box::new(std::iter::empty()),
+    }
+}
+
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// YAML booleans and XML `"true"`.
+fn truthy(value: &Value) -> bool {
+    value.as_bool().unwrap_or(value.as_str() == Some("true"))
+}
+
+/// Line range of the next change (after those in `seen`) naming `table` under
+/// `key`: the XML element, or the YAML/JSON block holding the key.
+fn change_lines(
+    lines: &[&str],
+    key: &str,
+    table: &str,
+    seen: &[RangeInclusive<usize>],
+) -> Option<RangeInclusive<usize>> {
+    let indent = |l: &str| l.len() - l.trim_start().len();
+    let at = (0..lines.len()).find(|&i| {
+        lines[i].contains(key) && lines[i].contains(table) && !seen.iter().any(|r| r.contains(&i))
+    })?;
+    let trimmed = lines[at].trim_start().trim_start_matches(['-', ' ', '"']);
+    let opener = match trimmed.starts_with(key) {
+        // `tableName: x` inside the change block: the block starts above
+        true => (0..at)
+            .rev()
+            .find(|&i| !lines[i].trim().is_empty() && indent(lines[i]) < indent(lines[at]))
+            .unwrap_or(at),
+        false => at,
+    };
+    Some(opener..=block_end(lines, opener))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parse(lang: &dyn LanguageSupport, file_path: &str, source: &str) -> Vec<Document> {
+        let file = SourceFile {
+            repo: "payments",
+            file_path,
+            source,
+        };
+        lang.parse_file(&file, false).expect("parse")
+    }
+
+    #[test]
+    fn migrations_become_tables() {
+        let src = "\
+-- V3: ledger
+CREATE TABLE payments.ledger_entries (
+    id BIGSERIAL PRIMARY KEY,
+    account_id BIGINT NOT NULL REFERENCES accounts (id),
+    amount NUMERIC(12, 2) NOT NULL DEFAULT 0,
+    memo TEXT
+);
+
+CREATE OR REPLACE FUNCTION touch() RETURNS trigger AS $$
+BEGIN
+    NEW.updated_at = now();
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE UNIQUE INDEX ledger_entries_account_idx
+    ON payments.ledger_entries (account_id, id);
+
+ALTER TABLE accounts ADD COLUMN closed_at TIMESTAMPTZ;
+ALTER TABLE accounts DROP COLUMN legacy_code;
+";
+        let docs = parse(&SqlLanguage, "db/migration/V3__ledger.sql", src);
+        assert_eq!(docs.len(), 2);
+
+        let ledger = &docs[0];
+        assert_eq!(ledger.symbol_name, "payments.ledger_entries");
+        assert_eq!((ledger.line_start, ledger.line_end), (2, 17));
+        let meta = &ledger.metadata;
+        assert_eq!(meta["operation"], "create");
+        assert_eq!(meta["primary_key"], json!(["id"]));
+        assert_eq!(
+            meta["columns"][2],
+            json!({ "name": "amount", "type": "NUMERIC(12,2)", "nullable": false, "default": "0" })
+        );
+        assert_eq!(
+            meta["foreign_keys"],
+            json!([{ "columns": ["account_id"], "references_table": "accounts", "references_columns": ["id"] }])
+        );
+        assert_eq!(
+            meta["indexes"],
+            json!([{ "name": "ledger_entries_account_idx", "columns": ["account_id", "id"], "unique": true }])
+        );
+
+        let accounts = &docs[1];
+        assert_eq!(
+            accounts.signature.as_deref(),
+            Some("ALTER TABLE accounts (closed_at TIMESTAMPTZ, -legacy_code)")
+        );
+        assert_eq!((accounts.line_start, accounts.line_end), (19, 20));
+    }
+
+    #[test]
+    fn liquibase_changelogs() {
+        let yaml = "\
+databaseChangeLog:
+  - changeSet:
+      id: 1
+      author: dev
+      changes:
+        - createTable:
+            tableName: ledger_entries
+            columns:
+              - column:
+                  name: id
+                  type: bigint
+                  constraints:
+                    primaryKey: true
+              - column:
+                  name: account_id
+                  type: bigint
+                  constraints:
+                    nullable: false
+                    references: accounts(id)
+                    foreignKeyName: fk_ledger_account
+";
+        let docs = parse(
+            &LiquibaseLanguage,
+            "db/changelog/db.changelog-master.yaml",
+            yaml,
+        );
+        assert_eq!(docs.len(), 1);
+        assert_eq!((docs[0].line_start, docs[0].line_end), (6, 20));
+        assert_eq!(
+            docs[0].signature.as_deref(),
+            Some("CREATE TABLE ledger_entries (id bigint PK, account_id bigint -> accounts)")
+        );
+
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<databaseChangeLog xmlns="http://www.liquibase.org/xml/ns/dbchangelog">
+    <changeSet id="2" author="dev">
+        <addColumn tableName="ledger_entries" schemaName="payments">
+            <column name="memo" type="varchar(255)"/>
+        </addColumn>
+        <createIndex indexName="ledger_memo_idx" tableName="ledger_entries" schemaName="payments">
+            <column name="memo"/>
+        </createIndex>
+    </changeSet>
+</databaseChangeLog>
+"#;
+        let docs = parse(&LiquibaseLanguage, "changelog-2.xml", xml);
+        assert_eq!(docs.len(), 1);
+        let meta = &docs[0].metadata;
+        assert_eq!(docs[0].symbol_name, "payments.ledger_entries");
+        assert_eq!(meta["operation"], "alter");
+        assert_eq!(meta["columns"][0]["nullable"], true);
+        assert_eq!(meta["indexes"][0]["columns"], json!(["memo"]));
+        assert_eq!((docs[0].line_start, docs[0].line_end), (4, 8));
+    }
+}
@@ -0,0 +1,397 @@
+//! ts_parser.rs
+//!
+//! TypeScript and JavaScript support for the language registry:
+//! - `.ts` → tree-sitter-typescript, `.tsx` → its TSX grammar
+//! - `.js`/`.jsx`/`.mjs`/`.cjs` → tree-sitter-javascript (which parses JSX)
+//!
+//! All of them share one set of extraction rules. Documents include:
+//! - function declarations (incl. generators)
+//! - `const` bindings to arrow functions / function expressions at module level
+//! - classes (incl. `abstract`), their `method_definition`s and arrow-function
+//!   fields (methods, parent_type = class name)
+//! - exported interfaces, type aliases and enums
+//! - Express routes (`app.get("/orders", handler)`) as `endpoint` documents
+//!   (see `routes.rs`)
+//! - `fetch`/axios calls as `http_call` documents (see `http_calls.rs`)
+//! - kafkajs / nats.js topic usages as `topic` documents (see `messaging.rs`)
+//! - SQL string and template literals as `sql_query` documents (see `sql.rs`)
+//!
+//! Notes:
+//! - A declaration's `export` / `const` wrapper is part of its code, signature
+//!   and line range, so JSDoc above `export ...` still attaches to it.
+//! - Doc comments are the JSDoc `/** ... */` block directly above.
+//! - Signatures are the declaration header without its body, whitespace-normalized.
+//! - qualified_name is the module path + parent_type + name, `.`-joined
+//!   (`src/orders.OrderService.get`).
+
+use crate::ingest::http_calls;
+use crate::ingest::language::{LanguageSupport, SourceFile};
+use crate::ingest::messaging;
+use crate::ingest::routes;
+use crate::ingest::rust_parser::{
+    Document, DocumentKind, ParseLanguage, block_doc_comment_above, lines_of, node_text,
+    normalize_ws,
+};
+use crate::ingest::sql;
+use std::path::Path;
+use tree_sitter::Node;
+
+/// One TypeScript/JavaScript dialect; registered once per grammar
+/// (`TYPESCRIPT`, `TSX`, `JAVASCRIPT`).
+pub struct ScriptLanguage {
+    name: &'static str,
+    extensions: &'static [&'static str],
+    grammar: ParseLanguage,
+}
+
+impl ScriptLanguage {
+    pub const TYPESCRIPT: Self = Self {
+        name: "typescript",
+        extensions: &["ts", "mts", "cts"],
+        grammar: ParseLanguage::TypeScript,
+    };
+
+    pub const TSX: Self = Self {
+        name: "tsx",
+        extensions: &["tsx"],
+        grammar: ParseLanguage::Tsx,
+    };
+
+    pub const JAVASCRIPT: Self = Self {
+        name: "javascript",
+        extensions: &["js", "jsx", "mjs", "cjs"],
+        grammar: ParseLanguage::JavaScript,
+    };
+}
+
+impl LanguageSupport for ScriptLanguage {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        self.extensions
+    }
+
+    fn grammar(&self) -> Option<ParseLanguage> {
+        Some(self.grammar)
+    }
+
+    fn visit(&self, file: &SourceFile, node: Node, out: &mut Vec<Document>) {
+        visit_node(file, node, out);
+    }
+
+    fn file_module(&self, file: &SourceFile, _root: Node) -> Option<String> {
+        Some(module_specifier(file.file_path))
+    }
+
+    fn path_separator(&self) -> &'static str {
+        "."
+    }
+
+    fn import_kinds(&self) -> &'static [&'static str] {
+        &["import_statement"]
+    }
+}
+
+/// Import-style module path of a file: extension and a trailing `/index`
+/// dropped (`src/orders/index.ts` → `src/orders`).
+fn module_specifier(file_path: &str) -> String {
+    let stem = Path::new(file_path)
+        .with_extension("")
+        .to_string_lossy()
+        .replace('\\', "/");
+    match stem.strip_suffix("/index") {
+        Some(dir) => dir.to_string(),
+        None => stem,
+    }
+}
+
+/// Extraction rules shared by every JS/TS dialect.
+fn visit_node(file: &SourceFile, node: Node, out: &mut Vec<Document>) {
+    let source = file.source;
+    match node.kind() {
+        "function_declaration" | "generator_function_declaration" => {
+            out.extend(named(file, node, DocumentKind::Function, None));
+        }
+        "class_declaration" | "abstract_class_declaration" => {
+            out.extend(named(file, node, DocumentKind::Class, None));
+        }
+        "method_definition" => {
+            out.extend(named(
+                file,
+                node,
+                DocumentKind::Method,
+                enclosing_class(source, node),
+            ));
+        }
+        // `handler = (req) => ...` inside a class body
+        "public_field_definition" | "field_definition" if has_function_value(node) => {
+            let name = node
+                .child_by_field_name("name")
+                .or_else(|| node.child_by_field_name("property"));
+            if let Some(name) = name {
+                out.push(build_document(
+                    file,
+                    node,
+                    node,
+                    node_text(source, name),
+                    DocumentKind::Method,
+                    enclosing_class(source, node),
+                ));
+            }
+        }
+        "lexical_declaration" if is_module_level_const(source, node) => {
+            for i in 0..node.named_child_count() {
+                if let Some(decl) = node.named_child(i)
+                    && decl.kind() == "variable_declarator"
+                    && has_function_value(decl)
+                    && let Some(name) = decl.child_by_field_name("name")
+                {
+                    out.push(build_document(
+                        file,
+                        outer_statement(node),
+                        decl,
+                        node_text(source, name),
+                        DocumentKind::Function,
+                        None,
+                    ));
+                }
+            }
+        }
+        "interface_declaration" | "type_alias_declaration" | "enum_declaration"
+            if is_exported(node) =>
+        {
+            let kind = match node.kind() {
+                "interface_declaration" => DocumentKind::Interface,
+                "type_alias_declaration" => DocumentKind::TypeAlias,
+                _ => DocumentKind::Enum,
+            };
+            out.extend(named(file, node, kind, None));
+        }
+        // Express `app.get("/path", handler)`, `fetch`/axios calls, broker clients
+        "call_expression" => {
+            routes::express_route(file, node, out);
+            http_calls::js_http_call(file, node, out);
+            messaging::js_topic_usage(file, node, out);
+        }
+        // `pool.query("SELECT ...")`, knex.raw, prisma.$queryRaw`...`
+        "string" | "template_string" => sql::sql_query(file, node, out),
+        _ => {}
+    }
+}
+
+/// Document for a declaration carrying a `name` field.
+fn named(
+    file: &SourceFile,
+    node: Node,
+    kind: DocumentKind,
+    parent_type: Option<String>,
+) -> Option<Document> {
+    let name = node.child_by_field_name("name")?;
+    Some(build_document(
+        file,
+        outer_statement(node),
+        node,
+        node_text(file.source, name),
+        kind,
+        parent_type,
+    ))
+}
+
+/// `outer` is the full statement (incl. `export`/`const`) used for code, lines,
+/// signature start and JSDoc; `decl` is the node whose body ends the signature.
+fn build_document(
+    file: &SourceFile,
+    outer: Node,
+    decl: Node,
+    symbol_name: String,
+    kind: DocumentKind,
+    parent_type: Option<String>,
+) -> Document {
+    let source = file.source;
+    let (line_start, line_end) = lines_of(&outer);
+    Document {
+        repo: file.repo.to_string(),
+        file_path: file.file_path.to_string(),
+        symbol_name,
+        kind,
+        signature: Some(normalize_ws(
+            &source[outer.start_byte()..body_start(decl).unwrap_or(outer.end_byte())],
+        )),
+        doc_comment: block_doc_comment_above(source, outer),
+        code: node_text(source, outer),
+        parent_type,
+        module_path: None,
+        qualified_name: None,
+        annotations: Vec::new(),
+        metadata: Default::default(),
+        line_start,
+        line_end,
+    }
+}
+
+/// Start of the body that the signature stops at: a function/class/enum body,
+/// or the body of the function bound by a declarator/field.
+fn body_start(decl: Node) -> Option<usize> {
+    if let Some(value) = decl.child_by_field_name("value")
+        && is_function(value)
+    {
+        return body_start(value);
+    }
+    decl.child_by_field_name("body").map(|b| b.start_byte())
+}
+
+/// The statement to attribute a declaration to: the `export` wrapper if any.
+fn outer_statement(node: Node) -> Node {
+    match node.parent() {
+        Some(p) if p.kind() == "export_statement" => p,
+        _ => node,
+    }
+}
+
+fn is_exported(node: Node) -> bool {
+    node.parent()
+        .is_some_and(|p| p.kind() == "export_statement")
+}
+
+/// `const` declaration directly at module level (possibly exported).
+fn is_module_level_const(source: &str, node: Node) -> bool {
+    let keyword = node.child(0).map(|k| node_text(source, k));
+    let parent = outer_statement(node).parent();
+    keyword.as_deref() == Some("const") && parent.is_some_and(|p| p.kind() == "program")
+}
+
+fn has_function_value(node: Node) -> bool {
+    node.child_by_field_name("value").is_some_and(is_function)
+}
+
+fn is_function(node: Node) -> bool {
+    matches!(
+        node.kind(),
+        "arrow_function" | "function_expression" | "function" | "generator_function"
+    )
+}
+
+/// Name of the class whose body contains `node`.
+fn enclosing_class(source: &str, node: Node) -> Option<String> {
+    let body = node.parent().filter(|p| p.kind() == "class_body")?;
+    let class = body.parent()?;
+    class
+        .child_by_field_name("name")
+        .map(|n| node_text(source, n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::language::fixtures::{find, parse};
+
+    #[test]
+    fn typescript_declarations() {
+        let src = r#"
+/**
+ * Adds two numbers.
+ */
+export function add(a: number, b: number): number {
+  return a + b;
+}
+
+/** Order service. */
+export default class OrderService extends Base {
+  /** Loads one order. */
+  async get(id: string): Promise<Order> {
+    return this.repo.get(id);
+  }
+  private onEvent = (e: Event) => this.handle(e);
+}
+
+export const handler = async (req: Request) => {
+  return ok();
+};
+let notConst = () => 1;
+
+export interface Order { id: string }
+export type OrderId = string;
+export enum Status { Open, Closed }
+interface Internal {}
+
+function outer() {
+  const inner = () => 1;
+}
+"#;
+        let docs = parse(&ScriptLanguage::TYPESCRIPT, "src/orders.ts", src);
+        let names: Vec<&str> = docs.iter().map(|d| d.symbol_name.as_str()).collect();
+        assert!(!names.contains(&"notConst"));
+        assert!(!names.contains(&"Internal"));
+        assert!(!names.contains(&"inner"));
+
+        let add = find(&docs, "add");
+        assert_eq!(add.kind, DocumentKind::Function);
+        assert_eq!(add.doc_comment.as_deref(), Some("Adds two numbers."));
+        assert_eq!(
+            add.signature.as_deref(),
+            Some("export function add(a: number, b: number): number")
+        );
+
+        let svc = find(&docs, "OrderService");
+        assert_eq!(svc.kind, DocumentKind::Class);
+        assert_eq!(svc.doc_comment.as_deref(), Some("Order service."));
+
+        let get = find(&docs, "get");
+        assert_eq!(get.kind, DocumentKind::Method);
+        assert_eq!(get.parent_type.as_deref(), Some("OrderService"));
+        assert_eq!(get.doc_comment.as_deref(), Some("Loads one order."));
+        assert_eq!(
+            get.qualified_name.as_deref(),
+            Some("src/orders.OrderService.get")
+        );
+        assert_eq!(
+            find(&docs, "onEvent").parent_type.as_deref(),
+            Some("OrderService")
+        );
+
+        let handler = find(&docs, "handler");
+        assert_eq!(handler.kind, DocumentKind::Function);
+        assert_eq!(
+            handler.signature.as_deref(),
+            Some("export const handler = async (req: Request) =>")
+        );
+
+        assert_eq!(find(&docs, "Order").kind, DocumentKind::Interface);
+        assert_eq!(find(&docs, "OrderId").kind, DocumentKind::TypeAlias);
+        assert_eq!(find(&docs, "Status").kind, DocumentKind::Enum);
+    }
+
+    #[test]
+    fn tsx_and_jsx_components() {
+        let tsx = r#"
+export const App = ({ name }: Props) => <div>{name}</div>;
+export function Page() { return <App name="x" />; }
+"#;
+        let docs = parse(&ScriptLanguage::TSX, "src/App.tsx", tsx);
+        assert_eq!(find(&docs, "App").kind, DocumentKind::Function);
+        assert_eq!(find(&docs, "Page").kind, DocumentKind::Function);
+
+        let jsx = r#"
+/** Button. */
+const Button = (props) => <button>{props.label}</button>;
+class Form extends React.Component {
+  render() { return <Button label="ok" />; }
+}
+module.exports = { Button };
+"#;
+        let docs = parse(&ScriptLanguage::JAVASCRIPT, "src/Button.jsx", jsx);
+        assert_eq!(
+            find(&docs, "Button").doc_comment.as_deref(),
+            Some("Button.")
+        );
+        assert_eq!(find(&docs, "render").parent_type.as_deref(), Some("Form"));
+
+        let docs = parse(&ScriptLanguage::JAVASCRIPT, "src/orders/index.js", jsx);
+        assert_eq!(
+            find(&docs, "Button").qualified_name.as_deref(),
+            Some("src/orders.Button")
+        );
+    }
+}
@@ -1,43 +1,23 @@
+mod cli;
 pub mod client;
+mod config;
 mod index;
 mod indexing;
 mod inference;
 pub mod ingest;
 mod terminal;
 pub mod transform;
+mod watch;
 
-use anyhow::{Context, Result};
-use std::fmt::{Display, Formatter};
-// -------- hardcoded config (per your requirements) --------
-
-const QDRANT_URL: &str = "http://localhost:6333";
-const EMBED_BASE_MODEL: &str = "text-embedding-embeddinggemma-300m"; // change as needed
-const VECTOR_SIZE: usize = 768; // must match model
-
-const INCLUDE_FILENAME_DOC: bool = true;
-const EMBED_BATCH: usize = 64;
-const UPSERT_BATCH: usize = 64;
-const UPSERT_RETRIES: usize = 3;
-
-// daemon-ish loop controls
-const RESCAN_INTERVAL_SECS: u64 = 600; // 10 minutes (safe default)
-
-// ----------------------------------------------------------
-
-#[derive(Debug, Clone)]
-enum Mode {
-    Index,
-    Query,
-    Thing,
-}
-
-impl Display for Mode {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
-    }
-}
+use clap::Parser;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    terminal::terminal().await
+    let cli = cli::Cli::parse();
+    let config = config::Config::load(&cli.overrides)?;
+
+    match cli.command {
+        Some(command) => Ok(cli::run(&config, command).await?),
+        None => terminal::terminal(&config).await,
+    }
 }
@@ -1,31 +1,26 @@
-use inquire::{Select, Text};
+use inquire::{Confirm, Select, Text};
 
-use crate::client::llm_client::ask_llm;
-use crate::indexing;
-use crate::inference::rag;
-use anyhow::Result;
+use crate::cli::{self, Command};
+use crate::config::Config;
 use std::fmt::{Display, Formatter};
-// -------- hardcoded config (per your requirements) --------
-
-const QDRANT_URL: &str = "http://localhost:6333";
-const EMBED_BASE_MODEL: &str = "text-embedding-embeddinggemma-300m"; // change as needed
-const VECTOR_SIZE: usize = 768; // must match model
-
-const INCLUDE_FILENAME_DOC: bool = true;
-const EMBED_BATCH: usize = 64;
-const UPSERT_BATCH: usize = 64;
-const UPSERT_RETRIES: usize = 3;
-
-// daemon-ish loop controls
-const RESCAN_INTERVAL_SECS: u64 = 600; // 10 minutes (safe default)
-
-// ----------------------------------------------------------
+use std::path::PathBuf;
 
 #[derive(Debug, Clone)]
 enum Mode {
     Index,
+    Watch,
     Query,
-    Thing,
+    Lookup,
+    Routes,
+    Callers,
+    Dependencies,
+    Consumers,
+    Producers,
+    Table,
+    Preview,
+    Ask,
+    Collections,
+    Drop,
 }
 
 impl Display for Mode {
@@ -34,33 +29,132 @@ impl Display for Mode {
     }
 }
 
-pub async fn terminal() -> Result<(), Box<dyn std::error::Error>> {
-    // First choice: Index or Query
-    let mode = Select::new("Select operation:", vec![Mode::Query, Mode::Index]).prompt()?;
+/// Interactive fallback used when no subcommand is given. Each prompt maps
+/// onto the flags of the equivalent subcommand.
+pub async fn terminal(config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    let mode = Select::new(
+        "Select operation:",
+        vec![
+            Mode::Query,
+            Mode::Lookup,
+            Mode::Routes,
+            Mode::Callers,
+            Mode::Dependencies,
+            Mode::Consumers,
+            Mode::Producers,
+            Mode::Table,
+            Mode::Preview,
+            Mode::Index,
+            Mode::Watch,
+            Mode::Ask,
+            Mode::Collections,
+            Mode::Drop,
+        ],
+    )
+    .prompt()?;
 
-    match mode {
+    let command = match mode {
         Mode::Index => {
-            let path = Text::new("Enter path to index:").prompt()?;
-            indexing::index(&path).await?;
+            let path = Text::new("Enter path to index:")
+                .with_default(".")
+                .prompt()?;
+            Command::Index {
+                path: PathBuf::from(path),
+                no_prune: false,
+            }
+        }
+        Mode::Watch => {
+            let path = Text::new("Enter path to watch:")
+                .with_default(".")
+                .prompt()?;
+            Command::Watch {
+                path: PathBuf::from(path),
+                interval_secs: None,
+                no_prune: false,
+            }
         }
         Mode::Query => {
+            let collection = Text::new("Enter collection name:").prompt()?;
+            let repo = Text::new("Enter repository name:")
+                .with_default("*")
+                .prompt()?;
+            let text = Text::new("Enter query:").prompt()?;
+            Command::Query {
+                collection,
+                repo,
+                annotation: None,
+                text,
+            }
+        }
+        Mode::Lookup => {
+            let collection = Text::new("Enter collection name:").prompt()?;
+            let qualified_name = Text::new("Qualified name:").prompt()?;
+            Command::Lookup {
+                collection,
+                qualified_name,
+            }
+        }
+        Mode::Routes => {
+            let collection = Text::new("Enter collection name:").prompt()?;
+            let repo = Text::new("Enter repository name:")
+                .with_default("*")
+                .prompt()?;
+            Command::Routes { collection, repo }
+        }
+        Mode::Callers => {
+            let collection = Text::new("Enter collection name:").prompt()?;
+            let route = Text::new("Route (e.g. POST /orders):").prompt()?;
+            Command::Callers {
+                collection,
+                repo: "*".into(),
+                route,
+            }
+        }
+        Mode::Dependencies => {
             let collection = Text::new("Enter collection name:").prompt()?;
             let repo = Text::new("Enter repository name:").prompt()?;
-            let prompt = Text::new("Enter query:").prompt()?;
-            let docs = rag(&prompt, &collection, &repo).await?;
-            println!("{:#?}", docs);
+            Command::Dependencies { collection, repo }
+        }
+        Mode::Consumers | Mode::Producers => {
+            let collection = Text::new("Enter collection name:").prompt()?;
+            let topic = Text::new("Topic / subject:").prompt()?;
+            if matches!(mode, Mode::Consumers) {
+                Command::Consumers { collection, topic }
+            } else {
+                Command::Producers { collection, topic }
+            }
+        }
+        Mode::Table => {
+            let collection = Text::new("Enter collection name:").prompt()?;
+            let table = Text::new("Table (e.g. payments.ledger_entries):").prompt()?;
+            Command::Table { collection, table }
+        }
+        Mode::Preview => {
+            let collection = Text::new("Enter collection name:").prompt()?;
+            let qualified_name = Text::new("Qualified name:").prompt()?;
+            Command::Preview {
+                collection,
+                qualified_name,
+                template_dir: None,
+            }
         }
-        Mode::Thing => {
+        Mode::Ask => {
             let prompt = Text::new("Prompt:").prompt()?;
-            ask_llm(
-                "http://localhost:1234/v1/chat/completions",
-                "lm-studio",
-                "qwen/qwen3-coder-30b", // Your model name
-                &prompt,
-            )
-            .await;
+            Command::Ask { prompt }
         }
-    }
+        Mode::Collections => Command::Collections,
+        Mode::Drop => {
+            let collection = Text::new("Collection to delete:").prompt()?;
+            let yes = Confirm::new(&format!("Delete collection '{collection}'?"))
+                .with_default(false)
+                .prompt()?;
+            if !yes {
+                return Ok(());
+            }
+            Command::Drop { collection, yes }
+        }
+    };
 
+    cli::run(config, command).await?;
     Ok(())
 }
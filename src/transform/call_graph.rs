@@ -0,0 +1,195 @@
+//! call_graph.rs
+//!
+//! Matches `http_call` points against the `endpoint` points of *other* repos.
+//!
+//! A call targets an endpoint when the methods agree (an endpoint's `ANY`
+//! accepts every method) and the paths are equal once path parameters are
+//! collapsed (`:id`, `{id}`, `<id>`, `*` and the calls' `{}` all compare equal).
+//! When several repos expose the route, the call's `host`/`base_url_key`
+//! narrows it down: `ORDERS_URL` or `http://orders-svc:8080` picks the repo
+//! whose name starts with `orders`. Calls that stay ambiguous keep every
+//! candidate; calls matching nothing are external (third-party APIs).
+//!
+//! The result is stored on the call points as top-level payload keys
+//! (`call_targets`, `target_routes`, `target_repos`; see
+//! `indexing::link_call_graph`) rather than in `metadata`, which every
+//! indexing pass rewrites.
+
+use crate::client::qdrant_client::ScrolledPoint;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Endpoint a call resolves to.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct CallTarget {
+    pub repo: String,
+    /// `POST /orders/{id}`, as the endpoint names itself.
+    pub route: String,
+    pub point_id: String,
+}
+
+struct Route<'a> {
+    point_id: &'a str,
+    repo: &'a str,
+    name: &'a str,
+    method: &'a str,
+    path: String,
+}
+
+/// Targets of every `http_call` among `points` (scrolled with `repo`, `type`,
+/// `symbol_name` and `metadata`), keyed by call point id. Unmatched calls map
+/// to an empty list.
+pub fn resolve_calls(points: &[ScrolledPoint]) -> HashMap<String, Vec<CallTarget>> {
+    let routes: Vec<Route> = points
+        .iter()
+        .filter(|p| p.payload["type"] == "endpoint")
+        .map(|p| Route {
+            point_id: &p.id,
+            repo: str_of(&p.payload["repo"]),
+            name: str_of(&p.payload["symbol_name"]),
+            method: str_of(&p.payload["metadata"]["method"]),
+            path: normalize_path(str_of(&p.payload["metadata"]["path"])),
+        })
+        .collect();
+
+    points
+        .iter()
+        .filter(|p| p.payload["type"] == "http_call")
+        .map(|call| {
+            let meta = &call.payload["metadata"];
+            let repo = str_of(&call.payload["repo"]);
+            let method = str_of(&meta["method"]);
+            let path = normalize_path(str_of(&meta["path"]));
+            let matches: Vec<&Route> = routes
+                .iter()
+                .filter(|r| r.repo != repo && r.path == path)
+                .filter(|r| r.method == method || r.method == "ANY")
+                .collect();
+
+            let hints = [str_of(&meta["host"]), str_of(&meta["base_url_key"])];
+            let hinted: Vec<&Route> = matches
+                .iter()
+                .copied()
+                .filter(|r| hints.iter().any(|h| repo_hinted(r.repo, h)))
+                .collect();
+            let chosen = if hinted.is_empty() { matches } else { hinted };
+
+            let mut targets: Vec<CallTarget> = chosen
+                .into_iter()
+                .map(|r| CallTarget {
+                    repo: r.repo.to_string(),
+                    route: r.name.to_string(),
+                    point_id: r.point_id.to_string(),
+                })
+                .collect();
+            targets.sort();
+            (call.id.clone(), targets)
+        })
+        .collect()
+}
+
+fn str_of(value: &serde_json::Value) -> &str {
+    value.as_str().unwrap_or_default()
+}
+
+/// `/orders/:id`, `/orders/{id}`, `/orders/<id>` → `/orders/{}`; trailing `/`
+/// dropped.
+pub fn normalize_path(path: &str) -> String {
+    let segments: Vec<&str> = path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            let param = s.starts_with(':')
+                || s == "*"
+                || (s.starts_with('{') && s.ends_with('}'))
+                || (s.starts_with('<') && s.ends_with('>'));
+            if param { "{}" } else { s }
+        })
+        .collect();
+    format!("/{}", segments.join("/"))
+}
+
+/// Whether a host / config key names `repo`: its first word (3+ chars,
+/// e.g. `orders` of `orders-service`) appears in the hint, ignoring case and
+/// punctuation.
+fn repo_hinted(repo: &str, hint: &str) -> bool {
+    let squash = |s: &str| -> String {
+        s.chars()
+            .filter(char::is_ascii_alphanumeric)
+            .map(|c| c.to_ascii_lowercase())
+            .collect()
+    };
+    let Some(word) = repo
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .find(|w| !w.is_empty())
+        .map(squash)
+        .filter(|w| w.len() >= 3)
+    else {
+        return false;
+    };
+    squash(hint).contains(&word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn endpoint(id: &str, repo: &str, method: &str, path: &str) -> ScrolledPoint {
+        ScrolledPoint {
+            id: id.into(),
+            payload: json!({
+                "repo": repo,
+                "type": "endpoint",
+                "symbol_name": format!("{method} {path}"),
+                "metadata": { "method": method, "path": path },
+            }),
+        }
+    }
+
+    fn call(id: &str, repo: &str, method: &str, path: &str, key: &str) -> ScrolledPoint {
+        ScrolledPoint {
+            id: id.into(),
+            payload: json!({
+                "repo": repo,
+                "type": "http_call",
+                "metadata": { "method": method, "path": path, "base_url_key": key, "host": null },
+            }),
+        }
+    }
+
+    #[test]
+    fn normalizes_path_params() {
+        assert_eq!(normalize_path("/orders/:id/items/"), "/orders/{}/items");
+        assert_eq!(normalize_path("/orders/{order_id}"), "/orders/{}");
+        assert_eq!(normalize_path("/files/<name>"), "/files/{}");
+        assert_eq!(normalize_path(""), "/");
+    }
+
+    #[test]
+    fn matches_calls_to_other_repos_routes() {
+        let points = vec![
+            endpoint("e1", "orders-service", "POST", "/orders"),
+            endpoint("e2", "orders-service", "GET", "/orders/:id"),
+            endpoint("e3", "legacy-orders", "GET", "/orders/{id}"),
+            endpoint("e4", "billing", "ANY", "/invoices"),
+            endpoint("e5", "billing", "POST", "/orders"),
+            call("c1", "billing", "POST", "/orders", "ORDERS_URL"),
+            call("c2", "gateway", "GET", "/orders/{}", "ORDERS_SERVICE_URL"),
+            call("c3", "gateway", "GET", "/orders/{}", "upstream"),
+            call("c4", "gateway", "DELETE", "/invoices", "BILLING_URL"),
+            call("c5", "gateway", "GET", "/v1/charges", "STRIPE_URL"),
+        ];
+        let targets = resolve_calls(&points);
+        let ids =
+            |c: &str| -> Vec<&str> { targets[c].iter().map(|t| t.point_id.as_str()).collect() };
+        // own repo's POST /orders is not a target
+        assert_eq!(ids("c1"), vec!["e1"]);
+        assert_eq!(ids("c2"), vec!["e2"]);
+        // no hint: every candidate
+        assert_eq!(ids("c3"), vec!["e3", "e2"]);
+        assert_eq!(ids("c4"), vec!["e4"]);
+        assert!(ids("c5").is_empty());
+        assert_eq!(targets["c1"][0].route, "POST /orders");
+    }
+}
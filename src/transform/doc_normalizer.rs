@@ -3,16 +3,18 @@
 //! Cleans, enriches, and canonicalizes parsed Documents before embedding/indexing.
 //!
 //! Responsibilities:
-//! - Normalize whitespace / dedent code blocks
-//! - Truncate overly large code fields (optional safeguard)
-//! - Compute stable `hash_source` (content hash)
+//! - Normalize whitespace / dedent code blocks (chunks are left verbatim)
+//! - Truncate overly large code fields (last safeguard: oversized documents
+//!   are split into chunks before, see `ingest::chunker`)
+//! - Compute stable `hash_source` (content hash) and the deterministic `point_id`
 //! - Attach `timestamp_indexed`
 //!
 //! Does not mutate `repo`, `file_path`, or line ranges.
 
 use crate::index::id_generator;
-use crate::ingest::rust_parser::Document;
+use crate::ingest::rust_parser::{Annotation, Document};
 use chrono::{DateTime, Utc};
+use std::collections::HashMap;
 
 pub struct DocNormalizer {
     pub max_code_chars: usize, // safeguard (e.g., 100_000)
@@ -27,26 +29,74 @@ impl Default for DocNormalizer {
 }
 
 impl DocNormalizer {
-    pub fn normalize(&self, doc: Document) -> NormalizedDoc {
-        // Clean code (trim, dedent, and cap length)
-        let code = normalize_code(&doc.code, self.max_code_chars);
+    /// Normalizes a batch, numbering repeats of the same (file, qualified
+    /// symbol, kind) in order of appearance so each gets a distinct point id.
+    /// Chunks count as their parent: the first takes the next ordinal, the
+    /// rest (which follow it) share it.
+    pub fn normalize_all(&self, docs: Vec<Document>) -> Vec<NormalizedDoc> {
+        let mut seen: HashMap<(String, String, &'static str), u32> = HashMap::new();
+        docs.into_iter()
+            .map(|doc| {
+                let key = (
+                    doc.file_path.clone(),
+                    qualified_symbol_of(&doc),
+                    doc.kind.as_str(),
+                );
+                let next = seen.entry(key).or_insert(0);
+                let ordinal = match chunk_index_of(&doc) {
+                    Some(i) if i > 0 => next.saturating_sub(1),
+                    _ => {
+                        *next += 1;
+                        *next - 1
+                    }
+                };
+                self.normalize(doc, ordinal)
+            })
+            .collect()
+    }
+
+    /// Normalizes a single document. `ordinal` is its index among documents
+    /// sharing the same (file, qualified symbol, kind), chunks of one parent
+    /// counted once; 0 for unique ones.
+    pub fn normalize(&self, doc: Document, ordinal: u32) -> NormalizedDoc {
+        // Clean code (trim, dedent, and cap length); chunks stay verbatim so
+        // retrieval can stitch them back into the exact symbol
+        let code = match chunk_index_of(&doc) {
+            Some(_) => doc.code.clone(),
+            None => normalize_code(&doc.code, self.max_code_chars),
+        };
         let doc_comment = doc.doc_comment.as_ref().map(|s| s.trim().to_string());
         let signature = doc.signature.as_ref().map(|s| s.trim().to_string());
 
         let hash_source =
             id_generator::content_hash(signature.as_deref(), doc_comment.as_deref(), &code);
 
+        let kind = doc.kind.as_str().to_string();
+        let point_id = id_generator::deterministic_point_id(
+            &doc.repo,
+            &doc.file_path,
+            &qualified_symbol_of(&doc),
+            &kind,
+            ordinal,
+            chunk_index_of(&doc),
+        );
+
         let timestamp_indexed = Utc::now();
 
         NormalizedDoc {
+            point_id,
             repo: doc.repo,
             file_path: doc.file_path,
             symbol_name: doc.symbol_name,
-            kind: doc.kind.as_str().to_string(),
+            kind,
             signature,
             doc_comment,
             code,
             parent_type: doc.parent_type,
+            module_path: doc.module_path,
+            qualified_name: doc.qualified_name,
+            annotations: doc.annotations,
+            metadata: doc.metadata,
             line_start: doc.line_start,
             line_end: doc.line_end,
             hash_source,
@@ -58,6 +108,8 @@ impl DocNormalizer {
 /// Canonical normalized Document ready for embedding and upsert.
 #[derive(Debug, Clone)]
 pub struct NormalizedDoc {
+    /// Qdrant point id (UUIDv5 of the canonical document key).
+    pub point_id: String,
     pub repo: String,
     pub file_path: String,
     pub symbol_name: String,
@@ -66,6 +118,10 @@ pub struct NormalizedDoc {
     pub doc_comment: Option<String>,
     pub code: String,
     pub parent_type: Option<String>,
+    pub module_path: Option<String>,
+    pub qualified_name: Option<String>,
+    pub annotations: Vec<Annotation>,
+    pub metadata: serde_json::Map<String, serde_json::Value>,
     pub line_start: u32,
     pub line_end: u32,
     pub hash_source: String,
@@ -73,6 +129,23 @@ pub struct NormalizedDoc {
 }
 
 /// ---- helpers ----
+fn qualified_symbol_of(doc: &Document) -> String {
+    id_generator::qualified_symbol(
+        doc.module_path.as_deref(),
+        doc.parent_type.as_deref(),
+        &doc.symbol_name,
+        doc.kind.as_str(),
+    )
+}
+
+/// `chunk_index` of a chunk (see `ingest::chunker`).
+fn chunk_index_of(doc: &Document) -> Option<u32> {
+    doc.metadata
+        .get("chunk_index")
+        .and_then(|v| v.as_u64())
+        .map(|i| i as u32)
+}
+
 fn normalize_code(src: &str, max_chars: usize) -> String {
     let mut s = src.trim().replace("\r\n", "\n");
     if s.len() > max_chars {
@@ -117,11 +190,95 @@ mod tests {
             doc_comment: Some("/// docs".into()),
             code: "    fn foo() {}".into(),
             parent_type: None,
+            module_path: None,
+            qualified_name: None,
+            annotations: Vec::new(),
+            metadata: Default::default(),
             line_start: 1,
             line_end: 2,
         };
-        let norm = DocNormalizer::default().normalize(doc);
+        let norm = DocNormalizer::default().normalize(doc, 0);
         assert!(norm.hash_source.len() > 10);
         assert!(norm.code.starts_with("fn foo"));
     }
+
+    #[test]
+    fn repeated_symbols_get_distinct_ids() {
+        let method = |parent: &str, line: u32| Document {
+            repo: "r".into(),
+            file_path: "src/lib.rs".into(),
+            symbol_name: "new".into(),
+            kind: DocumentKind::Method,
+            signature: Some("fn new(&self)".into()),
+            doc_comment: None,
+            code: "fn new(&self) {}".into(),
+            parent_type: Some(parent.into()),
+            module_path: None,
+            qualified_name: None,
+            annotations: Vec::new(),
+            metadata: Default::default(),
+            line_start: line,
+            line_end: line,
+        };
+        let norm = DocNormalizer::default().normalize_all(vec![
+            method("A", 1),
+            method("B", 5),
+            method("A", 9), // e.g. a second `impl A` block under another cfg
+        ]);
+        assert_ne!(norm[0].point_id, norm[1].point_id);
+        assert_ne!(norm[0].point_id, norm[2].point_id);
+        assert_ne!(norm[1].point_id, norm[2].point_id);
+        // first occurrence is unaffected by later repeats
+        assert_eq!(
+            norm[0].point_id,
+            DocNormalizer::default()
+                .normalize(method("A", 1), 0)
+                .point_id
+        );
+    }
+
+    #[test]
+    fn chunks_share_their_parent_ordinal() {
+        let chunk = |parent: &str, index: u32, count: u32| {
+            let mut doc = Document {
+                repo: "r".into(),
+                file_path: "src/lib.rs".into(),
+                symbol_name: "run".into(),
+                kind: DocumentKind::Method,
+                signature: Some("fn run(&self)".into()),
+                doc_comment: None,
+                code: format!("// part {index}"),
+                parent_type: Some(parent.into()),
+                module_path: None,
+                qualified_name: None,
+                annotations: Vec::new(),
+                metadata: Default::default(),
+                line_start: 1,
+                line_end: 1,
+            };
+            if count > 1 {
+                doc.metadata.insert("chunk_index".into(), index.into());
+                doc.metadata.insert("chunk_count".into(), count.into());
+            }
+            doc
+        };
+        let ids = |first_chunks: u32| {
+            let mut docs: Vec<Document> = (0..first_chunks)
+                .map(|i| chunk("A", i, first_chunks))
+                .collect();
+            docs.push(chunk("B", 0, 1));
+            docs.push(chunk("A", 0, 1)); // `#[cfg]` twin of the first `A::run`
+            let norm = DocNormalizer::default().normalize_all(docs);
+            let ids: Vec<String> = norm.into_iter().map(|n| n.point_id).collect();
+            ids
+        };
+        let (one, three) = (ids(1), ids(3));
+        // the twin keeps its id however many chunks the first `A::run` has
+        assert_eq!(one.last(), three.last());
+        assert_eq!(one[1], three[3]);
+        let mut unique = three.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), three.len());
+    }
 }
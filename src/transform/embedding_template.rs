@@ -0,0 +1,234 @@
+//! embedding_template.rs
+//!
+//! Renders the text embedded for each point from a minijinja template.
+//!
+//! A template sees the point's payload (`repo`, `file_path`, `type`,
+//! `symbol_name`, `qualified_name`, `parent_type`, `module_path`, `signature`,
+//! `doc_comment`, `code`, `line_start`, `line_end`, `annotations`,
+//! `annotation_names`, `metadata`) plus the file's `language`. Missing and
+//! null values render as nothing, so `{% if signature %}` keeps empty
+//! sections out; nothing is HTML-escaped.
+//!
+//! With `embedder.template_dir` set, every `*.j2` / `*.jinja` / `*.jinja2`
+//! file there is a template named after its file stem. A document uses the
+//! first of `<language>.<type>`, `<type>`, `<language>` and `default` that
+//! exists (`python.function.j2`, `endpoint.j2`, `markdown.j2`, ...);
+//! `default` falls back to `DEFAULT_TEMPLATE`.
+//!
+//! Points store the fingerprint of the template they were embedded with
+//! (`embed_template`), so editing a template re-embeds exactly its points.
+
+use minijinja::{AutoEscape, Environment, Value, escape_formatter};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use thiserror::Error;
+
+/// Built-in `default` template: header lines, then the doc comment and
+/// signature when present, then the code.
+pub const DEFAULT_TEMPLATE: &str = "\
+repo: {{ repo }}
+path: {{ file_path }}
+type: {{ type }}
+symbol: {{ symbol_name }}
+{%- if qualified_name %}
+qualified: {{ qualified_name }}
+{%- endif %}
+{%- if parent_type %}
+parent: {{ parent_type }}
+{%- endif %}
+{%- if doc_comment %}
+
+[DOC]
+{{ doc_comment }}
+{%- endif %}
+{%- if signature %}
+
+[SIGNATURE]
+{{ signature }}
+{%- endif %}
+
+[CODE]
+{{ code }}";
+
+const EXTENSIONS: &[&str] = &["j2", "jinja", "jinja2"];
+
+#[derive(Debug, Error)]
+pub enum TemplateError {
+    #[error("failed to read templates in {path}: {source}")]
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("invalid template {name}: {source}")]
+    Syntax {
+        name: String,
+        source: minijinja::Error,
+    },
+}
+
+/// The templates in use, by name.
+pub struct EmbeddingTemplates {
+    env: Environment<'static>,
+    /// Template name → hash of its source.
+    fingerprints: HashMap<String, String>,
+}
+
+impl Default for EmbeddingTemplates {
+    fn default() -> Self {
+        Self::load(None).expect("built-in template is valid")
+    }
+}
+
+impl EmbeddingTemplates {
+    /// The templates of `dir` (see the module docs), or only the built-in
+    /// default without one.
+    pub fn load(dir: Option<&Path>) -> Result<Self, TemplateError> {
+        let mut sources = HashMap::new();
+        if let Some(dir) = dir {
+            let io = |source| TemplateError::Io {
+                path: dir.display().to_string(),
+                source,
+            };
+            for entry in fs::read_dir(dir).map_err(io)? {
+                let path = entry.map_err(io)?.path();
+                let is_template = path
+                    .extension()
+                    .and_then(|x| x.to_str())
+                    .is_some_and(|x| EXTENSIONS.contains(&x));
+                let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                    continue;
+                };
+                if is_template {
+                    sources.insert(name.to_string(), fs::read_to_string(&path).map_err(io)?);
+                }
+            }
+        }
+        sources
+            .entry("default".to_string())
+            .or_insert_with(|| DEFAULT_TEMPLATE.to_string());
+
+        let mut env = Environment::new();
+        env.set_auto_escape_callback(|_| AutoEscape::None);
+        env.set_formatter(|out, state, value| {
+            if value.is_none() || value.is_undefined() {
+                Ok(())
+            } else {
+                escape_formatter(out, state, value)
+            }
+        });
+        let mut fingerprints = HashMap::new();
+        for (name, source) in sources {
+            fingerprints.insert(name.clone(), hex::encode(Sha256::digest(source.as_bytes())));
+            env.add_template_owned(name.clone(), source)
+                .map_err(|source| TemplateError::Syntax { name, source })?;
+        }
+        Ok(Self { env, fingerprints })
+    }
+
+    /// Name of the template for a document of `kind` in `language`.
+    pub fn select(&self, kind: &str, language: Option<&str>) -> &str {
+        let candidates = [
+            language.map(|l| format!("{l}.{kind}")),
+            Some(kind.to_string()),
+            language.map(str::to_string),
+        ];
+        candidates
+            .into_iter()
+            .flatten()
+            .find_map(|name| {
+                self.fingerprints
+                    .get_key_value(&name)
+                    .map(|(k, _)| k.as_str())
+            })
+            .unwrap_or("default")
+    }
+
+    /// Hash of the source of template `name`.
+    pub fn fingerprint(&self, name: &str) -> &str {
+        self.fingerprints.get(name).map_or("", String::as_str)
+    }
+
+    /// Renders template `name` for a point's payload.
+    pub fn render(
+        &self,
+        name: &str,
+        payload: &serde_json::Value,
+        language: Option<&str>,
+    ) -> Result<String, minijinja::Error> {
+        let mut context = payload.clone();
+        context["language"] = serde_json::json!(language);
+        self.env
+            .get_template(name)?
+            .render(Value::from_serialize(&context))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn default_template_skips_empty_sections() {
+        let templates = EmbeddingTemplates::default();
+        let payload = json!({
+            "repo": "billing",
+            "file_path": "src/ledger.rs",
+            "type": "function",
+            "symbol_name": "post",
+            "qualified_name": "billing::ledger::post",
+            "parent_type": null,
+            "doc_comment": null,
+            "signature": "pub fn post(entry: Entry)",
+            "code": "pub fn post(entry: Entry) {}",
+            "line_start": 10,
+        });
+        let input = templates.render("default", &payload, Some("rust")).unwrap();
+        assert_eq!(
+            input,
+            "repo: billing\npath: src/ledger.rs\ntype: function\nsymbol: post\n\
+             qualified: billing::ledger::post\n\n\
+             [SIGNATURE]\npub fn post(entry: Entry)\n\n\
+             [CODE]\npub fn post(entry: Entry) {}"
+        );
+    }
+
+    #[test]
+    fn picks_the_most_specific_template() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, source: &str| fs::write(dir.path().join(name), source).unwrap();
+        write("python.function.j2", "{{ language }} fn {{ symbol_name }}");
+        write(
+            "endpoint.j2",
+            "{{ metadata.method }} {{ metadata.path }}{{ parent_type }}",
+        );
+        write("rust.jinja", "<{{ code }}>");
+        write("notes.txt", "not a template");
+
+        let templates = EmbeddingTemplates::load(Some(dir.path())).unwrap();
+        assert_eq!(
+            templates.select("function", Some("python")),
+            "python.function"
+        );
+        assert_eq!(templates.select("endpoint", Some("python")), "endpoint");
+        assert_eq!(templates.select("function", Some("rust")), "rust");
+        assert_eq!(templates.select("function", Some("go")), "default");
+        assert_eq!(templates.fingerprint("default").len(), 64);
+
+        let endpoint = json!({ "metadata": { "method": "GET", "path": "/orders" } });
+        assert_eq!(
+            templates.render("endpoint", &endpoint, None).unwrap(),
+            "GET /orders"
+        );
+        let function = json!({ "symbol_name": "get_price" });
+        assert_eq!(
+            templates
+                .render("python.function", &function, Some("python"))
+                .unwrap(),
+            "python fn get_price"
+        );
+    }
+}
@@ -1 +1,5 @@
+pub(crate) mod call_graph;
 pub(crate) mod doc_normalizer;
+pub(crate) mod embedding_template;
+pub(crate) mod route_linker;
+pub(crate) mod topic_graph;
@@ -0,0 +1,179 @@
+//! route_linker.rs
+//!
+//! Links `endpoint` documents found in code to their handler's point.
+//!
+//! The handler recorded at the route (`metadata.handler`, as written there:
+//! `handlers::show`, `OrderController.get`, `controller.update`) is matched
+//! against the repo's function/method documents by qualified-name suffix
+//! (leading `crate`/`self`/`super` dropped):
+//! - a unique match in the route's own file wins, then a unique match in the repo
+//! - failing that, a unique same-file function/method with the handler's bare name
+//!
+//! A handler split into chunks (see `ingest::chunker`) is linked through its
+//! first chunk.
+//!
+//! Runs after every pass over the collection's stored points (see
+//! `indexing::link_route_handlers`), so a watch pass over one changed file
+//! still resolves handlers in the repo's other files. The match is stored as
+//! the top-level payload key `handler_point_id` rather than in `metadata`,
+//! which every indexing pass rewrites.
+
+use crate::client::qdrant_client::ScrolledPoint;
+use std::collections::HashMap;
+
+struct Candidate<'a> {
+    repo: &'a str,
+    file_path: &'a str,
+    segments: Vec<String>,
+    point_id: &'a str,
+}
+
+/// The handler point id of every `endpoint` with a `metadata.handler` among
+/// `points` (scrolled with `repo`, `file_path`, `type`, `symbol_name`,
+/// `qualified_name` and `metadata`), keyed by endpoint point id; `None` when
+/// the handler does not resolve.
+pub fn route_handlers(points: &[ScrolledPoint]) -> HashMap<String, Option<String>> {
+    let candidates: Vec<Candidate> = points
+        .iter()
+        .filter(|p| p.payload["type"] == "function" || p.payload["type"] == "method")
+        .filter(|p| {
+            let index = &p.payload["metadata"]["chunk_index"];
+            index.is_null() || *index == 0
+        })
+        .map(|p| Candidate {
+            repo: str_field(p, "repo"),
+            file_path: str_field(p, "file_path"),
+            segments: segments(
+                p.payload["qualified_name"]
+                    .as_str()
+                    .unwrap_or(str_field(p, "symbol_name")),
+            ),
+            point_id: &p.id,
+        })
+        .collect();
+
+    let mut links = HashMap::new();
+    for p in points.iter().filter(|p| p.payload["type"] == "endpoint") {
+        let Some(handler) = p.payload["metadata"]["handler"].as_str() else {
+            continue;
+        };
+        let (repo, file_path) = (str_field(p, "repo"), str_field(p, "file_path"));
+        let wanted = segments(handler);
+        let linked = wanted.last().and_then(|name| {
+            let in_repo: Vec<&Candidate> = candidates.iter().filter(|c| c.repo == repo).collect();
+            let suffix: Vec<&Candidate> = in_repo
+                .iter()
+                .copied()
+                .filter(|c| c.segments.ends_with(&wanted))
+                .collect();
+            let by_name: Vec<&Candidate> = in_repo
+                .iter()
+                .copied()
+                .filter(|c| c.segments.last() == Some(name))
+                .collect();
+            unique(suffix.iter().filter(|c| c.file_path == file_path))
+                .or_else(|| unique(suffix.iter()))
+                .or_else(|| unique(by_name.iter().filter(|c| c.file_path == file_path)))
+                .map(|c| c.point_id.to_string())
+        });
+        links.insert(p.id.clone(), linked);
+    }
+    links
+}
+
+fn str_field<'p>(point: &'p ScrolledPoint, key: &str) -> &'p str {
+    point.payload[key].as_str().unwrap_or_default()
+}
+
+fn unique<'a, 'p>(
+    mut matches: impl Iterator<Item = &'a &'a Candidate<'p>>,
+) -> Option<&'a Candidate<'p>> {
+    let first = matches.next()?;
+    matches.next().is_none().then_some(*first)
+}
+
+/// `crate::handlers::show` → `[handlers, show]`; `.` and `::` both separate.
+fn segments(path: &str) -> Vec<String> {
+    let segments: Vec<String> = path
+        .split("::")
+        .flat_map(|p| p.split('.'))
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect();
+    let skip = segments
+        .iter()
+        .take_while(|s| matches!(s.as_str(), "crate" | "self" | "super"))
+        .count();
+    segments[skip..].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn function(id: &str, file: &str, kind: &str, qualified: &str) -> ScrolledPoint {
+        ScrolledPoint {
+            id: id.into(),
+            payload: json!({
+                "repo": "orders",
+                "file_path": file,
+                "type": kind,
+                "symbol_name": qualified.rsplit("::").next().unwrap(),
+                "qualified_name": qualified,
+                "metadata": {},
+            }),
+        }
+    }
+
+    fn route(id: &str, file: &str, handler: &str) -> ScrolledPoint {
+        ScrolledPoint {
+            id: id.into(),
+            payload: json!({
+                "repo": "orders",
+                "file_path": file,
+                "type": "endpoint",
+                "metadata": { "handler": handler },
+            }),
+        }
+    }
+
+    #[test]
+    fn links_handlers_by_suffix_then_file() {
+        let mut second_chunk = function("chunk-1", "src/api.rs", "function", "orders::api::create");
+        second_chunk.payload["metadata"] = json!({ "chunk_index": 1 });
+        let points = vec![
+            function(
+                "show",
+                "src/handlers.rs",
+                "function",
+                "orders::handlers::show",
+            ),
+            function(
+                "admin-show",
+                "src/admin.rs",
+                "function",
+                "orders::admin::show",
+            ),
+            function("create", "src/api.rs", "function", "orders::api::create"),
+            second_chunk,
+            function(
+                "update",
+                "src/api.rs",
+                "method",
+                "orders::api::Controller::update",
+            ),
+            route("r1", "src/api.rs", "crate::handlers::show"),
+            route("r2", "src/api.rs", "create"),
+            route("r3", "src/api.rs", "controller.update"),
+            route("r4", "src/api.rs", "show"),
+        ];
+        let links = route_handlers(&points);
+        assert_eq!(links["r1"].as_deref(), Some("show"));
+        assert_eq!(links["r2"].as_deref(), Some("create"));
+        assert_eq!(links["r3"].as_deref(), Some("update"));
+        assert_eq!(links["r4"], None);
+        assert_eq!(links.len(), 4);
+    }
+}
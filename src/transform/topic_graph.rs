@@ -0,0 +1,129 @@
+//! topic_graph.rs
+//!
+//! Aggregates the `topic` points of all repos into per-topic participant
+//! lists: every usage of `order.created` learns which repos publish to it and
+//! which subscribe to it.
+//!
+//! NATS wildcards are honoured in both directions: a subscription to
+//! `orders.*` (one token) or `orders.>` (one or more tokens) makes its repo a
+//! subscriber of `orders.created`, and a usage of the pattern sees the
+//! publishers of every subject it covers.
+//!
+//! The lists are stored on the topic points as top-level payload keys
+//! (`publishers`, `subscribers`; see `indexing::link_topics`) rather than in
+//! `metadata`, which every indexing pass rewrites.
+
+use crate::client::qdrant_client::ScrolledPoint;
+use std::collections::{BTreeSet, HashMap};
+
+/// Repos on each side of a topic.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Participants {
+    pub publishers: BTreeSet<String>,
+    pub subscribers: BTreeSet<String>,
+}
+
+/// Participants of the topic of every `topic` point among `points` (scrolled
+/// with `repo`, `type` and `metadata`), keyed by point id.
+pub fn topic_participants(points: &[ScrolledPoint]) -> HashMap<String, Participants> {
+    let usages: Vec<(&str, &str, &str, &str)> = points
+        .iter()
+        .filter(|p| p.payload["type"] == "topic")
+        .map(|p| {
+            let meta = &p.payload["metadata"];
+            (
+                p.id.as_str(),
+                p.payload["repo"].as_str().unwrap_or_default(),
+                meta["topic"].as_str().unwrap_or_default(),
+                meta["role"].as_str().unwrap_or_default(),
+            )
+        })
+        .collect();
+
+    usages
+        .iter()
+        .map(|(id, _, topic, _)| {
+            let mut participants = Participants::default();
+            for (_, repo, other, role) in &usages {
+                if !topics_overlap(topic, other) {
+                    continue;
+                }
+                let side = match *role {
+                    "publish" => &mut participants.publishers,
+                    "subscribe" => &mut participants.subscribers,
+                    _ => continue,
+                };
+                side.insert(repo.to_string());
+            }
+            (id.to_string(), participants)
+        })
+        .collect()
+}
+
+/// Whether two topics / subjects can carry the same messages.
+pub fn topics_overlap(a: &str, b: &str) -> bool {
+    a == b || subject_matches(a, b) || subject_matches(b, a)
+}
+
+/// NATS subject matching: `*` is one token, a trailing `>` one or more.
+pub fn subject_matches(pattern: &str, subject: &str) -> bool {
+    let mut subject_tokens = subject.split('.');
+    for token in pattern.split('.') {
+        match (token, subject_tokens.next()) {
+            (">", Some(_)) => return true,
+            ("*", Some(_)) => {}
+            (t, Some(s)) if t == s => {}
+            _ => return false,
+        }
+    }
+    subject_tokens.next().is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn usage(id: &str, repo: &str, topic: &str, role: &str) -> ScrolledPoint {
+        ScrolledPoint {
+            id: id.into(),
+            payload: json!({
+                "repo": repo,
+                "type": "topic",
+                "metadata": { "topic": topic, "role": role },
+            }),
+        }
+    }
+
+    #[test]
+    fn nats_wildcards() {
+        assert!(subject_matches("orders.*", "orders.created"));
+        assert!(!subject_matches("orders.*", "orders.created.v2"));
+        assert!(subject_matches("orders.>", "orders.created.v2"));
+        assert!(!subject_matches("orders.>", "orders"));
+        assert!(!subject_matches("orders.created", "orders"));
+    }
+
+    #[test]
+    fn collects_publishers_and_subscribers_per_topic() {
+        let points = vec![
+            usage("p1", "orders", "orders.created", "publish"),
+            usage("s1", "billing", "orders.created", "subscribe"),
+            usage("s2", "audit", "orders.>", "subscribe"),
+            usage("s3", "billing", "payments.charged", "subscribe"),
+        ];
+        let graph = topic_participants(&points);
+        let set = |repos: &[&str]| repos.iter().map(|r| r.to_string()).collect();
+
+        assert_eq!(graph["p1"].publishers, set(&["orders"]));
+        assert_eq!(graph["p1"].subscribers, set(&["audit", "billing"]));
+        assert_eq!(graph["s2"].publishers, set(&["orders"]));
+        assert_eq!(
+            graph["s3"],
+            Participants {
+                publishers: set(&[]),
+                subscribers: set(&["billing"]),
+            }
+        );
+    }
+}
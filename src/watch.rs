@@ -0,0 +1,247 @@
+//! watch.rs
+//!
+//! Long-running daemon mode on top of `indexing`:
+//! - initial full pass, then a full rescan every `rescan_interval_secs`
+//! - filesystem notifications in between; changes are debounced, grouped per
+//!   repo and only the touched files are re-parsed
+//! - SIGINT/SIGTERM stop the loop; an in-flight pass is dropped at its next
+//!   await point (passes are idempotent, so the next start simply redoes it)
+
+use crate::config::Config;
+use crate::indexing::{
+    IndexContext, ensure_collection, index_repo, link_across_repos, project_root, tick_once,
+};
+use anyhow::{Context, Result};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::MissedTickBehavior;
+
+pub async fn watch(config: &Config, p: &Path) -> Result<()> {
+    let root = project_root(p)?;
+    let ctx = IndexContext::new(config, &root)?;
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<notify::Result<Event>>();
+    let mut watcher = notify::recommended_watcher(move |res| {
+        // receiver gone means we're shutting down
+        let _ = tx.send(res);
+    })
+    .context("failed to create filesystem watcher")?;
+    watcher
+        .watch(&root, RecursiveMode::Recursive)
+        .with_context(|| format!("failed to watch {}", root.display()))?;
+
+    let interval_secs = config.indexing.rescan_interval_secs.max(1);
+    let debounce = Duration::from_millis(config.indexing.watch_debounce_ms);
+    eprintln!(
+        "Watching project:\n  root: {}\n  collection: {}\n  rescan: every {interval_secs}s\n",
+        root.display(),
+        ctx.collection
+    );
+
+    // first tick completes immediately → initial full pass
+    let mut rescan = tokio::time::interval(Duration::from_secs(interval_secs));
+    rescan.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            _ = rescan.tick() => {
+                eprintln!("[watch] periodic rescan");
+                tokio::select! {
+                    _ = tick_once(&ctx, &root) => {}
+                    _ = &mut shutdown => break,
+                }
+            }
+            Some(first) = rx.recv() => {
+                let mut changes = ChangeSet::default();
+                changes.record(&root, first);
+
+                // debounce: keep collecting until the burst settles
+                let settle = tokio::time::sleep(debounce);
+                tokio::pin!(settle);
+                loop {
+                    tokio::select! {
+                        _ = &mut settle => break,
+                        Some(next) = rx.recv() => changes.record(&root, next),
+                    }
+                }
+                if changes.is_empty() {
+                    continue;
+                }
+
+                tokio::select! {
+                    _ = reindex_changes(&ctx, changes) => {}
+                    _ = &mut shutdown => break,
+                }
+            }
+        }
+    }
+
+    eprintln!("[watch] shutting down");
+    Ok(())
+}
+
+async fn reindex_changes(ctx: &IndexContext<'_>, changes: ChangeSet) {
+    if let Err(e) = ensure_collection(ctx).await {
+        eprintln!("[ensure_collection] error: {e:#}");
+        return;
+    }
+    for (repo_root, files) in changes.repos {
+        match &files {
+            Some(files) => eprintln!("[watch] {repo_root:?}: {} changed file(s)", files.len()),
+            None => eprintln!("[watch] {repo_root:?}: repo changed"),
+        }
+        index_repo(ctx, &repo_root, files.as_ref()).await;
+    }
+    link_across_repos(ctx).await;
+}
+
+/// Resolves once SIGINT (Ctrl-C) or, on unix, SIGTERM is received.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            eprintln!("[watch] cannot listen for Ctrl-C: {e}");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{SignalKind, signal};
+        match signal(SignalKind::terminate()) {
+            Ok(mut s) => {
+                s.recv().await;
+            }
+            Err(e) => {
+                eprintln!("[watch] cannot listen for SIGTERM: {e}");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+/// Changed paths grouped by repo root. `None` means "reindex the whole repo"
+/// (the repo directory itself was created/removed/renamed).
+#[derive(Debug, Default)]
+struct ChangeSet {
+    repos: HashMap<PathBuf, Option<HashSet<String>>>,
+}
+
+impl ChangeSet {
+    fn is_empty(&self) -> bool {
+        self.repos.is_empty()
+    }
+
+    fn record(&mut self, root: &Path, event: notify::Result<Event>) {
+        let event = match event {
+            Ok(ev) => ev,
+            Err(e) => {
+                eprintln!("[watch] notify error: {e}");
+                return;
+            }
+        };
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+        for path in &event.paths {
+            self.record_path(root, path);
+        }
+    }
+
+    fn record_path(&mut self, root: &Path, path: &Path) {
+        let Ok(rel) = path.strip_prefix(root) else {
+            return;
+        };
+        let mut comps = rel.components();
+        let Some(Component::Normal(repo)) = comps.next() else {
+            return;
+        };
+        let repo_root = root.join(repo);
+        let rest = comps.as_path();
+
+        // VCS internals churn constantly and are never indexed
+        if rest.components().any(|c| c.as_os_str() == ".git") {
+            return;
+        }
+
+        if rest.as_os_str().is_empty() {
+            // top-level files are not part of any repo; a removed one no
+            // longer exists, so only an existing directory counts as a repo
+            if !path.is_dir() {
+                return;
+            }
+            self.repos.insert(repo_root, None);
+            return;
+        }
+
+        // `None` means the whole repo is already scheduled
+        if let Some(files) = self
+            .repos
+            .entry(repo_root)
+            .or_insert_with(|| Some(HashSet::new()))
+        {
+            files.insert(rest.to_string_lossy().to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_changed_files_by_repo() {
+        let root = Path::new("/proj");
+        let mut cs = ChangeSet::default();
+        cs.record_path(root, Path::new("/proj/billing/src/lib.rs"));
+        cs.record_path(root, Path::new("/proj/billing/src/api.rs"));
+        cs.record_path(root, Path::new("/proj/orders/src/main.rs"));
+        cs.record_path(root, Path::new("/proj/orders/.git/index"));
+        cs.record_path(root, Path::new("/elsewhere/x.rs"));
+
+        let billing = cs.repos[Path::new("/proj/billing")].as_ref().unwrap();
+        assert_eq!(billing.len(), 2);
+        assert!(
+            billing.contains(
+                &Path::new("src")
+                    .join("lib.rs")
+                    .to_string_lossy()
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            cs.repos[Path::new("/proj/orders")].as_ref().unwrap().len(),
+            1
+        );
+        assert_eq!(cs.repos.len(), 2);
+    }
+
+    #[test]
+    fn repo_dir_event_schedules_whole_repo() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir(root.join("payments")).unwrap();
+        let mut cs = ChangeSet::default();
+        cs.record_path(root, &root.join("payments/src/lib.rs"));
+        cs.record_path(root, &root.join("payments"));
+        cs.record_path(root, &root.join("payments/src/other.rs"));
+        assert!(cs.repos[&root.join("payments")].is_none());
+
+        // a removed top-level file is not a repo
+        cs.record_path(root, &root.join("README.md"));
+        assert_eq!(cs.repos.len(), 1);
+    }
+}